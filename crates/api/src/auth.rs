@@ -0,0 +1,75 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::header::{HeaderMap, AUTHORIZATION},
+    middleware::Next,
+    web, Error, FromRequest, HttpMessage, HttpRequest,
+};
+use chrono::Utc;
+use domain::hash_api_key;
+use store::{db::PgPool, repositories::user_repository::ApiKeyRepo};
+use uuid::Uuid;
+
+use crate::errors::ApiErrorResponse;
+
+/// Header read when no `Authorization: Bearer` header is sent.
+const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// The caller resolved by [`authenticate`]. Handlers that take it reject anonymous requests.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub id: Uuid,
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ApiErrorResponse;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            req.extensions()
+                .get::<AuthenticatedUser>()
+                .cloned()
+                .ok_or_else(|| ApiErrorResponse::unauthorized("Missing API key")),
+        )
+    }
+}
+
+/// Resolves the API key of the request to its user. Requests without a key pass through
+/// anonymously (market data stays public); requests with an unknown or revoked key are rejected.
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if let Some(key) = api_key(req.headers()) {
+        let key_hash = hash_api_key(&key);
+        let db_pool = req.app_data::<web::Data<PgPool>>()
+            .ok_or_else(|| ApiErrorResponse::internal("Database pool is not configured"))?;
+        let mut conn = db_pool.get()
+            .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+        let (key, _) = ApiKeyRepo::user_for_hash(&mut conn, &key_hash)
+            .await
+            .map_err(|_| ApiErrorResponse::internal("Cannot fetch API key from database"))?
+            .ok_or_else(|| ApiErrorResponse::unauthorized("Invalid API key"))?;
+        ApiKeyRepo::touch(&mut conn, key.id, Utc::now().naive_utc())
+            .await
+            .map_err(|_| ApiErrorResponse::internal("Cannot update API key usage"))?;
+
+        req.extensions_mut().insert(AuthenticatedUser { id: key.user_id });
+    }
+
+    next.call(req).await
+}
+
+fn api_key(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let header = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok());
+
+    bearer.or(header).map(|key| key.trim().to_string()).filter(|key| !key.is_empty())
+}
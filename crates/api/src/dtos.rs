@@ -1,15 +1,19 @@
 use std::{collections::HashSet, str::FromStr};
 
-use domain::MarketSymbol;
+use domain::{Account, AllocationTarget, ApiKey, AssetPnl, ConversionError, CorrelationMatrix, Currency, CurrencyConverter, DcaPlan, DcaSimulation, MacroRegime, MarketSymbol, NavPoint, PerformanceReport, PlannedBuy, Portfolio, RebalancePlan, SignalExplanation};
+use importer::RowError;
 use serde::Serialize;
-use chrono::NaiveDate;
-use store::models::{market_data_db::MarketDataDB, market_metrics_db::MarketMetricDataDB};
+use chrono::{NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+use store::models::{market_data_db::MarketDataDB, market_metrics_db::MarketMetricDataDB, signal_db::StrategySignalDB};
 
 #[derive(Serialize)]
 pub struct DashboardResponse {
+    pub currency: Currency,
     pub snapshots: Vec<AssetSnapshot>,
     pub fear_greed: FearGreedIndex,
     pub macro_metrics: Option<MacroMetrics>,
+    pub macro_regime: Option<MacroRegimeSummary>,
 }
 
 #[derive(Serialize)]
@@ -22,16 +26,21 @@ pub struct AssetSnapshot {
 }
 
 impl AssetSnapshot {
-    pub fn from_market_data(symbol: MarketSymbol, market_data: Vec<MarketDataDB>, market_metric: Vec<MarketMetricDataDB>) -> Self {
-        Self {
+    pub fn from_market_data(
+        symbol: MarketSymbol,
+        market_data: Vec<MarketDataDB>,
+        market_metric: Vec<MarketMetricDataDB>,
+        converter: &CurrencyConverter,
+    ) -> Result<Self, ConversionError> {
+        Ok(Self {
             symbol: symbol.as_str().to_string(),
             formatted_name: symbol.formatted_name().to_string(),
-            prices: PriceData::from_market_data(market_data),
-            metrics: Self::group_metrics(market_metric),
-        }
+            prices: PriceData::from_market_data(market_data, converter)?,
+            metrics: Self::group_metrics(market_metric, converter)?,
+        })
     }
 
-    fn group_metrics(metrics: Vec<MarketMetricDataDB>) -> Vec<MetricData> {
+    fn group_metrics(metrics: Vec<MarketMetricDataDB>, converter: &CurrencyConverter) -> Result<Vec<MetricData>, ConversionError> {
         let mut grouped: std::collections::HashMap<String, Vec<MarketMetricDataDB>> = std::collections::HashMap::new();
         
         for metric in metrics {
@@ -39,7 +48,7 @@ impl AssetSnapshot {
         }
         
         grouped.into_iter().map(|(name, data)| {
-            MetricData::from_market_metrics(name, data)
+            MetricData::from_market_metrics(name, data, converter)
         }).collect()
     }
 }
@@ -51,8 +60,8 @@ pub struct PriceData {
 }
 
 impl PriceData {
-    pub fn from_market_data(market_data: Vec<MarketDataDB>) -> Self {
-        Self {
+    pub fn from_market_data(market_data: Vec<MarketDataDB>, converter: &CurrencyConverter) -> Result<Self, ConversionError> {
+        Ok(Self {
             data: market_data
                 .into_iter()
                 .map(|md| {
@@ -60,10 +69,11 @@ impl PriceData {
                         .unwrap()
                         .and_utc()
                         .timestamp_millis();
-                    (timestamp, md.price_usd, md.volume_usd)
+                    let volume = md.volume_usd.map(|v| converter.convert(v, md.timestamp)).transpose()?;
+                    Ok((timestamp, converter.convert(md.price_usd, md.timestamp)?, volume))
                 })
-                .collect(),
-        }
+                .collect::<Result<_, ConversionError>>()?,
+        })
     }
 }
 
@@ -77,11 +87,13 @@ pub struct MetricData {
 }
 
 impl MetricData {
-    pub fn from_market_metrics(name: String, metrics: Vec<MarketMetricDataDB>) -> Self {
-        let formatted_name = match MarketSymbol::from_str(&name) {
-            Ok(symbol) => symbol.formatted_name().to_string(),
-            Err(_) => name.clone(),
+    pub fn from_market_metrics(name: String, metrics: Vec<MarketMetricDataDB>, converter: &CurrencyConverter) -> Result<Self, ConversionError> {
+        let symbol = MarketSymbol::from_str(&name).ok();
+        let formatted_name = match &symbol {
+            Some(symbol) => symbol.formatted_name().to_string(),
+            None => name.clone(),
         };
+        let convert = symbol.is_some_and(|s| s.is_usd_denominated());
 
         let data = metrics
             .into_iter()
@@ -90,18 +102,138 @@ impl MetricData {
                     .unwrap()
                     .and_utc()
                     .timestamp_millis();
-                (timestamp, mm.value.unwrap_or(0.0))
+                let value = mm.value.unwrap_or(0.0);
+                let value = if convert { converter.convert(value, mm.timestamp)? } else { value };
+                Ok((timestamp, value))
             })
-            .collect();
+            .collect::<Result<_, ConversionError>>()?;
 
-        Self {
+        Ok(Self {
             name,
             formatted_name,
             data,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct PortfolioNavResponse {
+    pub portfolio_id: Uuid,
+    pub currency: Currency,
+    // Array of [timestamp, nav]
+    pub data: Vec<(i64, f64)>,
+    // Timestamps whose NAV was valued with forward-filled closes
+    pub forward_filled: Vec<i64>,
+}
+
+impl PortfolioNavResponse {
+    pub fn from_nav(portfolio_id: Uuid, currency: Currency, nav: Vec<NavPoint>) -> Self {
+        let mut rows = nav;
+        rows.sort_by_key(|r| r.date);
+
+        let mut data = Vec::with_capacity(rows.len());
+        let mut forward_filled = Vec::new();
+        for row in rows {
+            let timestamp = row.date.and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+                .timestamp_millis();
+            if row.forward_filled {
+                forward_filled.push(timestamp);
+            }
+            data.push((timestamp, row.nav));
         }
+
+        Self { portfolio_id, currency, data, forward_filled }
     }
 }
 
+#[derive(Serialize)]
+pub struct PortfolioPerformanceResponse {
+    pub portfolio_id: Uuid,
+    pub currency: Currency,
+    pub periods: Vec<PerformanceReport>,
+}
+
+#[derive(Serialize)]
+pub struct PortfolioTargetsResponse {
+    pub portfolio_id: Uuid,
+    pub targets: Vec<AllocationTarget>,
+}
+
+#[derive(Serialize)]
+pub struct PortfolioRebalanceResponse {
+    pub portfolio_id: Uuid,
+    pub plan: RebalancePlan,
+}
+
+#[derive(Serialize)]
+pub struct PortfoliosResponse {
+    pub portfolios: Vec<Portfolio>,
+}
+
+#[derive(Serialize)]
+pub struct AccountsResponse {
+    pub portfolio_id: Uuid,
+    pub accounts: Vec<Account>,
+}
+
+#[derive(Serialize)]
+pub struct ApiKeysResponse {
+    pub keys: Vec<ApiKey>,
+}
+
+#[derive(Serialize)]
+pub struct IssuedApiKeyResponse {
+    pub id: Uuid,
+    pub key: String,
+    pub prefix: String,
+    pub label: Option<String>,
+}
+
+/// A stored signal with what it was computed from. `explanation` is `None` for signals stored
+/// before their inputs were recorded.
+#[derive(Serialize)]
+pub struct SignalResponse {
+    pub id: Uuid,
+    pub asset_symbol: String,
+    pub timestamp: NaiveDateTime,
+    pub signal_type: String,
+    pub value: Option<f64>,
+    pub description: Option<String>,
+    pub source: Option<String>,
+    pub explanation: Option<SignalExplanation>,
+}
+
+impl From<StrategySignalDB> for SignalResponse {
+    fn from(signal: StrategySignalDB) -> Self {
+        Self {
+            explanation: signal.inputs.and_then(|inputs| serde_json::from_value(inputs).ok()),
+            id: signal.id,
+            asset_symbol: signal.asset_symbol,
+            timestamp: signal.timestamp,
+            signal_type: signal.signal_type,
+            value: signal.value,
+            description: signal.description,
+            source: signal.source,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SignalsResponse {
+    pub signals: Vec<SignalResponse>,
+}
+
+#[derive(Serialize)]
+pub struct DcaResponse {
+    pub currency: Currency,
+    pub plan: DcaPlan,
+    pub to: NaiveDate,
+    pub simulations: Vec<DcaSimulation>,
+    pub schedule: Vec<PlannedBuy>,
+}
+
 // Compact array format: [timestamp, value, avg7d, avg14d, avg21d, classification]
 #[derive(Serialize)]
 pub struct FearGreedIndex {
@@ -158,7 +290,7 @@ pub struct MacroMetricEntry {
 }
 
 impl MacroMetrics {
-    pub fn from_market_data(entries: Vec<MarketMetricDataDB>) -> Option<Self> {
+    pub fn from_market_data(entries: Vec<MarketMetricDataDB>, converter: &CurrencyConverter) -> Result<Option<Self>, ConversionError> {
         let allowed: HashSet<String> = MarketSymbol::macro_metrics()
             .iter()
             .map(|symbol| symbol.as_str().to_owned())
@@ -173,29 +305,151 @@ impl MacroMetrics {
         }
 
         if grouped.is_empty() {
-            return None;
+            return Ok(None);
         }
 
         let data: Vec<MacroMetricEntry> = grouped
             .into_iter()
             .filter_map(|(name, metrics)| {
                 MarketSymbol::from_str(&name).ok().map(|symbol| {
+                    let convert = symbol.is_usd_denominated();
                     let values = metrics
                         .into_iter()
                         .filter_map(|m| {
-                            m.value.map(|v| (m.timestamp, v, m.source.clone()))
+                            m.value.map(|v| {
+                                let v = if convert { converter.convert(v, m.timestamp)? } else { v };
+                                Ok((m.timestamp, v, m.source.clone()))
+                            })
                         })
-                        .collect();
+                        .collect::<Result<_, ConversionError>>()?;
 
-                    MacroMetricEntry {
+                    Ok(MacroMetricEntry {
                         name: name.clone(),
                         formatted_name: symbol.formatted_name().to_string(),
                         values,
-                    }
+                    })
                 })
             })
-            .collect();
+            .collect::<Result<_, ConversionError>>()?;
+
+        Ok(if data.is_empty() { None } else { Some(Self { data }) })
+    }
+}
+
+// The conclusion of the macro panel: the latest stored regime and what it is made of
+#[derive(Serialize)]
+pub struct MacroRegimeSummary {
+    pub date: NaiveDate,
+    pub regime: MacroRegime,
+    #[serde(rename = "formattedName")]
+    pub formatted_name: String,
+    /// First day of the current run of this regime within the loaded history
+    pub since: NaiveDate,
+    pub score: Option<f64>,
+    pub sub_scores: Vec<MacroSubScoreEntry>,
+}
+
+#[derive(Serialize)]
+pub struct MacroSubScoreEntry {
+    pub name: String,
+    #[serde(rename = "formattedName")]
+    pub formatted_name: String,
+    pub value: f64,
+}
 
-        if data.is_empty() { None } else { Some(Self { data }) }
+impl MacroRegimeSummary {
+    /// `regimes` are stored regime codes, newest first; `scores` the latest score and sub-scores.
+    pub fn from_market_data(regimes: Vec<MarketMetricDataDB>, scores: Vec<MarketMetricDataDB>) -> Option<Self> {
+        let mut run = regimes
+            .iter()
+            .filter_map(|m| Some((m.timestamp, MacroRegime::from_code(m.value?)?)));
+        let (date, regime) = run.next()?;
+        let since = run.take_while(|(_, r)| *r == regime).last().map_or(date, |(d, _)| d);
+
+        let scores: Vec<MarketMetricDataDB> = scores.into_iter().filter(|m| m.timestamp == date).collect();
+        let value_of = |symbol: &MarketSymbol| scores.iter().find(|m| m.name == symbol.as_str()).and_then(|m| m.value);
+
+        Some(Self {
+            date,
+            regime,
+            formatted_name: regime.formatted_name().to_string(),
+            since,
+            score: value_of(&MarketSymbol::MacroRegimeScore),
+            sub_scores: MarketSymbol::macro_regime_metrics()
+                .iter()
+                .skip(2) // the regime and its score come first
+                .filter_map(|symbol| {
+                    value_of(symbol).map(|value| MacroSubScoreEntry {
+                        name: symbol.as_str().to_string(),
+                        formatted_name: symbol.formatted_name().to_string(),
+                        value,
+                    })
+                })
+                .collect(),
+        })
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct PortfolioPnlResponse {
+    pub portfolio_id: Uuid,
+    pub cost_basis_method: String,
+    pub currency: Currency,
+    pub assets: Vec<AssetPnl>,
+}
+
+#[derive(Serialize)]
+pub struct ImportResponse {
+    pub source: String,
+    pub rows_read: usize,
+    pub rows_skipped: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub errors: Vec<RowError>,
+}
+
+#[derive(Serialize)]
+pub struct CorrelationMatrixResponse {
+    #[serde(flatten)]
+    pub matrix: CorrelationMatrix,
+    #[serde(rename = "formattedNames")]
+    pub formatted_names: Vec<String>,
+}
+
+impl CorrelationMatrixResponse {
+    pub fn new(matrix: CorrelationMatrix, symbols: &[MarketSymbol]) -> Self {
+        Self { matrix, formatted_names: symbols.iter().map(|s| s.formatted_name().to_string()).collect() }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CorrelationPairsResponse {
+    pub window_days: i64,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub pairs: Vec<CorrelationPair>,
+}
+
+// Compact array format like the metrics: [timestamp, correlation]
+#[derive(Serialize)]
+pub struct CorrelationPair {
+    pub a: String,
+    pub b: String,
+    #[serde(rename = "formattedName")]
+    pub formatted_name: String,
+    pub data: Vec<(i64, f64)>,
+}
+
+impl CorrelationPair {
+    pub fn new(a: &MarketSymbol, b: &MarketSymbol, series: Vec<(NaiveDate, f64)>) -> Self {
+        Self {
+            a: a.as_str().to_string(),
+            b: b.as_str().to_string(),
+            formatted_name: format!("{} vs {}", a.formatted_name(), b.formatted_name()),
+            data: series
+                .into_iter()
+                .map(|(date, value)| (date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis(), value))
+                .collect(),
+        }
+    }
+}
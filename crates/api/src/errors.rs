@@ -16,7 +16,10 @@ impl fmt::Display for ApiErrorResponse {
 
 impl ResponseError for ApiErrorResponse {
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::BadRequest().json(self)
+        match self.error.as_str() {
+            "UNAUTHORIZED" => HttpResponse::Unauthorized().json(self),
+            _ => HttpResponse::BadRequest().json(self),
+        }
     }
 }
 
@@ -28,6 +31,13 @@ impl ApiErrorResponse {
         }
     }
 
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        ApiErrorResponse {
+            error: "UNAUTHORIZED".into(),
+            message: message.into(),
+        }
+    }
+
     pub fn internal(message: impl Into<String>) -> Self {
         ApiErrorResponse {
             error: "INTERNAL_ERROR".into(),
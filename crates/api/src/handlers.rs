@@ -1,14 +1,20 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
-use actix_web::{get, HttpResponse};
-use domain::MarketSymbol;
+use actix_web::{delete, get, post, HttpResponse};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use domain::{Account, AllocationBucket, AllocationTarget, ApiKey, CostBasisMethod, Currency, CurrencyConverter, DcaFrequency, DcaMethod, DcaPlan, MarketDataSet, MayerMultiple, CORRELATION_SERIES, MIN_CORRELATION_OBSERVATIONS, compare_dca, correlation_matrix, dca_schedule, rolling_correlation, GeneratedApiKey, Jurisdiction, LedgerTransaction, LotBook, MarketSymbol, MAX_ACCOUNT_NAME_LEN, MAX_ACCOUNT_VENUE_LEN, MAX_ASSET_SYMBOL_LEN, normalize_symbol, NavPoint, PerformancePeriod, Portfolio, PortfolioState, TransactionKind, TaxReport, performance, price_symbol, rebalance, validate_targets, value_income};
+use importer::{ImportFormat, ImportReport, RowError};
 use serde::Deserialize;
-use store::{db::PgPool, repositories::{market_data_repository::MarketDataRepo, market_metrics_repository::MarketMetricRepo}};
-use crate::{dtos::*, errors::ApiErrorResponse};
+use store::{db::{PgPool, PgPooledConnection}, models::{account_db::AccountDB, ledger_transaction_db::LedgerTransactionDB, portfolio_db::PortfolioDB, portfolio_target_db::PortfolioTargetDB, user_db::ApiKeyDB}, repositories::{account_repository::AccountRepo, ledger_repository::LedgerRepo, market_data_repository::MarketDataRepo, market_metrics_repository::MarketMetricRepo, portfolio_nav_repository::PortfolioNavRepo, portfolio_repository::PortfolioRepo, portfolio_target_repository::PortfolioTargetRepo, signal_repository::SignalsRepo, user_repository::ApiKeyRepo}};
+use uuid::Uuid;
+use crate::{auth::AuthenticatedUser, dtos::*, errors::ApiErrorResponse};
 use actix_web::{web, Result};
 
 #[get("/api/dashboard")]
-async fn btc_dashboard(db_pool: web::Data<PgPool>) -> Result<HttpResponse> {
+async fn btc_dashboard(
+    db_pool: web::Data<PgPool>,
+    query: web::Query<CurrencyQuery>,
+) -> Result<HttpResponse> {
     let mut conn = db_pool.get()
         .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database!"))?;
 
@@ -20,19 +26,132 @@ async fn btc_dashboard(db_pool: web::Data<PgPool>) -> Result<HttpResponse> {
 
     let latest_macro_metrics = MarketMetricRepo::latest_array_metrics(&mut conn, &MarketSymbol::macro_metrics()).await.unwrap();
     let latest_fg = MarketMetricRepo::latest_n(&mut conn, MarketSymbol::FearGreedIndex, 378).await.unwrap();
+    let latest_regimes = MarketMetricRepo::latest_n(&mut conn, MarketSymbol::MacroRegime, 365)
+        .await
+        .map_err(|_| ApiErrorResponse::internal("Cannot fetch macro regimes from database"))?;
+    let latest_regime_scores = MarketMetricRepo::latest_array_metrics(&mut conn, &MarketSymbol::macro_regime_metrics())
+        .await
+        .map_err(|_| ApiErrorResponse::internal("Cannot fetch macro regime scores from database"))?;
+
+    let dates = latest_btc_values.iter().chain(&latest_eth_values).map(|md| md.timestamp)
+        .chain(latest_btc_metrics.iter().chain(&latest_eth_metrics).chain(&latest_macro_metrics).map(|mm| mm.timestamp));
+    let (from, to) = dates.fold((NaiveDate::MAX, NaiveDate::MIN), |(lo, hi), d| (lo.min(d), hi.max(d)));
+    let converter = load_converter(&mut conn, query.currency.as_deref(), from, to).await?;
+    let converted = |e: domain::ConversionError| ApiErrorResponse::bad_request(format!("Cannot convert dashboard: {}", e));
 
     let response = DashboardResponse { 
+        currency: converter.currency(),
         snapshots: vec![
-            AssetSnapshot::from_market_data(MarketSymbol::BtcUsd, latest_btc_values, latest_btc_metrics), 
-            AssetSnapshot::from_market_data(MarketSymbol::EthUsd, latest_eth_values, latest_eth_metrics)
+            AssetSnapshot::from_market_data(MarketSymbol::BtcUsd, latest_btc_values, latest_btc_metrics, &converter).map_err(converted)?, 
+            AssetSnapshot::from_market_data(MarketSymbol::EthUsd, latest_eth_values, latest_eth_metrics, &converter).map_err(converted)?
         ],
         fear_greed: FearGreedIndex::from_market_data(latest_fg),
-        macro_metrics: MacroMetrics::from_market_data(latest_macro_metrics), 
+        macro_metrics: MacroMetrics::from_market_data(latest_macro_metrics, &converter).map_err(converted)?, 
+        macro_regime: MacroRegimeSummary::from_market_data(latest_regimes, latest_regime_scores),
     };
 
     Ok(HttpResponse::Ok().json(response))
 }
 
+#[derive(Deserialize)]
+pub struct CurrencyQuery {
+    pub currency: Option<String>,
+}
+
+#[get("/api/portfolio/nav")]
+async fn portfolio_nav(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<PortfolioNavQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    load_portfolio(&mut conn, &user, query.portfolio_id).await?;
+
+    let days = query.days.unwrap_or(365);
+    let nav: Vec<NavPoint> = PortfolioNavRepo::latest_n(&mut conn, query.portfolio_id, days)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch NAV of portfolio {} for the last {} days", query.portfolio_id, days)))?
+        .into_iter()
+        .map(NavPoint::from)
+        .collect();
+
+    let converter = load_nav_converter(&mut conn, query.currency.as_deref(), &nav).await?;
+    let nav = converter.convert_nav(&nav)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert NAV of portfolio {}: {}", query.portfolio_id, e)))?;
+
+    Ok(HttpResponse::Ok().json(PortfolioNavResponse::from_nav(query.portfolio_id, converter.currency(), nav)))
+}
+
+#[derive(Deserialize)]
+pub struct PortfolioNavQuery {
+    pub portfolio_id: Uuid,
+    pub days: Option<i64>,
+    pub currency: Option<String>,
+}
+
+/// Returns and risk of a portfolio for one period, or for all of them when `period` is omitted.
+#[get("/api/portfolio/performance")]
+async fn portfolio_performance(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<PerformanceQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let periods = match &query.period {
+        Some(period) => vec![PerformancePeriod::from_str(period)
+            .map_err(|_| ApiErrorResponse::bad_request(format!("Invalid period: {}", period)))?],
+        None => PerformancePeriod::ALL.to_vec(),
+    };
+
+    load_portfolio(&mut conn, &user, query.portfolio_id).await?;
+
+    let nav: Vec<NavPoint> = PortfolioNavRepo::for_portfolio(&mut conn, query.portfolio_id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch NAV of portfolio {}", query.portfolio_id)))?
+        .into_iter()
+        .map(NavPoint::from)
+        .collect();
+
+    let converter = load_nav_converter(&mut conn, query.currency.as_deref(), &nav).await?;
+    let nav = converter.convert_nav(&nav)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert NAV of portfolio {}: {}", query.portfolio_id, e)))?;
+
+    let risk_free = match (nav.first(), nav.last()) {
+        (Some(first), Some(last)) => {
+            MarketMetricRepo::range(&mut conn, MarketSymbol::DFF.as_str(), first.date - Duration::days(7), last.date)
+                .await
+                .map_err(|_| ApiErrorResponse::internal("Cannot fetch DFF from database"))?
+                .into_iter()
+                .filter_map(|row| row.value.map(|v| (row.timestamp, v)))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let today = Utc::now().date_naive();
+    let reports = periods
+        .into_iter()
+        .filter_map(|period| performance(&nav, &risk_free, period, today))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(PortfolioPerformanceResponse {
+        portfolio_id: query.portfolio_id,
+        currency: converter.currency(),
+        periods: reports,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct PerformanceQuery {
+    pub portfolio_id: Uuid,
+    pub period: Option<String>,
+    pub currency: Option<String>,
+}
+
 #[get("/api/historical")]
 async fn historical_metrics(
     db_pool: web::Data<PgPool>,
@@ -48,11 +167,949 @@ async fn historical_metrics(
         .await
         .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch {} from database for the last {} days", &query.symbol, query.days)))?;
 
-    Ok(HttpResponse::Ok().json(MacroMetrics::from_market_data(data)))
+    let converter = match (data.iter().map(|m| m.timestamp).min(), data.iter().map(|m| m.timestamp).max()) {
+        (Some(from), Some(to)) => load_converter(&mut conn, query.currency.as_deref(), from, to).await?,
+        _ => CurrencyConverter::usd(),
+    };
+    let metrics = MacroMetrics::from_market_data(data, &converter)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert {}: {}", query.symbol, e)))?;
+
+    Ok(HttpResponse::Ok().json(metrics))
 }
 
+/// `currency` only applies to USD-denominated series; rates, ratios and indices are returned as stored.
 #[derive(Deserialize)]
 pub struct HistoricalMetricsQuery {
     pub symbol: String,
     pub days: i64,
-}
\ No newline at end of file
+    pub currency: Option<String>,
+}
+
+/// Return correlations between the `market_data` price series over the `window` days up to `at`.
+#[get("/api/correlations")]
+async fn correlations(
+    db_pool: web::Data<PgPool>,
+    query: web::Query<CorrelationsQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let window = correlation_window(query.window)?;
+    let at = query.at.unwrap_or_else(|| Utc::now().date_naive()).min(Utc::now().date_naive());
+    let symbols = match &query.symbols {
+        Some(symbols) => symbols.split(',').map(price_series).collect::<Result<Vec<_>, _>>()?,
+        None => CORRELATION_SERIES.to_vec(),
+    };
+
+    // A few extra days so the first return of the window has the close before it.
+    let data = MarketMetricRepo::market_data_set(&mut conn, &symbols, at - Duration::days(window + 7), at)
+        .await
+        .map_err(|_| ApiErrorResponse::internal("Cannot fetch prices from database"))?;
+
+    let matrix = correlation_matrix(&data.at(at), &symbols, window);
+    Ok(HttpResponse::Ok().json(CorrelationMatrixResponse::new(matrix, &symbols)))
+}
+
+#[derive(Deserialize)]
+pub struct CorrelationsQuery {
+    /// Days of returns, 90 by default
+    pub window: Option<i64>,
+    /// Today by default
+    pub at: Option<NaiveDate>,
+    /// Comma-separated price series, all of them by default
+    pub symbols: Option<String>,
+}
+
+/// Rolling correlation of selected pairs, e.g. `pairs=BTC_USD:NASDAQ_USD,BTC_USD:GOLD_USD`, on
+/// every day from `from` to `to`.
+#[get("/api/correlations/pairs")]
+async fn correlation_pairs(
+    db_pool: web::Data<PgPool>,
+    query: web::Query<CorrelationPairsQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let window = correlation_window(query.window)?;
+    let today = Utc::now().date_naive();
+    let to = query.to.unwrap_or(today).min(today);
+    let from = query.from.unwrap_or(to - Duration::days(365));
+    if from > to {
+        return Err(ApiErrorResponse::bad_request(format!("from {} is after to {}", from, to)).into());
+    }
+
+    let pairs = query.pairs.as_deref().unwrap_or(DEFAULT_CORRELATION_PAIRS)
+        .split(',')
+        .map(|pair| match pair.split_once(':') {
+            Some((a, b)) => Ok((price_series(a)?, price_series(b)?)),
+            None => Err(ApiErrorResponse::bad_request(format!("Invalid pair {}, expected A:B", pair))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let symbols: Vec<MarketSymbol> = pairs.iter().flat_map(|(a, b)| [a.clone(), b.clone()]).collect();
+
+    let data = MarketMetricRepo::market_data_set(&mut conn, &symbols, from - Duration::days(window + 7), to)
+        .await
+        .map_err(|_| ApiErrorResponse::internal("Cannot fetch prices from database"))?;
+    let ctx = data.at(to);
+
+    Ok(HttpResponse::Ok().json(CorrelationPairsResponse {
+        window_days: window,
+        from,
+        to,
+        pairs: pairs
+            .iter()
+            .map(|(a, b)| CorrelationPair::new(a, b, rolling_correlation(ctx.series(a), ctx.series(b), from, window)))
+            .collect(),
+    }))
+}
+
+/// Whether BTC trades like a tech stock, a hedge or neither.
+const DEFAULT_CORRELATION_PAIRS: &str = "BTC_USD:NASDAQ_USD,BTC_USD:SP500_USD,BTC_USD:GOLD_USD,BTC_USD:USD_INDEX_USD";
+const DEFAULT_CORRELATION_WINDOW: i64 = 90;
+const MAX_CORRELATION_WINDOW: i64 = 5 * 365;
+
+#[derive(Deserialize)]
+pub struct CorrelationPairsQuery {
+    /// Comma-separated `A:B` pairs of price series
+    pub pairs: Option<String>,
+    pub window: Option<i64>,
+    /// A year before `to` by default
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+fn correlation_window(window: Option<i64>) -> Result<i64, ApiErrorResponse> {
+    let window = window.unwrap_or(DEFAULT_CORRELATION_WINDOW);
+    if !(MIN_CORRELATION_OBSERVATIONS as i64..=MAX_CORRELATION_WINDOW).contains(&window) {
+        return Err(ApiErrorResponse::bad_request(format!(
+            "window must be between {} and {} days",
+            MIN_CORRELATION_OBSERVATIONS, MAX_CORRELATION_WINDOW
+        )));
+    }
+    Ok(window)
+}
+
+/// One of the `market_data` series, e.g. `BTC_USD` or `NASDAQ_USD`.
+fn price_series(name: &str) -> Result<MarketSymbol, ApiErrorResponse> {
+    MarketSymbol::from_str(name.trim())
+        .ok()
+        .filter(|s| s.is_price_series())
+        .ok_or_else(|| ApiErrorResponse::bad_request(format!("Not a price series: {}", name.trim())))
+}
+
+/// Most recent strategy signals, newest first, with the inputs and thresholds behind each one.
+#[get("/api/signals")]
+async fn list_signals(
+    db_pool: web::Data<PgPool>,
+    query: web::Query<SignalsQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let limit = query.limit.unwrap_or(50);
+    if !(1..=MAX_SIGNALS).contains(&limit) {
+        return Err(ApiErrorResponse::bad_request(format!("limit must be between 1 and {}", MAX_SIGNALS)).into());
+    }
+    let asset = query.asset.as_deref().map(str::to_uppercase);
+
+    let signals = SignalsRepo::latest_n(&mut conn, asset.as_deref(), limit)
+        .map_err(|_| ApiErrorResponse::internal("Cannot fetch signals from database"))?
+        .into_iter()
+        .map(SignalResponse::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(SignalsResponse { signals }))
+}
+
+const MAX_SIGNALS: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct SignalsQuery {
+    pub asset: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// One signal with its explanation: the exact input values, thresholds and rule version that
+/// produced it.
+#[get("/api/signals/{id}")]
+async fn get_signal(
+    db_pool: web::Data<PgPool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let id = path.into_inner();
+    let signal = SignalsRepo::get_by_id(&mut conn, id)
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch signal {}", id)))?
+        .ok_or_else(|| ApiErrorResponse::bad_request(format!("Unknown signal: {}", id)))?;
+
+    Ok(HttpResponse::Ok().json(SignalResponse::from(signal)))
+}
+
+/// Historical DCA outcome of every method for one plan, and the next planned buys of `method`,
+/// replayed against closes converted at each day's FX rate.
+#[get("/api/dca")]
+async fn dca(
+    db_pool: web::Data<PgPool>,
+    query: web::Query<DcaQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let asset = query.asset.as_deref().unwrap_or("BTC").trim().to_uppercase();
+    if price_symbol(&asset).is_none() {
+        return Err(ApiErrorResponse::bad_request(format!("No USD price series for {}", asset)).into());
+    }
+    if !(query.amount.is_finite() && query.amount > 0.0) {
+        return Err(ApiErrorResponse::bad_request("amount must be positive").into());
+    }
+    let frequency = match &query.frequency {
+        Some(frequency) => DcaFrequency::from_str(frequency).map_err(|e| ApiErrorResponse::bad_request(e.to_string()))?,
+        None => DcaFrequency::default(),
+    };
+    let method = match &query.method {
+        Some(method) => DcaMethod::from_str(method).map_err(|e| ApiErrorResponse::bad_request(e.to_string()))?,
+        None => DcaMethod::default(),
+    };
+    let today = Utc::now().date_naive();
+    let to = query.to.unwrap_or(today).min(today);
+    if query.from > to {
+        return Err(ApiErrorResponse::bad_request(format!("from {} is after to {}", query.from, to)).into());
+    }
+
+    // The Mayer multiple needs 200 closes before the first buy.
+    let warm_up = query.from - Duration::days(MayerMultiple::WINDOW_DAYS as i64 + 60);
+    let converter = load_converter(&mut conn, query.currency.as_deref(), warm_up, today).await?;
+    let prices = MarketDataRepo::price_history(&mut conn, std::slice::from_ref(&asset), warm_up, today)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch prices of {}", asset)))?
+        .into_iter()
+        .map(|(symbol, closes)| Ok((symbol, converter.convert_series(&closes)?)))
+        .collect::<Result<_, domain::ConversionError>>()
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert prices of {}: {}", asset, e)))?;
+    let data = MarketDataSet {
+        prices,
+        metrics: MarketMetricRepo::history(&mut conn, &[MarketSymbol::FearGreedIndex], warm_up, today)
+            .await
+            .map_err(|_| ApiErrorResponse::internal("Cannot fetch the Fear & Greed Index"))?,
+        indicators: Default::default(),
+    };
+
+    let plan = DcaPlan { asset, amount: query.amount, frequency, start: query.from, method };
+    let schedule = dca_schedule(&plan, &data.at(today), query.planned.unwrap_or(12).min(520));
+
+    Ok(HttpResponse::Ok().json(DcaResponse {
+        simulations: compare_dca(&plan, &data, to),
+        currency: converter.currency(),
+        to,
+        plan,
+        schedule,
+    }))
+}
+
+/// `$100 weekly into BTC since 2019` is `asset=BTC&amount=100&frequency=weekly&from=2019-01-01`.
+#[derive(Deserialize)]
+pub struct DcaQuery {
+    pub asset: Option<String>,
+    /// Per buy, in `currency`
+    pub amount: f64,
+    pub currency: Option<String>,
+    pub frequency: Option<String>,
+    pub from: NaiveDate,
+    pub to: Option<NaiveDate>,
+    /// Method of the forward schedule
+    pub method: Option<String>,
+    /// Number of planned buys, 12 by default
+    pub planned: Option<usize>,
+}
+
+/// Cost basis, realized and unrealized P&L per asset. Lots are converted at the FX rate of their
+/// acquisition, prices at the latest one.
+#[get("/api/portfolio/pnl")]
+async fn portfolio_pnl(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<PortfolioPnlQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let (portfolio, transactions) = load_portfolio_ledger(&mut conn, &user, query.portfolio_id).await?;
+
+    let book = LotBook::from_transactions(&transactions, portfolio.cost_basis_method)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Ledger of portfolio {} is inconsistent: {}", portfolio.id, e)))?;
+
+    let assets: Vec<String> = transactions.iter().map(|tx| tx.asset_symbol.clone()).collect();
+    let prices = latest_prices(&mut conn, &assets).await?;
+
+    let today = Utc::now().date_naive();
+    let first = transactions.iter().map(|tx| tx.timestamp.date()).min().unwrap_or(today);
+    let converter = load_converter(&mut conn, query.currency.as_deref(), first, today).await?;
+    let assets = book.pnl_in_currency(&prices, &converter, today)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert P&L of portfolio {}: {}", portfolio.id, e)))?;
+
+    Ok(HttpResponse::Ok().json(PortfolioPnlResponse {
+        portfolio_id: portfolio.id,
+        cost_basis_method: portfolio.cost_basis_method.as_str().to_string(),
+        currency: converter.currency(),
+        assets,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct PortfolioPnlQuery {
+    pub portfolio_id: Uuid,
+    pub currency: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PortfolioQuery {
+    pub portfolio_id: Uuid,
+}
+
+#[get("/api/portfolio/targets")]
+async fn portfolio_targets(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<PortfolioQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    load_portfolio(&mut conn, &user, query.portfolio_id).await?;
+    let targets = load_targets(&mut conn, query.portfolio_id).await?;
+
+    Ok(HttpResponse::Ok().json(PortfolioTargetsResponse {
+        portfolio_id: query.portfolio_id,
+        targets,
+    }))
+}
+
+/// Replaces the target allocation. Weights must sum to 1; `band` defaults to 5 percentage points.
+#[post("/api/portfolio/targets")]
+async fn set_portfolio_targets(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<PortfolioQuery>,
+    body: web::Json<Vec<AllocationTarget>>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let targets = body.into_inner();
+    validate_targets(&targets)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Invalid targets: {}", e)))?;
+
+    load_portfolio(&mut conn, &user, query.portfolio_id).await?;
+
+    let rows: Vec<PortfolioTargetDB> = targets
+        .iter()
+        .map(|t| PortfolioTargetDB::from_target(query.portfolio_id, t))
+        .collect();
+    PortfolioTargetRepo::replace(&mut conn, query.portfolio_id, &rows)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot store targets of portfolio {}", query.portfolio_id)))?;
+
+    Ok(HttpResponse::Ok().json(PortfolioTargetsResponse {
+        portfolio_id: query.portfolio_id,
+        targets,
+    }))
+}
+
+/// Trades that bring the portfolio back inside its target bands at the latest closes.
+#[get("/api/portfolio/rebalance")]
+async fn portfolio_rebalance(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<PortfolioRebalanceQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let (portfolio, transactions) = load_portfolio_ledger(&mut conn, &user, query.portfolio_id).await?;
+    let targets = load_targets(&mut conn, portfolio.id).await?;
+    if targets.is_empty() {
+        return Err(ApiErrorResponse::bad_request(format!("Portfolio {} has no target allocation", portfolio.id)).into());
+    }
+
+    let holdings = PortfolioState::from_transactions(&transactions)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Ledger of portfolio {} is inconsistent: {}", portfolio.id, e)))?
+        .holdings();
+    let book = LotBook::from_transactions(&transactions, portfolio.cost_basis_method)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Ledger of portfolio {} is inconsistent: {}", portfolio.id, e)))?;
+
+    let mut assets: Vec<String> = holdings.keys().cloned().collect();
+    assets.extend(targets.iter().map(|t| match &t.bucket {
+        AllocationBucket::Asset(asset) => asset.clone(),
+        AllocationBucket::Class(class) => class.default_asset().to_string(),
+    }));
+    let prices = latest_prices(&mut conn, &assets).await?;
+
+    let today = Utc::now().date_naive();
+    let plan = rebalance(&holdings, &prices, &book, &targets, portfolio.tax_jurisdiction, today)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot rebalance portfolio {}: {}", portfolio.id, e)))?;
+    let converter = load_converter(&mut conn, query.currency.as_deref(), today, today).await?;
+    let plan = plan.in_currency(&converter)
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert rebalance plan of portfolio {}: {}", portfolio.id, e)))?;
+
+    Ok(HttpResponse::Ok().json(PortfolioRebalanceResponse {
+        portfolio_id: portfolio.id,
+        plan,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct PortfolioRebalanceQuery {
+    pub portfolio_id: Uuid,
+    pub currency: Option<String>,
+}
+
+/// Realized gains of one calendar year, per matched lot, as JSON or CSV (`format=csv`).
+#[get("/api/portfolio/tax-report")]
+async fn tax_report(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<TaxReportQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let (portfolio, transactions) = load_portfolio_ledger(&mut conn, &user, query.portfolio_id).await?;
+
+    let report = TaxReport::build(
+        portfolio.id,
+        query.year,
+        portfolio.cost_basis_method,
+        portfolio.tax_jurisdiction,
+        &transactions,
+    )
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Ledger of portfolio {} is inconsistent: {}", portfolio.id, e)))?;
+
+    let report = match (report.rows.iter().map(|r| r.acquired_on).min(), report.rows.iter().map(|r| r.disposed_on).max()) {
+        (Some(from), Some(to)) => {
+            let converter = load_converter(&mut conn, query.currency.as_deref(), from, to).await?;
+            report.in_currency(&converter)
+                .map_err(|e| ApiErrorResponse::bad_request(format!("Cannot convert tax report of portfolio {}: {}", portfolio.id, e)))?
+        }
+        _ => report,
+    };
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(HttpResponse::Ok().json(report)),
+        "csv" => Ok(HttpResponse::Ok()
+            .content_type("text/csv; charset=utf-8")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"tax-report-{}-{}.csv\"", portfolio.id, query.year),
+            ))
+            .body(report.to_csv())),
+        other => Err(ApiErrorResponse::bad_request(format!("Invalid report format: {}", other)).into()),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TaxReportQuery {
+    pub portfolio_id: Uuid,
+    pub year: i32,
+    pub format: Option<String>,
+    pub currency: Option<String>,
+}
+
+/// Portfolios owned by the caller.
+#[get("/api/portfolios")]
+async fn list_portfolios(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let portfolios = PortfolioRepo::for_owner(&mut conn, user.id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch portfolios of user {}", user.id)))?
+        .into_iter()
+        .map(Portfolio::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ApiErrorResponse::internal(format!("Invalid portfolio row: {}", e)))?;
+
+    Ok(HttpResponse::Ok().json(PortfoliosResponse { portfolios }))
+}
+
+/// Creates a portfolio owned by the caller. FIFO and US rules unless given otherwise.
+#[post("/api/portfolios")]
+async fn create_portfolio(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    body: web::Json<NewPortfolio>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let cost_basis_method = match &body.cost_basis_method {
+        Some(method) => CostBasisMethod::from_str(method)
+            .map_err(|_| ApiErrorResponse::bad_request(format!("Invalid cost basis method: {}", method)))?,
+        None => CostBasisMethod::Fifo,
+    };
+    let tax_jurisdiction = match &body.tax_jurisdiction {
+        Some(jurisdiction) => Jurisdiction::from_str(jurisdiction)
+            .map_err(|_| ApiErrorResponse::bad_request(format!("Invalid tax jurisdiction: {}", jurisdiction)))?,
+        None => Jurisdiction::Us,
+    };
+
+    let rec = PortfolioDB {
+        id: Uuid::new_v4(),
+        name: body.name.clone(),
+        created_at: Utc::now().naive_utc(),
+        cost_basis_method: cost_basis_method.as_str().to_string(),
+        tax_jurisdiction: tax_jurisdiction.as_str().to_string(),
+        owner_id: Some(user.id),
+    };
+    PortfolioRepo::insert(&mut conn, &rec)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot store portfolio {}", rec.name)))?;
+
+    let portfolio = Portfolio::try_from(rec)
+        .map_err(|e| ApiErrorResponse::internal(format!("Invalid portfolio: {}", e)))?;
+    Ok(HttpResponse::Ok().json(portfolio))
+}
+
+#[derive(Deserialize)]
+pub struct NewPortfolio {
+    pub name: String,
+    pub cost_basis_method: Option<String>,
+    pub tax_jurisdiction: Option<String>,
+}
+
+/// Accounts of a portfolio of the caller, oldest first.
+#[get("/api/portfolios/{id}/accounts")]
+async fn list_accounts(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let portfolio = load_portfolio(&mut conn, &user, path.into_inner()).await?;
+    let accounts = AccountRepo::for_portfolio(&mut conn, portfolio.id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch accounts of portfolio {}", portfolio.id)))?
+        .into_iter()
+        .map(Account::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(AccountsResponse { portfolio_id: portfolio.id, accounts }))
+}
+
+/// Adds an account, e.g. an exchange or a wallet, to a portfolio of the caller.
+#[post("/api/portfolios/{id}/accounts")]
+async fn create_account(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+    body: web::Json<NewAccount>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let portfolio = load_portfolio(&mut conn, &user, path.into_inner()).await?;
+    let name = body.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_ACCOUNT_NAME_LEN {
+        return Err(ApiErrorResponse::bad_request(format!("name must have 1 to {} characters", MAX_ACCOUNT_NAME_LEN)).into());
+    }
+    let venue = body.venue.as_deref().map(str::trim).filter(|v| !v.is_empty());
+    if venue.is_some_and(|v| v.chars().count() > MAX_ACCOUNT_VENUE_LEN) {
+        return Err(ApiErrorResponse::bad_request(format!("venue must have at most {} characters", MAX_ACCOUNT_VENUE_LEN)).into());
+    }
+
+    let rec = AccountDB {
+        id: Uuid::new_v4(),
+        portfolio_id: portfolio.id,
+        name: name.to_string(),
+        venue: venue.map(str::to_string),
+        created_at: Utc::now().naive_utc(),
+    };
+    AccountRepo::insert(&mut conn, &rec)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot store account {}", rec.name)))?;
+
+    Ok(HttpResponse::Ok().json(Account::from(rec)))
+}
+
+#[derive(Deserialize)]
+pub struct NewAccount {
+    pub name: String,
+    pub venue: Option<String>,
+}
+
+/// Records a transaction entered by hand. It is rejected when the portfolio's ledger would no
+/// longer replay with it, e.g. when it sells more than the account holds at that time.
+#[post("/api/portfolio/transactions")]
+async fn create_transaction(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    body: web::Json<NewTransaction>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let account = AccountRepo::get(&mut conn, body.account_id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch account {} from database", body.account_id)))?
+        .ok_or_else(|| ApiErrorResponse::bad_request(format!("Unknown account: {}", body.account_id)))?;
+    let (portfolio, mut transactions) = load_portfolio_ledger(&mut conn, &user, account.portfolio_id)
+        .await
+        .map_err(|_| ApiErrorResponse::bad_request(format!("Unknown account: {}", body.account_id)))?;
+
+    let kind = TransactionKind::from_str(&body.kind)
+        .map_err(|_| ApiErrorResponse::bad_request(format!("Invalid transaction kind: {}", body.kind)))?;
+    let asset_symbol = normalize_symbol(&body.asset_symbol);
+    if asset_symbol.is_empty() || asset_symbol.chars().count() > MAX_ASSET_SYMBOL_LEN {
+        return Err(ApiErrorResponse::bad_request(format!("asset_symbol must have 1 to {} characters", MAX_ASSET_SYMBOL_LEN)).into());
+    }
+    if !(body.quantity.is_finite() && body.quantity > 0.0) {
+        return Err(ApiErrorResponse::bad_request("quantity must be positive").into());
+    }
+    for (field, value) in [("price_usd", body.price_usd), ("fee_usd", body.fee_usd)] {
+        if value.is_some_and(|v| !(v.is_finite() && v >= 0.0)) {
+            return Err(ApiErrorResponse::bad_request(format!("{} must not be negative", field)).into());
+        }
+    }
+    if let Some(counterparty) = body.counterparty_account_id {
+        let known = AccountRepo::get(&mut conn, counterparty)
+            .await
+            .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch account {} from database", counterparty)))?
+            .is_some_and(|other| other.portfolio_id == portfolio.id);
+        if !known {
+            return Err(ApiErrorResponse::bad_request(format!("Unknown counterparty account: {}", counterparty)).into());
+        }
+    }
+
+    let tx = LedgerTransaction {
+        id: Uuid::new_v4(),
+        account_id: account.id,
+        timestamp: body.timestamp,
+        kind,
+        asset_symbol,
+        quantity: body.quantity,
+        price_usd: body.price_usd,
+        fee_usd: body.fee_usd,
+        counterparty_account_id: body.counterparty_account_id,
+        note: body.note.clone(),
+        external_id: None,
+    };
+    transactions.push(tx.clone());
+    PortfolioState::from_transactions(&transactions)
+        .and_then(|_| LotBook::from_transactions(&transactions, portfolio.cost_basis_method))
+        .map_err(|e| ApiErrorResponse::bad_request(format!("Transaction does not fit the ledger: {}", e)))?;
+
+    reset_nav_from(&mut conn, portfolio.id, tx.timestamp.date()).await?;
+    LedgerRepo::insert(&mut conn, &LedgerTransactionDB::from(&tx))
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot store transaction {}", tx.id)))?;
+
+    Ok(HttpResponse::Ok().json(tx))
+}
+
+/// `quantity` is positive, the direction comes from `kind`. Transfers name the receiving account
+/// in `counterparty_account_id`.
+#[derive(Deserialize)]
+pub struct NewTransaction {
+    pub account_id: Uuid,
+    pub timestamp: NaiveDateTime,
+    pub kind: String,
+    pub asset_symbol: String,
+    pub quantity: f64,
+    pub price_usd: Option<f64>,
+    pub fee_usd: Option<f64>,
+    pub counterparty_account_id: Option<Uuid>,
+    pub note: Option<String>,
+}
+
+/// API keys of the caller, without the keys themselves.
+#[get("/api/keys")]
+async fn list_api_keys(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let keys = ApiKeyRepo::for_user(&mut conn, user.id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch API keys of user {}", user.id)))?
+        .into_iter()
+        .map(ApiKey::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ApiKeysResponse { keys }))
+}
+
+/// Issues another key for the caller. The key is only ever returned by this call.
+#[post("/api/keys")]
+async fn issue_api_key(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<IssueApiKeyQuery>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let generated = GeneratedApiKey::generate();
+    let rec = ApiKeyDB {
+        id: Uuid::new_v4(),
+        user_id: user.id,
+        key_hash: generated.hash,
+        prefix: generated.prefix.clone(),
+        label: query.label.clone(),
+        created_at: Utc::now().naive_utc(),
+        last_used_at: None,
+        revoked_at: None,
+    };
+    ApiKeyRepo::insert(&mut conn, &rec)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot store API key for user {}", user.id)))?;
+
+    Ok(HttpResponse::Ok().json(IssuedApiKeyResponse {
+        id: rec.id,
+        key: generated.key,
+        prefix: generated.prefix,
+        label: rec.label,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct IssueApiKeyQuery {
+    pub label: Option<String>,
+}
+
+#[delete("/api/keys/{id}")]
+async fn revoke_api_key(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let id = path.into_inner();
+    let revoked = ApiKeyRepo::revoke(&mut conn, id, user.id, Utc::now().naive_utc())
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot revoke API key {}", id)))?;
+    if revoked == 0 {
+        return Err(ApiErrorResponse::bad_request(format!("Unknown API key: {}", id)).into());
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Imports an exchange CSV export (request body) into an account. Valid rows are stored,
+/// invalid ones and those the account cannot cover come back in `errors`; rows imported before
+/// are counted as duplicates.
+#[post("/api/portfolio/import")]
+async fn import_transactions(
+    db_pool: web::Data<PgPool>,
+    user: AuthenticatedUser,
+    query: web::Query<ImportQuery>,
+    body: String,
+) -> Result<HttpResponse> {
+    let mut conn = db_pool.get()
+        .map_err(|_| ApiErrorResponse::internal("Cannot use the connection with the database"))?;
+
+    let format = ImportFormat::from_str(&query.format)
+        .map_err(|_| ApiErrorResponse::bad_request(format!("Invalid import format: {}", query.format)))?;
+
+    let account = AccountRepo::get(&mut conn, query.account_id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch account {} from database", query.account_id)))?
+        .ok_or_else(|| ApiErrorResponse::bad_request(format!("Unknown account: {}", query.account_id)))?;
+    let (portfolio, ledger) = load_portfolio_ledger(&mut conn, &user, account.portfolio_id)
+        .await
+        .map_err(|_| ApiErrorResponse::bad_request(format!("Unknown account: {}", query.account_id)))?;
+
+    let mut report = importer::import(format, query.account_id, &body);
+    value_imported_income(&mut conn, &mut report).await?;
+    report.reject_overdrafts(&ledger, portfolio.cost_basis_method);
+
+    let recs: Vec<LedgerTransactionDB> = report.transactions.iter().map(LedgerTransactionDB::from).collect();
+    if let Some(from) = report.transactions.iter().map(|tx| tx.timestamp.date()).min() {
+        reset_nav_from(&mut conn, portfolio.id, from).await?;
+    }
+    let imported = LedgerRepo::insert_imported(&mut conn, &recs)
+        .await
+        .map_err(|_| ApiErrorResponse::internal("Cannot store imported transactions"))?;
+
+    Ok(HttpResponse::Ok().json(ImportResponse {
+        source: report.source,
+        rows_read: report.rows_read,
+        rows_skipped: report.rows_skipped,
+        imported,
+        duplicates: report.transactions.len() - imported,
+        errors: report.errors,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    pub account_id: Uuid,
+    pub format: String,
+}
+
+/// Values imported income at the `market_data` close of its receipt date. Income that cannot be
+/// valued is reported as a row error instead of being stored without a cost basis.
+async fn value_imported_income(
+    conn: &mut PgPooledConnection,
+    report: &mut ImportReport,
+) -> Result<(), ApiErrorResponse> {
+    let income: Vec<&LedgerTransaction> = report.transactions.iter().filter(|tx| tx.kind.is_income()).collect();
+    let (Some(from), Some(to)) = (
+        income.iter().map(|tx| tx.timestamp.date()).min(),
+        income.iter().map(|tx| tx.timestamp.date()).max(),
+    ) else {
+        return Ok(());
+    };
+    let assets: Vec<String> = income.iter().map(|tx| tx.asset_symbol.clone()).collect();
+
+    let prices = MarketDataRepo::price_history(conn, &assets, from - Duration::days(7), to)
+        .await
+        .map_err(|_| ApiErrorResponse::internal("Cannot fetch prices to value imported income"))?;
+
+    let unvalued = value_income(&mut report.transactions, &prices);
+    let errors: Vec<RowError> = report.transactions
+        .iter()
+        .filter(|tx| unvalued.contains(&tx.id))
+        .map(|tx| RowError {
+            line: report.line_of(tx.id),
+            field: Some("price".to_string()),
+            message: format!(
+                "No {} price on or before {} to value {} {:?}",
+                tx.asset_symbol,
+                tx.timestamp.date(),
+                tx.kind.as_str(),
+                tx.external_id,
+            ),
+        })
+        .collect();
+    report.errors.extend(errors);
+    report.transactions.retain(|tx| !unvalued.contains(&tx.id));
+
+    Ok(())
+}
+
+/// Drops the stored NAV from `from` onwards ahead of a ledger write, so the NAV job recomputes it.
+async fn reset_nav_from(conn: &mut PgPooledConnection, portfolio_id: Uuid, from: NaiveDate) -> Result<(), ApiErrorResponse> {
+    PortfolioNavRepo::delete_from(conn, portfolio_id, from)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot reset NAV of portfolio {}", portfolio_id)))?;
+    Ok(())
+}
+
+/// Portfolio of the caller. Portfolios of other users are reported as unknown, like missing ones.
+async fn load_portfolio(
+    conn: &mut PgPooledConnection,
+    user: &AuthenticatedUser,
+    portfolio_id: Uuid,
+) -> Result<Portfolio, ApiErrorResponse> {
+    PortfolioRepo::get_owned(conn, portfolio_id, user.id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch portfolio {} from database", portfolio_id)))?
+        .ok_or_else(|| ApiErrorResponse::bad_request(format!("Unknown portfolio: {}", portfolio_id)))?
+        .try_into()
+        .map_err(|e| ApiErrorResponse::internal(format!("Invalid portfolio {}: {}", portfolio_id, e)))
+}
+
+async fn load_portfolio_ledger(
+    conn: &mut PgPooledConnection,
+    user: &AuthenticatedUser,
+    portfolio_id: Uuid,
+) -> Result<(Portfolio, Vec<LedgerTransaction>), ApiErrorResponse> {
+    let portfolio = load_portfolio(conn, user, portfolio_id).await?;
+
+    let transactions = LedgerRepo::for_portfolio(conn, portfolio_id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch ledger of portfolio {}", portfolio_id)))?
+        .into_iter()
+        .map(LedgerTransaction::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ApiErrorResponse::internal(format!("Invalid ledger row: {}", e)))?;
+
+    Ok((portfolio, transactions))
+}
+
+/// Converter for the `currency` query parameter (USD when omitted), with FX rates from two weeks
+/// before `from` so the first days can be forward-filled over weekends and holidays.
+async fn load_converter(
+    conn: &mut PgPooledConnection,
+    currency: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<CurrencyConverter, ApiErrorResponse> {
+    let currency = match currency {
+        Some(currency) => Currency::from_str(currency)
+            .map_err(|_| ApiErrorResponse::bad_request(format!("Invalid currency: {}", currency)))?,
+        None => Currency::Usd,
+    };
+    let Some(symbol) = currency.usd_rate_symbol() else {
+        return Ok(CurrencyConverter::usd());
+    };
+
+    let rates = MarketMetricRepo::range(conn, symbol.as_str(), from - Duration::days(14), to)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch {} from database", symbol.as_str())))?
+        .into_iter()
+        .filter_map(|row| row.value.map(|v| (row.timestamp, v)))
+        .collect();
+
+    Ok(CurrencyConverter::new(currency, rates))
+}
+
+async fn load_nav_converter(
+    conn: &mut PgPooledConnection,
+    currency: Option<&str>,
+    nav: &[NavPoint],
+) -> Result<CurrencyConverter, ApiErrorResponse> {
+    let today = Utc::now().date_naive();
+    let from = nav.iter().map(|p| p.date).min().unwrap_or(today);
+    let to = nav.iter().map(|p| p.date).max().unwrap_or(today);
+    load_converter(conn, currency, from, to).await
+}
+
+async fn load_targets(
+    conn: &mut PgPooledConnection,
+    portfolio_id: Uuid,
+) -> Result<Vec<AllocationTarget>, ApiErrorResponse> {
+    PortfolioTargetRepo::for_portfolio(conn, portfolio_id)
+        .await
+        .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch targets of portfolio {}", portfolio_id)))?
+        .into_iter()
+        .map(AllocationTarget::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ApiErrorResponse::internal(format!("Invalid target row: {}", e)))
+}
+
+/// Latest `market_data` close for every asset that has a USD price series.
+async fn latest_prices(
+    conn: &mut PgPooledConnection,
+    assets: &[String],
+) -> Result<BTreeMap<String, f64>, ApiErrorResponse> {
+    let mut prices = BTreeMap::new();
+    prices.insert(domain::CASH_ASSET.to_string(), 1.0);
+
+    for asset in assets {
+        if prices.contains_key(asset) {
+            continue;
+        }
+        let Some(symbol) = price_symbol(asset) else { continue };
+
+        let latest = MarketDataRepo::latest_n_for_asset(conn, symbol, 1)
+            .await
+            .map_err(|_| ApiErrorResponse::internal(format!("Cannot fetch latest price for {}", asset)))?;
+
+        if let Some(row) = latest.first() {
+            prices.insert(asset.clone(), row.price_usd);
+        }
+    }
+
+    Ok(prices)
+}
@@ -1,16 +1,18 @@
+mod auth;
 mod dtos;
 mod handlers;
 mod errors;
 
 use std::env;
 
-use actix_web::{web, App, HttpServer};
+use actix_web::{middleware::from_fn, web, App, HttpServer};
 use actix_cors::Cors;
 use dotenvy::dotenv;
 use store::db::establish_pool;
 use telemetry::setup_observability;
 
-use crate::handlers::{btc_dashboard, historical_metrics};
+use crate::auth::authenticate;
+use crate::handlers::{btc_dashboard, correlation_pairs, correlations, create_account, create_portfolio, create_transaction, dca, get_signal, historical_metrics, import_transactions, issue_api_key, list_accounts, list_api_keys, list_portfolios, list_signals, portfolio_nav, portfolio_performance, portfolio_pnl, portfolio_rebalance, portfolio_targets, revoke_api_key, set_portfolio_targets, tax_report};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -27,14 +29,37 @@ async fn main() -> std::io::Result<()> {
         let cors = Cors::default()
             .allowed_origin("http://localhost:3000")
             .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
-            .allowed_headers(vec!["Content-Type"])
+            .allowed_headers(vec!["Content-Type", "Authorization", "X-Api-Key"])
             .max_age(3600);
         
         App::new()
+            .wrap(from_fn(authenticate))
             .wrap(cors)
             .app_data(web::Data::new(db_pool.clone()))
+            .app_data(web::PayloadConfig::new(10 * 1024 * 1024))
             .service(btc_dashboard)
+            .service(portfolio_nav)
+            .service(portfolio_performance)
+            .service(portfolio_targets)
+            .service(set_portfolio_targets)
+            .service(portfolio_rebalance)
             .service(historical_metrics)
+            .service(correlations)
+            .service(correlation_pairs)
+            .service(dca)
+            .service(list_signals)
+            .service(get_signal)
+            .service(portfolio_pnl)
+            .service(tax_report)
+            .service(import_transactions)
+            .service(list_portfolios)
+            .service(create_portfolio)
+            .service(list_accounts)
+            .service(create_account)
+            .service(create_transaction)
+            .service(list_api_keys)
+            .service(issue_api_key)
+            .service(revoke_api_key)
     })
     .bind(("127.0.0.1", port))?
     .run()
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use clap::{Args, Subcommand};
+use domain::{AlertChannel, AlertCondition, AlertRule};
+use store::db::establish_pool;
+use store::models::alert_db::AlertRuleDB;
+use store::repositories::alert_repository::{AlertDeliveryRepo, AlertRuleRepo};
+use uuid::Uuid;
+
+#[derive(Args)]
+pub struct AlertArgs {
+    #[command(subcommand)]
+    command: AlertCommand,
+}
+
+#[derive(Subcommand)]
+enum AlertCommand {
+    /// Add a rule, e.g. --when "FEAR_GREED_INDEX below 15" --channel webhook --target https://...
+    Add {
+        #[arg(long)]
+        name: String,
+        /// "SYMBOL crosses [above|below] N", "SYMBOL above|below N" or
+        /// "new [ACTION] signal [for ASSET] [from SOURCE]"
+        #[arg(long)]
+        when: AlertCondition,
+        /// webhook or email
+        #[arg(long)]
+        channel: AlertChannel,
+        /// Webhook URL or mail address
+        #[arg(long)]
+        target: String,
+        /// Minimum minutes between two alerts of the rule
+        #[arg(long, default_value_t = 60)]
+        cooldown: i64,
+    },
+    /// List all rules
+    List,
+    /// Pause or resume a rule
+    Enable {
+        #[arg(long)]
+        id: Uuid,
+        #[arg(long, action = clap::ArgAction::Set, default_value_t = true)]
+        enabled: bool,
+    },
+    /// Delete a rule and its delivery log
+    Remove {
+        #[arg(long)]
+        id: Uuid,
+    },
+    /// Show the latest deliveries
+    Log {
+        #[arg(long)]
+        rule_id: Option<Uuid>,
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+}
+
+pub async fn run(args: AlertArgs) -> Result<()> {
+    let pool = establish_pool();
+    let mut conn = pool.get().context("Cannot use the connection with the database")?;
+
+    match args.command {
+        AlertCommand::Add { name, when, channel, target, cooldown } => {
+            let rule = AlertRule {
+                id: Uuid::new_v4(),
+                name,
+                condition: when,
+                channel,
+                target,
+                cooldown_minutes: cooldown,
+                enabled: true,
+                created_at: Utc::now().naive_utc(),
+            };
+            AlertRuleRepo::insert(&mut conn, &AlertRuleDB::from(&rule))
+                .await
+                .with_context(|| format!("Cannot create alert rule {}", rule.name))?;
+            println!("alert_rule_id: {}", rule.id);
+            Ok(())
+        }
+        AlertCommand::List => {
+            for rule in AlertRuleRepo::all(&mut conn).await? {
+                println!(
+                    "{}  {:<24} {:<8} {:<40} {} every {}m -> {}",
+                    rule.id,
+                    rule.name,
+                    if rule.enabled { "enabled" } else { "paused" },
+                    rule.condition,
+                    rule.channel,
+                    rule.cooldown_minutes,
+                    rule.target,
+                );
+            }
+            Ok(())
+        }
+        AlertCommand::Enable { id, enabled } => match AlertRuleRepo::set_enabled(&mut conn, id, enabled).await? {
+            0 => Err(anyhow!("Unknown alert rule: {}", id)),
+            _ => Ok(()),
+        },
+        AlertCommand::Remove { id } => match AlertRuleRepo::delete(&mut conn, id).await? {
+            0 => Err(anyhow!("Unknown alert rule: {}", id)),
+            _ => Ok(()),
+        },
+        AlertCommand::Log { rule_id, limit } => {
+            for delivery in AlertDeliveryRepo::latest_n(&mut conn, rule_id, limit).await? {
+                println!(
+                    "{}  {}  {:<10} {}{}",
+                    delivery.created_at.format("%Y-%m-%d %H:%M"),
+                    delivery.rule_id,
+                    delivery.status,
+                    delivery.subject,
+                    delivery.error.map(|e| format!(" ({e})")).unwrap_or_default(),
+                );
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{Duration, NaiveDate};
+use clap::{Args, ValueEnum};
+use domain::{
+    BacktestConfig, BacktestResult, MarketDataSet, MarketSymbol, Strategy, StrategyRegistry, TwoHundredWeekMa, backtest,
+};
+use store::db::{PgPooledConnection, establish_pool};
+use store::repositories::market_metrics_repository::MarketMetricRepo;
+
+use crate::rules;
+
+#[derive(Args)]
+pub struct BacktestArgs {
+    /// Name of a registered strategy
+    #[arg(long)]
+    strategy: String,
+    /// TOML rules file, replayed as strategy `rules`
+    #[arg(long)]
+    rules: Option<PathBuf>,
+    #[arg(long)]
+    from: NaiveDate,
+    #[arg(long)]
+    to: NaiveDate,
+    #[arg(long, default_value_t = 10_000.0)]
+    initial_cash: f64,
+    /// Fee per fill in basis points of the notional
+    #[arg(long, default_value_t = 10.0)]
+    fee_bps: f64,
+    /// Slippage per fill in basis points of the close
+    #[arg(long, default_value_t = 5.0)]
+    slippage_bps: f64,
+    /// Annual rate in percent earned by uninvested cash
+    #[arg(long, default_value_t = 0.0)]
+    cash_rate: f64,
+    /// Share of equity one buy or sell signal moves
+    #[arg(long, default_value_t = 0.25)]
+    position_step: f64,
+    #[arg(long, default_value_t = 0.0)]
+    min_confidence: f64,
+    #[arg(long, value_enum, default_value_t = BacktestFormat::Summary)]
+    format: BacktestFormat,
+    /// Write to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BacktestFormat {
+    Summary,
+    Json,
+    /// Equity curve only
+    Csv,
+}
+
+pub async fn run(args: BacktestArgs) -> Result<()> {
+    let mut registry = StrategyRegistry::builtin();
+    let mut warm_up = Duration::zero();
+    if let Some(path) = &args.rules {
+        let rules = rules::load(path)?;
+        warm_up = rules.warm_up();
+        registry.register(Box::new(rules))?;
+    }
+    let strategy = registry
+        .get(&args.strategy)
+        .map_err(|e| anyhow!("{}; available: {}", e, registry.names().join(", ")))?;
+
+    let config = BacktestConfig {
+        fee_rate: args.fee_bps / 10_000.0,
+        slippage: args.slippage_bps / 10_000.0,
+        cash_rate: args.cash_rate / 100.0,
+        position_step: args.position_step,
+        min_confidence: args.min_confidence,
+        ..BacktestConfig::new(args.from, args.to, args.initial_cash)
+    };
+
+    let pool = establish_pool();
+    let mut conn = pool.get().context("Cannot use the connection with the database")?;
+    let data = load_market_data(&mut conn, strategy, warm_up, args.from, args.to).await?;
+
+    let result = backtest(strategy, &data, &config)?;
+    let rendered = match args.format {
+        BacktestFormat::Summary => summary(&result),
+        BacktestFormat::Json => serde_json::to_string_pretty(&result)? + "\n",
+        BacktestFormat::Csv => result.equity_csv(),
+    };
+
+    match args.output {
+        Some(path) => std::fs::write(&path, rendered).with_context(|| format!("Cannot write {}", path.display()))?,
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
+
+/// The strategy's inputs plus DFF for the Sharpe ratio, from `from` minus at least the warm-up of
+/// the longest built-in window to `to`.
+pub async fn load_market_data(
+    conn: &mut PgPooledConnection,
+    strategy: &dyn Strategy,
+    warm_up: Duration,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<MarketDataSet> {
+    let warm_up = warm_up.max(Duration::days(TwoHundredWeekMa::WINDOW_DAYS as i64 + 60));
+    let mut inputs = strategy.inputs();
+    inputs.push(MarketSymbol::DFF);
+
+    Ok(MarketMetricRepo::market_data_set(conn, &inputs, from - warm_up, to).await?)
+}
+
+fn summary(result: &BacktestResult) -> String {
+    let stats = &result.stats;
+    let mut out = format!(
+        "strategy:        {}\nperiod:          {} to {}\nfinal equity:    {:.2}\ntotal return:    {:.2}%\ntrades:          {}\nfees:            {:.2}\nslippage:        {:.2}\ncash interest:   {:.2}\navg exposure:    {:.1}%\n",
+        result.strategy,
+        result.config.from,
+        result.config.to,
+        stats.final_equity,
+        stats.total_return * 100.0,
+        stats.trade_count,
+        stats.total_fees_usd,
+        stats.total_slippage_usd,
+        stats.cash_interest_usd,
+        stats.average_exposure * 100.0,
+    );
+    if let Some(report) = &stats.performance {
+        out.push_str(&format!(
+            "volatility:      {:.2}%\nmax drawdown:    {:.2}% ({} days)\nsharpe:          {}\n",
+            report.annualized_volatility * 100.0,
+            report.max_drawdown * 100.0,
+            report.max_drawdown_days,
+            report.sharpe_ratio.map_or("n/a".to_string(), |s| format!("{s:.2}")),
+        ));
+    }
+    out
+}
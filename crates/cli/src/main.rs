@@ -0,0 +1,55 @@
+mod alerts;
+mod backtest;
+mod optimize;
+mod rules;
+mod tax_report;
+mod users;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use dotenvy::dotenv;
+
+use crate::alerts::AlertArgs;
+use crate::backtest::BacktestArgs;
+use crate::optimize::OptimizeArgs;
+use crate::rules::RulesArgs;
+use crate::tax_report::TaxReportArgs;
+use crate::users::UserArgs;
+
+/// Offline portfolio tools working on the same database as the API.
+#[derive(Parser)]
+#[command(name = "portfolio", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage alert rules and inspect their delivery log
+    Alerts(AlertArgs),
+    /// Replay a strategy over stored market history
+    Backtest(BacktestArgs),
+    /// Walk-forward parameter search for a built-in strategy
+    Optimize(OptimizeArgs),
+    /// Validate TOML signal rules or dry-run them over stored history
+    Rules(RulesArgs),
+    /// Export the realized gains of one year as CSV or JSON
+    TaxReport(TaxReportArgs),
+    /// Manage users, their API keys and which portfolios they own
+    User(UserArgs),
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    match Cli::parse().command {
+        Command::Alerts(args) => alerts::run(args).await,
+        Command::Backtest(args) => backtest::run(args).await,
+        Command::Optimize(args) => optimize::run(args).await,
+        Command::Rules(args) => rules::run(args).await,
+        Command::TaxReport(args) => tax_report::run(args).await,
+        Command::User(args) => users::run(args).await,
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::{Duration, NaiveDate};
+use clap::{Args, ValueEnum};
+use domain::{
+    BacktestConfig, Objective, SearchMethod, WalkForwardConfig, WalkForwardReport, builtin_tunables, walk_forward,
+};
+use store::db::establish_pool;
+
+use crate::backtest::load_market_data;
+
+#[derive(Args)]
+pub struct OptimizeArgs {
+    /// Name of a built-in strategy with tunable parameters
+    #[arg(long)]
+    strategy: String,
+    #[arg(long)]
+    from: NaiveDate,
+    #[arg(long)]
+    to: NaiveDate,
+    /// Days each parameter search looks at
+    #[arg(long, default_value_t = 730)]
+    in_sample_days: i64,
+    /// Days the chosen parameters then trade; windows advance by this much
+    #[arg(long, default_value_t = 182)]
+    out_of_sample_days: i64,
+    #[arg(long, value_enum, default_value_t = SearchArg::Grid)]
+    search: SearchArg,
+    /// Combinations drawn per window with `--search random`
+    #[arg(long, default_value_t = 20)]
+    samples: usize,
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+    /// sharpe or total-return
+    #[arg(long, default_value = "sharpe")]
+    objective: Objective,
+    #[arg(long, default_value_t = 10_000.0)]
+    initial_cash: f64,
+    /// Fee per fill in basis points of the notional
+    #[arg(long, default_value_t = 10.0)]
+    fee_bps: f64,
+    /// Slippage per fill in basis points of the close
+    #[arg(long, default_value_t = 5.0)]
+    slippage_bps: f64,
+    #[arg(long, value_enum, default_value_t = OptimizeFormat::Summary)]
+    format: OptimizeFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchArg {
+    Grid,
+    Random,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OptimizeFormat {
+    Summary,
+    Json,
+}
+
+pub async fn run(args: OptimizeArgs) -> Result<()> {
+    let tunables = builtin_tunables();
+    let strategy = tunables.iter().find(|s| s.name() == args.strategy).ok_or_else(|| {
+        let names: Vec<&str> = tunables.iter().map(|s| s.name()).collect();
+        anyhow!("Strategy {} has no tunable parameters; available: {}", args.strategy, names.join(", "))
+    })?;
+
+    let config = WalkForwardConfig {
+        backtest: BacktestConfig {
+            fee_rate: args.fee_bps / 10_000.0,
+            slippage: args.slippage_bps / 10_000.0,
+            ..BacktestConfig::new(args.from, args.to, args.initial_cash)
+        },
+        in_sample_days: args.in_sample_days,
+        out_of_sample_days: args.out_of_sample_days,
+        search: match args.search {
+            SearchArg::Grid => SearchMethod::Grid,
+            SearchArg::Random => SearchMethod::Random { samples: args.samples, seed: args.seed },
+        },
+        objective: args.objective,
+    };
+
+    let pool = establish_pool();
+    let mut conn = pool.get().context("Cannot use the connection with the database")?;
+    let data = load_market_data(&mut conn, strategy.as_ref(), Duration::zero(), args.from, args.to).await?;
+
+    let report = walk_forward(strategy.as_ref(), &strategy.search_space(), &data, &config)?;
+    match args.format {
+        OptimizeFormat::Summary => print!("{}", summary(&report)),
+        OptimizeFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    Ok(())
+}
+
+fn summary(report: &WalkForwardReport) -> String {
+    let mut out = format!("strategy:        {}\nobjective:       {}\n\n", report.strategy, report.objective.as_str());
+    for window in &report.windows {
+        let parameters: Vec<String> = window.parameters.iter().map(|(name, value)| format!("{name}={value}")).collect();
+        out.push_str(&format!(
+            "{} to {}  in {:>7.2}  out {:>7.2}  return {:>7.2}%  {}\n",
+            window.out_of_sample_from,
+            window.out_of_sample_to,
+            window.in_sample_score,
+            window.out_of_sample_score,
+            window.out_of_sample_return * 100.0,
+            parameters.join(" "),
+        ));
+    }
+    out.push_str(&format!(
+        "\nwindows:         {} ({} skipped)\nout-of-sample:   {:.2}%\nmean in/out:     {:.2} / {:.2}\nefficiency:      {}\n",
+        report.windows.len(),
+        report.skipped_windows,
+        report.out_of_sample_return * 100.0,
+        report.mean_in_sample_score,
+        report.mean_out_of_sample_score,
+        report.efficiency.map_or("n/a".to_string(), |e| format!("{e:.2}")),
+    ));
+    for stability in &report.stability {
+        out.push_str(&format!(
+            "{:<20} {} distinct, {} in {:.0}% of windows, {} changes\n",
+            stability.name,
+            stability.distinct_values,
+            stability.most_common,
+            stability.most_common_share * 100.0,
+            stability.changes,
+        ));
+    }
+    out
+}
@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
+use clap::{Args, Subcommand};
+use domain::{RuleSet, Strategy};
+use store::db::establish_pool;
+use store::repositories::market_metrics_repository::MarketMetricRepo;
+
+#[derive(Args)]
+pub struct RulesArgs {
+    #[command(subcommand)]
+    command: RulesCommand,
+}
+
+#[derive(Subcommand)]
+enum RulesCommand {
+    /// Validate a rules file and list its rules
+    Check {
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Print what the rules would have fired over stored history, without storing anything
+    DryRun {
+        #[arg(long)]
+        file: PathBuf,
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+    },
+}
+
+pub async fn run(args: RulesArgs) -> Result<()> {
+    match args.command {
+        RulesCommand::Check { file } => {
+            let rules = load(&file)?;
+            for rule in rules.rules() {
+                println!("{:<24} {:<8} {:<8} {}", rule.name, rule.asset, rule.action.as_str(), rule.when);
+            }
+            println!("{}: {} rules OK", file.display(), rules.rules().len());
+            Ok(())
+        }
+        RulesCommand::DryRun { file, from, to } => {
+            if from > to {
+                return Err(anyhow!("--from {} is after --to {}", from, to));
+            }
+            let rules = load(&file)?;
+
+            let pool = establish_pool();
+            let mut conn = pool.get().context("Cannot use the connection with the database")?;
+            let data = MarketMetricRepo::market_data_set(&mut conn, &rules.inputs(), from - rules.warm_up(), to).await?;
+
+            let fired = rules.dry_run(&data, from, to);
+            for signal in &fired {
+                println!("{}  {:<8} {:<32} {}", signal.date, signal.asset_symbol, signal.signal_type(), signal.reason);
+            }
+            println!("{} signals from {} to {}", fired.len(), from, to);
+            Ok(())
+        }
+    }
+}
+
+/// Parses a rules file, listing every validation error with its line.
+pub fn load(path: &Path) -> Result<RuleSet> {
+    let source = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+    RuleSet::parse(&source).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(|e| format!("{}: {}", path.display(), e)).collect();
+        anyhow!("Invalid rules file\n{}", errors.join("\n"))
+    })
+}
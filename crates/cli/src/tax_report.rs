@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow};
+use clap::{Args, ValueEnum};
+use domain::{LedgerTransaction, Portfolio, TaxReport};
+use store::db::establish_pool;
+use store::repositories::{ledger_repository::LedgerRepo, portfolio_repository::PortfolioRepo};
+use uuid::Uuid;
+
+#[derive(Args)]
+pub struct TaxReportArgs {
+    #[arg(long)]
+    portfolio_id: Uuid,
+    #[arg(long)]
+    year: i32,
+    #[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+    format: ReportFormat,
+    /// Write to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Csv,
+    Json,
+}
+
+pub async fn run(args: TaxReportArgs) -> Result<()> {
+    let pool = establish_pool();
+    let mut conn = pool.get().context("Cannot use the connection with the database")?;
+
+    let portfolio: Portfolio = PortfolioRepo::get(&mut conn, args.portfolio_id)
+        .await?
+        .ok_or_else(|| anyhow!("Unknown portfolio: {}", args.portfolio_id))?
+        .try_into()?;
+
+    let transactions = LedgerRepo::for_portfolio(&mut conn, portfolio.id)
+        .await?
+        .into_iter()
+        .map(LedgerTransaction::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let report = TaxReport::build(
+        portfolio.id,
+        args.year,
+        portfolio.cost_basis_method,
+        portfolio.tax_jurisdiction,
+        &transactions,
+    )
+        .map_err(|e| anyhow!("Ledger of portfolio {} is inconsistent: {}", portfolio.id, e))?;
+
+    let rendered = match args.format {
+        ReportFormat::Csv => report.to_csv(),
+        ReportFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+    };
+
+    match args.output {
+        Some(path) => std::fs::write(&path, rendered).with_context(|| format!("Cannot write {}", path.display()))?,
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
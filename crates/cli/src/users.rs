@@ -0,0 +1,93 @@
+use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use clap::{Args, Subcommand};
+use domain::GeneratedApiKey;
+use store::db::{PgPooledConnection, establish_pool};
+use store::models::user_db::{ApiKeyDB, UserDB};
+use store::repositories::{portfolio_repository::PortfolioRepo, user_repository::{ApiKeyRepo, UserRepo}};
+use uuid::Uuid;
+
+#[derive(Args)]
+pub struct UserArgs {
+    #[command(subcommand)]
+    command: UserCommand,
+}
+
+#[derive(Subcommand)]
+enum UserCommand {
+    /// Create a user and print its first API key
+    Create {
+        #[arg(long)]
+        name: String,
+    },
+    /// Issue another API key for a user
+    IssueKey {
+        #[arg(long)]
+        user_id: Uuid,
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Make a user the owner of a portfolio
+    AssignPortfolio {
+        #[arg(long)]
+        portfolio_id: Uuid,
+        #[arg(long)]
+        user_id: Uuid,
+    },
+}
+
+pub async fn run(args: UserArgs) -> Result<()> {
+    let pool = establish_pool();
+    let mut conn = pool.get().context("Cannot use the connection with the database")?;
+
+    match args.command {
+        UserCommand::Create { name } => {
+            let user = UserDB {
+                id: Uuid::new_v4(),
+                name,
+                created_at: Utc::now().naive_utc(),
+            };
+            UserRepo::insert(&mut conn, &user)
+                .await
+                .with_context(|| format!("Cannot create user {}", user.name))?;
+            println!("user_id: {}", user.id);
+            issue_key(&mut conn, user.id, Some("initial".to_string())).await
+        }
+        UserCommand::IssueKey { user_id, label } => {
+            UserRepo::get(&mut conn, user_id)
+                .await?
+                .ok_or_else(|| anyhow!("Unknown user: {}", user_id))?;
+            issue_key(&mut conn, user_id, label).await
+        }
+        UserCommand::AssignPortfolio { portfolio_id, user_id } => {
+            UserRepo::get(&mut conn, user_id)
+                .await?
+                .ok_or_else(|| anyhow!("Unknown user: {}", user_id))?;
+            match PortfolioRepo::set_owner(&mut conn, portfolio_id, Some(user_id)).await? {
+                0 => Err(anyhow!("Unknown portfolio: {}", portfolio_id)),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+/// Stores the hash of a new key and prints the key itself, which cannot be recovered later.
+async fn issue_key(conn: &mut PgPooledConnection, user_id: Uuid, label: Option<String>) -> Result<()> {
+    let generated = GeneratedApiKey::generate();
+    let key = ApiKeyDB {
+        id: Uuid::new_v4(),
+        user_id,
+        key_hash: generated.hash,
+        prefix: generated.prefix,
+        label,
+        created_at: Utc::now().naive_utc(),
+        last_used_at: None,
+        revoked_at: None,
+    };
+    ApiKeyRepo::insert(conn, &key)
+        .await
+        .with_context(|| format!("Cannot store API key for user {}", user_id))?;
+
+    println!("api_key: {}", generated.key);
+    Ok(())
+}
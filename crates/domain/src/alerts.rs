@@ -0,0 +1,340 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::yields::{threshold_crossing, CrossingDirection};
+
+/// How an alert reaches whoever set it up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlertChannel {
+    /// JSON `POST` of the [`AlertMatch`] to the rule's target URL.
+    Webhook,
+    /// Mail to the rule's target address.
+    Email,
+}
+
+impl AlertChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertChannel::Webhook => "WEBHOOK",
+            AlertChannel::Email => "EMAIL",
+        }
+    }
+}
+
+impl FromStr for AlertChannel {
+    type Err = ParseAlertChannelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "WEBHOOK" => Ok(AlertChannel::Webhook),
+            "EMAIL" | "SMTP" => Ok(AlertChannel::Email),
+            other => Err(ParseAlertChannelError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAlertChannelError(pub String);
+
+impl fmt::Display for ParseAlertChannelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid alert channel: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAlertChannelError {}
+
+/// What an alert rule waits for, written the way it is stored:
+///
+/// - `BTC_DOMINANCE crosses 0.6`, `BTC_USD crosses above 100000`: the latest observation is on
+///   the other side of the level than the one before it.
+/// - `FEAR_GREED_INDEX below 15`, `DGS10 above 5`: the latest observation is beyond the level.
+/// - `new BUY signal for BTC from longterm`: a strategy signal was stored. Action, asset and
+///   source are optional filters.
+#[derive(Debug, Clone)]
+pub enum AlertCondition {
+    Crosses { symbol: MarketSymbol, level: f64, direction: Option<CrossingDirection> },
+    Beyond { symbol: MarketSymbol, level: f64, direction: CrossingDirection },
+    NewSignal { action: Option<String>, asset: Option<String>, source: Option<String> },
+}
+
+impl AlertCondition {
+    /// The series the condition reads, if any.
+    pub fn symbol(&self) -> Option<&MarketSymbol> {
+        match self {
+            AlertCondition::Crosses { symbol, .. } | AlertCondition::Beyond { symbol, .. } => Some(symbol),
+            AlertCondition::NewSignal { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for AlertCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertCondition::Crosses { symbol, level, direction: None } => {
+                write!(f, "{} crosses {}", symbol.as_str(), level)
+            }
+            AlertCondition::Crosses { symbol, level, direction: Some(direction) } => {
+                write!(f, "{} crosses {} {}", symbol.as_str(), direction.as_str().to_lowercase(), level)
+            }
+            AlertCondition::Beyond { symbol, level, direction } => {
+                write!(f, "{} {} {}", symbol.as_str(), direction.as_str().to_lowercase(), level)
+            }
+            AlertCondition::NewSignal { action, asset, source } => {
+                write!(f, "new")?;
+                if let Some(action) = action {
+                    write!(f, " {action}")?;
+                }
+                write!(f, " signal")?;
+                if let Some(asset) = asset {
+                    write!(f, " for {asset}")?;
+                }
+                if let Some(source) = source {
+                    write!(f, " from {source}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for AlertCondition {
+    type Err = ParseAlertConditionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ParseAlertConditionError(format!("{} ({reason})", s.trim()));
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        let lower: Vec<&str> = lower.iter().map(String::as_str).collect();
+
+        if lower.first() == Some(&"new") {
+            let mut rest = &words[1..];
+            let mut action = None;
+            if rest.first().is_some_and(|w| !w.eq_ignore_ascii_case("signal") && !w.eq_ignore_ascii_case("signals")) {
+                action = Some(rest[0].to_uppercase());
+                rest = &rest[1..];
+            }
+            match rest.first() {
+                Some(w) if w.eq_ignore_ascii_case("signal") || w.eq_ignore_ascii_case("signals") => rest = &rest[1..],
+                _ => return Err(invalid("expected `new [ACTION] signal`")),
+            }
+
+            let (mut asset, mut source) = (None, None);
+            while let [keyword, value, tail @ ..] = rest {
+                match keyword.to_lowercase().as_str() {
+                    "for" if asset.is_none() => asset = Some(value.to_uppercase()),
+                    "from" if source.is_none() => source = Some(value.to_string()),
+                    _ => return Err(invalid(&format!("unexpected `{keyword}`"))),
+                }
+                rest = tail;
+            }
+            if !rest.is_empty() {
+                return Err(invalid(&format!("unexpected `{}`", rest[0])));
+            }
+            return Ok(AlertCondition::NewSignal { action, asset, source });
+        }
+
+        let symbol = match words.first() {
+            Some(word) => MarketSymbol::from_str(word).map_err(|_| invalid(&format!("unknown series `{word}`")))?,
+            None => return Err(invalid("empty")),
+        };
+        let direction = |word: &str| match word {
+            "above" => Ok(CrossingDirection::Above),
+            "below" => Ok(CrossingDirection::Below),
+            _ => Err(invalid("expected `crosses`, `above` or `below`")),
+        };
+        let level = |word: &str| {
+            word.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| invalid("expected a number"))
+        };
+
+        match lower[1..] {
+            ["crosses", _] => Ok(AlertCondition::Crosses { symbol, level: level(words[2])?, direction: None }),
+            ["crosses", side, _] => {
+                Ok(AlertCondition::Crosses { symbol, direction: Some(direction(side)?), level: level(words[3])? })
+            }
+            [side, _] => Ok(AlertCondition::Beyond { symbol, direction: direction(side)?, level: level(words[2])? }),
+            _ => Err(invalid("expected `crosses`, `above` or `below` and a level")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAlertConditionError(pub String);
+
+impl fmt::Display for ParseAlertConditionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid alert condition: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAlertConditionError {}
+
+/// A stored strategy signal, as alert rules see it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalEvent {
+    pub id: Uuid,
+    pub asset_symbol: String,
+    pub timestamp: NaiveDateTime,
+    /// e.g. `MAYER_MULTIPLE_SELL`; the action is the last part.
+    pub signal_type: String,
+    pub value: Option<f64>,
+    pub description: Option<String>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub id: Uuid,
+    pub name: String,
+    pub condition: AlertCondition,
+    pub channel: AlertChannel,
+    /// Webhook URL or mail address.
+    pub target: String,
+    /// Minimum time between two alerts of this rule. Matches in between are logged as suppressed.
+    pub cooldown_minutes: i64,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+}
+
+/// A rule that matched, ready to be sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertMatch {
+    pub rule_id: Uuid,
+    pub rule_name: String,
+    /// What matched: the observation date of a series or the id of a signal. A match is sent at
+    /// most once, however often the rule is evaluated.
+    pub dedup_key: String,
+    pub subject: String,
+    pub message: String,
+}
+
+impl AlertRule {
+    /// Matches of the rule in `series`, the date-sorted observations of the condition's symbol,
+    /// and in `signals`. Only signals dated on or after the day the rule was created count, so a
+    /// new rule does not replay history.
+    pub fn evaluate(&self, series: &[(NaiveDate, f64)], signals: &[SignalEvent]) -> Vec<AlertMatch> {
+        match &self.condition {
+            AlertCondition::Crosses { symbol, level, direction } => {
+                let [.., (_, previous), (date, latest)] = series else {
+                    return Vec::new();
+                };
+                match threshold_crossing(*previous, *latest, &[*level]) {
+                    Some((crossed, _)) if direction.is_none_or(|d| d == crossed) => vec![self.matched(
+                        date.to_string(),
+                        format!("{} crossed {} {}", symbol.as_str(), crossed.as_str().to_lowercase(), level),
+                        format!("{} moved from {} to {} on {}", symbol.formatted_name(), previous, latest, date),
+                    )],
+                    _ => Vec::new(),
+                }
+            }
+            AlertCondition::Beyond { symbol, level, direction } => {
+                let Some((date, latest)) = series.last() else {
+                    return Vec::new();
+                };
+                let beyond = match direction {
+                    CrossingDirection::Above => latest > level,
+                    CrossingDirection::Below => latest < level,
+                };
+                if !beyond {
+                    return Vec::new();
+                }
+                vec![self.matched(
+                    date.to_string(),
+                    format!("{} is {} {}", symbol.as_str(), direction.as_str().to_lowercase(), level),
+                    format!("{} was {} on {}", symbol.formatted_name(), latest, date),
+                )]
+            }
+            AlertCondition::NewSignal { action, asset, source } => signals
+                .iter()
+                .filter(|s| s.timestamp.date() >= self.created_at.date())
+                .filter(|s| action.as_ref().is_none_or(|a| s.signal_type.ends_with(&format!("_{a}"))))
+                .filter(|s| asset.as_ref().is_none_or(|a| s.asset_symbol.eq_ignore_ascii_case(a)))
+                .filter(|s| source.as_ref().is_none_or(|src| s.source.as_deref() == Some(src.as_str())))
+                .map(|s| {
+                    self.matched(
+                        s.id.to_string(),
+                        format!("{} {} signal", s.asset_symbol, s.signal_type),
+                        format!(
+                            "{} on {} from {}: {}",
+                            s.signal_type,
+                            s.timestamp.date(),
+                            s.source.as_deref().unwrap_or("unknown source"),
+                            s.description.as_deref().unwrap_or("no description")
+                        ),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether an alert sent at `last_sent` still blocks another one at `now`.
+    pub fn cooling_down(&self, last_sent: Option<NaiveDateTime>, now: NaiveDateTime) -> bool {
+        last_sent.is_some_and(|sent| now - sent < Duration::minutes(self.cooldown_minutes))
+    }
+
+    fn matched(&self, dedup_key: String, subject: String, message: String) -> AlertMatch {
+        AlertMatch {
+            rule_id: self.id,
+            rule_name: self.name.clone(),
+            dedup_key,
+            subject: format!("[{}] {}", self.name, subject),
+            message,
+        }
+    }
+}
+
+/// Outcome of one match, as recorded in the delivery log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeliveryStatus {
+    Sent,
+    /// The notifier failed; the match is retried on the next evaluation.
+    Failed,
+    /// Matched during the rule's cooldown and dropped.
+    Suppressed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Sent => "SENT",
+            DeliveryStatus::Failed => "FAILED",
+            DeliveryStatus::Suppressed => "SUPPRESSED",
+        }
+    }
+
+    /// Whether the match is settled and must not be delivered again.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, DeliveryStatus::Failed)
+    }
+}
+
+impl FromStr for DeliveryStatus {
+    type Err = ParseDeliveryStatusError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "SENT" => Ok(DeliveryStatus::Sent),
+            "FAILED" => Ok(DeliveryStatus::Failed),
+            "SUPPRESSED" => Ok(DeliveryStatus::Suppressed),
+            other => Err(ParseDeliveryStatusError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDeliveryStatusError(pub String);
+
+impl fmt::Display for ParseDeliveryStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid delivery status: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDeliveryStatusError {}
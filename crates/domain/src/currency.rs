@@ -0,0 +1,135 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::nav::{value_as_of, NavPoint};
+use crate::MarketSymbol;
+
+/// Reporting currency. Everything is stored in USD and converted on the way out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    #[serde(rename = "USD")]
+    Usd,
+    #[serde(rename = "EUR")]
+    Eur,
+}
+
+impl Currency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        }
+    }
+
+    /// FRED series quoting the USD price of one unit of this currency; `None` for USD itself.
+    pub fn usd_rate_symbol(&self) -> Option<MarketSymbol> {
+        match self {
+            Currency::Usd => None,
+            Currency::Eur => Some(MarketSymbol::DEXUSEU),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseCurrencyError(pub String);
+
+impl fmt::Display for ParseCurrencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid Currency: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCurrencyError {}
+
+impl FromStr for Currency {
+    type Err = ParseCurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "USD" => Ok(Currency::Usd),
+            "EUR" => Ok(Currency::Eur),
+            other => Err(ParseCurrencyError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    MissingRate { currency: Currency, date: NaiveDate },
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::MissingRate { currency, date } => {
+                write!(f, "No USD/{} rate on or before {}", currency.as_str(), date)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// Converts USD amounts into `currency` at the rate of each amount's date. Rates are the USD
+/// price of one unit of the currency (`DEXUSEU` for EUR) and are forward-filled over days
+/// without a fixing.
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyConverter {
+    currency: Currency,
+    usd_per_unit: Vec<(NaiveDate, f64)>,
+}
+
+impl CurrencyConverter {
+    pub fn new(currency: Currency, mut usd_per_unit: Vec<(NaiveDate, f64)>) -> Self {
+        usd_per_unit.retain(|(_, rate)| rate.is_finite() && *rate > 0.0);
+        usd_per_unit.sort_by_key(|(date, _)| *date);
+        Self { currency, usd_per_unit }
+    }
+
+    /// Leaves every amount in USD.
+    pub fn usd() -> Self {
+        Self::default()
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn rate_as_of(&self, date: NaiveDate) -> Result<f64, ConversionError> {
+        if self.currency == Currency::Usd {
+            return Ok(1.0);
+        }
+        value_as_of(&self.usd_per_unit, date)
+            .map(|(_, rate)| rate)
+            .ok_or(ConversionError::MissingRate { currency: self.currency, date })
+    }
+
+    pub fn convert(&self, amount_usd: f64, date: NaiveDate) -> Result<f64, ConversionError> {
+        Ok(amount_usd / self.rate_as_of(date)?)
+    }
+
+    pub fn convert_series(&self, series: &[(NaiveDate, f64)]) -> Result<Vec<(NaiveDate, f64)>, ConversionError> {
+        series
+            .iter()
+            .map(|(date, value)| Ok((*date, self.convert(*value, *date)?)))
+            .collect()
+    }
+
+    /// NAV and flows of each day at that day's rate.
+    pub fn convert_nav(&self, nav: &[NavPoint]) -> Result<Vec<NavPoint>, ConversionError> {
+        nav.iter()
+            .map(|point| {
+                let rate = self.rate_as_of(point.date)?;
+                Ok(NavPoint {
+                    nav: point.nav / rate,
+                    net_flow: point.net_flow / rate,
+                    ..point.clone()
+                })
+            })
+            .collect()
+    }
+}
@@ -1,17 +1,88 @@
+mod alerts;
+mod currency;
 mod dex_primitives;
 mod portfolio;
+mod users;
+mod yields;
 mod utils;
 mod models;
 mod metrics;
+mod tests;
 
 pub use models::*;
 
+pub use alerts::{
+    AlertChannel, AlertCondition, AlertMatch, AlertRule, DeliveryStatus, ParseAlertChannelError, ParseAlertConditionError,
+    ParseDeliveryStatusError, SignalEvent,
+};
+pub use currency::{ConversionError, Currency, CurrencyConverter, ParseCurrencyError};
 pub use dex_primitives::adapter::DexAdapter;
 pub use dex_primitives::pool::{PoolPrice, SpreadEvent};
 pub use metrics::advanced_metrics::AdvancedMetrics;
+pub use metrics::btc_cycle::{
+    btc_cycle_history, BtcCycleReading, PowerLawFit, BTC_GENESIS, PI_CYCLE_FAST_DAYS, PI_CYCLE_SLOW_DAYS,
+    POWER_LAW_BAND_SIGMAS, TWO_HUNDRED_WEEK_MA_CHANGE_DAYS,
+};
+pub use metrics::correlation::{
+    correlation_matrix, rolling_beta, rolling_correlation, CorrelationMatrix, CORRELATION_SERIES,
+    MIN_CORRELATION_OBSERVATIONS,
+};
 pub use metrics::fear_greed::FearGreedIndexData;
 pub use metrics::fred::FredIndexData;
+pub use metrics::indicators::{
+    atr, bollinger_bands, ema, macd, rolling_volatility, rsi, sma, BollingerPoint, IndicatorCategory, IndicatorSeries,
+    MacdPoint, TechnicalIndicator, DEFAULT_INDICATORS,
+};
 pub use metrics::market_price::{ MarketPrice, MarketSymbol };
 pub use metrics::global_crypto::GlobalCryptoMarketData;
+pub use metrics::regime::{
+    classify_regime, macro_sub_scores, regime_history, MacroRegime, MacroRegimeReading, MacroSubScores,
+    ParseMacroRegimeError, MACRO_REGIME_INPUTS, REGIME_MAX_STALENESS_DAYS,
+};
+pub use metrics::risk::{
+    price_ratio, risk_series, DerivedSeries, BETA_WINDOW_DAYS, RELATIVE_STRENGTH_DAYS, RISK_INPUTS, VOLATILITY_WINDOWS,
+};
+pub use portfolio::backtest::{
+    backtest, BacktestConfig, BacktestError, BacktestResult, BacktestStats, BacktestTrade, EquityPoint,
+};
+pub use portfolio::dca::{
+    compare_dca, dca_schedule, simulate_dca, DcaBuy, DcaFrequency, DcaMethod, DcaPlan, DcaSimulation,
+    ParseDcaFrequencyError, ParseDcaMethodError, PlannedBuy,
+};
+pub use portfolio::income::{fair_market_value, income_in_year, value_income, IncomeRow};
+pub use portfolio::nav::{daily_nav, price_as_of, NavPoint, PriceHistory};
+pub use portfolio::performance::{
+    performance, xirr, ParsePerformancePeriodError, PerformancePeriod, PerformanceReport, DAYS_PER_YEAR,
+};
+pub use portfolio::portfolio_state::{
+    Account, LedgerError, LedgerTransaction, ParseTransactionKindError, Portfolio, PortfolioState, Position,
+    TransactionKind, CASH_ASSET, MAX_ACCOUNT_NAME_LEN, MAX_ACCOUNT_VENUE_LEN, MAX_ASSET_SYMBOL_LEN, MAX_EXTERNAL_ID_LEN,
+};
+pub use portfolio::portfolio_utils::{
+    holdings_as_of, price_symbol, AssetPnl, CostBasisMethod, Lot, LotBook, ParseCostBasisMethodError, RealizedGain,
+};
+pub use portfolio::rebalance::{
+    rebalance, validate_targets, AllocationBucket, AllocationTarget, AssetClass, BucketDrift, LotSale,
+    ParseAssetClassError, RebalanceError, RebalancePlan, RebalanceTrade, TradeSide, DEFAULT_BAND,
+};
+pub use portfolio::signal_rules::{Rule, RuleError, RuleSet, MAX_RULE_ASSET_LEN, MAX_RULE_NAME_LEN, MAX_RULE_WINDOW};
+pub use portfolio::signals::{
+    DominanceTrend, FearGreedExtremes, MarketContext, MarketDataSet, MayerMultiple, ParseSignalActionError, SeriesMap,
+    Signal, SignalAction, SignalExplanation, Strategy, StrategyError, StrategyRegistry, TwoHundredWeekMa,
+    BUILTIN_RULE_VERSION, LONG_TERM_ASSETS,
+};
+pub use portfolio::tax_report::{TaxLotRow, TaxReport};
+pub use portfolio::tax_rules::{
+    classify_gains, ClassifiedGain, GainClassification, GermanRules, HoldingPeriodRules, HoldingTerm, Jurisdiction,
+    ParseJurisdictionError, UsRules,
+};
+pub use portfolio::walk_forward::{
+    builtin_tunables, candidates, walk_forward, Objective, ParameterRange, ParameterStability, Parameters,
+    ParseObjectiveError, SearchMethod, Tunable, WalkForwardConfig, WalkForwardError, WalkForwardReport, WalkForwardWindow,
+};
 
-pub use utils::{current_timestamp_ms, normalize_symbol, chrono_to_offset, native_date_from_str};
\ No newline at end of file
+pub use users::{hash_api_key, ApiKey, GeneratedApiKey, User, API_KEY_PREFIX};
+pub use yields::{
+    threshold_crossing, CrossingDirection, ParseYieldKindError, YieldBenchmark, YieldKind, YieldObservation, YieldQuote,
+};
+pub use utils::{current_timestamp_ms, normalize_symbol, is_usd_quote, chrono_to_offset, native_date_from_str};
\ No newline at end of file
@@ -0,0 +1,194 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::metrics::indicators::sma;
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::nav::value_as_of;
+use crate::portfolio::signals::{MayerMultiple, TwoHundredWeekMa};
+
+/// Day of the genesis block, day zero of the power law.
+pub const BTC_GENESIS: NaiveDate = NaiveDate::from_ymd_opt(2009, 1, 3).unwrap();
+/// The Pi Cycle top indicator compares the 111-day SMA with twice the 350-day SMA.
+pub const PI_CYCLE_FAST_DAYS: usize = 111;
+pub const PI_CYCLE_SLOW_DAYS: usize = 350;
+/// The 200-week MA heatmap colors the price by the change of the MA over the last four weeks.
+pub const TWO_HUNDRED_WEEK_MA_CHANGE_DAYS: i64 = 28;
+/// Width of the power-law corridor, in standard deviations of the log residuals.
+pub const POWER_LAW_BAND_SIGMAS: f64 = 2.0;
+/// Closes needed before the power law is fitted; fewer do not span a cycle.
+const POWER_LAW_MIN_OBSERVATIONS: usize = 2 * 365;
+
+/// Least-squares fit of `log10(price) = intercept + slope * log10(days since genesis)` over the
+/// closes up to a day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PowerLawFit {
+    pub intercept: f64,
+    pub slope: f64,
+    /// Standard deviation of the residuals, in log10 units.
+    pub residual_std: f64,
+    pub observations: usize,
+}
+
+impl PowerLawFit {
+    /// Price on the fitted line.
+    pub fn fair_value(&self, date: NaiveDate) -> f64 {
+        10f64.powf(self.intercept + self.slope * days_since_genesis(date).log10())
+    }
+
+    /// The fitted line moved by `sigmas` residual standard deviations, e.g. `-2.0` for the floor.
+    pub fn band(&self, date: NaiveDate, sigmas: f64) -> f64 {
+        self.fair_value(date) * 10f64.powf(sigmas * self.residual_std)
+    }
+
+    /// How many residual standard deviations `price` is above (or below) the line.
+    pub fn deviation(&self, date: NaiveDate, price: f64) -> f64 {
+        if self.residual_std == 0.0 {
+            return 0.0;
+        }
+        (price / self.fair_value(date)).log10() / self.residual_std
+    }
+}
+
+/// The long-horizon BTC valuation indicators of one day, each from the closes up to that day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BtcCycleReading {
+    pub date: NaiveDate,
+    pub close: f64,
+    /// Close over its 200-day SMA.
+    pub mayer_multiple: Option<f64>,
+    pub two_hundred_week_ma: Option<f64>,
+    /// Change of the 200-week MA over the last four weeks, in percent.
+    pub two_hundred_week_ma_change: Option<f64>,
+    /// 111-day SMA over twice the 350-day SMA. At or above 1 the fast average has crossed the slow one.
+    pub pi_cycle_ratio: Option<f64>,
+    /// Whether the ratio crossed 1 from below on this day, historically within days of a cycle top.
+    pub pi_cycle_top: bool,
+    pub power_law: Option<PowerLawFit>,
+}
+
+impl BtcCycleReading {
+    /// The `market_metrics` values of this day. Indicators without enough history are left out.
+    pub fn metrics(&self) -> Vec<(MarketSymbol, f64)> {
+        let mut metrics = Vec::new();
+        let mut push = |symbol, value: Option<f64>| metrics.extend(value.map(|v| (symbol, v)));
+
+        push(MarketSymbol::BtcMayerMultiple, self.mayer_multiple);
+        push(MarketSymbol::Btc200WeekMa, self.two_hundred_week_ma);
+        push(MarketSymbol::Btc200WeekMaChange, self.two_hundred_week_ma_change);
+        push(MarketSymbol::BtcPiCycleRatio, self.pi_cycle_ratio);
+        push(MarketSymbol::BtcPiCycleTop, self.pi_cycle_ratio.map(|_| if self.pi_cycle_top { 1.0 } else { 0.0 }));
+        if let Some(fit) = &self.power_law {
+            push(MarketSymbol::BtcPowerLawFairValue, Some(fit.fair_value(self.date)));
+            push(MarketSymbol::BtcPowerLawLower, Some(fit.band(self.date, -POWER_LAW_BAND_SIGMAS)));
+            push(MarketSymbol::BtcPowerLawUpper, Some(fit.band(self.date, POWER_LAW_BAND_SIGMAS)));
+            push(MarketSymbol::BtcPowerLawDeviation, Some(fit.deviation(self.date, self.close)));
+            push(MarketSymbol::BtcPowerLawSlope, Some(fit.slope));
+            push(MarketSymbol::BtcPowerLawIntercept, Some(fit.intercept));
+        }
+        metrics
+    }
+}
+
+/// Readings of the days from `from` to `to` with a close in `closes`, the date-sorted `BTC_USD`
+/// history. Everything before `from` is used as history, nothing after a day is looked at.
+pub fn btc_cycle_history(closes: &[(NaiveDate, f64)], from: NaiveDate, to: NaiveDate) -> Vec<BtcCycleReading> {
+    let mayer = Trailing::new(closes, MayerMultiple::WINDOW_DAYS);
+    let pi_fast = Trailing::new(closes, PI_CYCLE_FAST_DAYS);
+    let pi_slow = Trailing::new(closes, PI_CYCLE_SLOW_DAYS);
+    let two_hundred_week = Trailing::new(closes, TwoHundredWeekMa::WINDOW_DAYS);
+
+    let pi_ratio = |i: usize| Some(pi_fast.at(i)? / (2.0 * pi_slow.at(i)?)).filter(|r| r.is_finite());
+    let mut fit = PowerLawSums::default();
+    let mut readings = Vec::new();
+
+    for (i, &(date, close)) in closes.iter().enumerate() {
+        fit.add(date, close);
+        if date < from || date > to {
+            continue;
+        }
+
+        let ma = two_hundred_week.at(i);
+        let ma_before = value_as_of(&two_hundred_week.values, date - Duration::days(TWO_HUNDRED_WEEK_MA_CHANGE_DAYS));
+        let pi_cycle_ratio = pi_ratio(i);
+        let previous_ratio = i.checked_sub(1).and_then(pi_ratio);
+
+        readings.push(BtcCycleReading {
+            date,
+            close,
+            mayer_multiple: mayer.at(i).filter(|m| *m > 0.0).map(|m| close / m),
+            two_hundred_week_ma: ma,
+            two_hundred_week_ma_change: ma
+                .zip(ma_before)
+                .filter(|(_, (_, before))| *before > 0.0)
+                .map(|(now, (_, before))| (now / before - 1.0) * 100.0),
+            pi_cycle_ratio,
+            pi_cycle_top: previous_ratio.zip(pi_cycle_ratio).is_some_and(|(before, now)| before < 1.0 && now >= 1.0),
+            power_law: fit.fit(),
+        });
+    }
+    readings
+}
+
+/// Days since genesis, at least one so the logarithm stays finite.
+fn days_since_genesis(date: NaiveDate) -> f64 {
+    (date - BTC_GENESIS).num_days().max(1) as f64
+}
+
+/// A trailing SMA looked up by the index of the close it ends on.
+struct Trailing {
+    offset: usize,
+    values: Vec<(NaiveDate, f64)>,
+}
+
+impl Trailing {
+    fn new(closes: &[(NaiveDate, f64)], window: usize) -> Self {
+        Self { offset: window.saturating_sub(1), values: sma(closes, window) }
+    }
+
+    fn at(&self, i: usize) -> Option<f64> {
+        self.values.get(i.checked_sub(self.offset)?).map(|(_, v)| *v)
+    }
+}
+
+/// Running sums of an expanding least-squares fit in log-log space.
+#[derive(Default)]
+struct PowerLawSums {
+    n: usize,
+    x: f64,
+    y: f64,
+    xx: f64,
+    xy: f64,
+    yy: f64,
+}
+
+impl PowerLawSums {
+    fn add(&mut self, date: NaiveDate, close: f64) {
+        if close <= 0.0 || date <= BTC_GENESIS {
+            return;
+        }
+        let (x, y) = (days_since_genesis(date).log10(), close.log10());
+        self.n += 1;
+        self.x += x;
+        self.y += y;
+        self.xx += x * x;
+        self.xy += x * y;
+        self.yy += y * y;
+    }
+
+    fn fit(&self) -> Option<PowerLawFit> {
+        if self.n < POWER_LAW_MIN_OBSERVATIONS {
+            return None;
+        }
+        let n = self.n as f64;
+        let sxx = self.xx - self.x * self.x / n;
+        let sxy = self.xy - self.x * self.y / n;
+        let syy = self.yy - self.y * self.y / n;
+        if sxx <= 0.0 {
+            return None;
+        }
+        let slope = sxy / sxx;
+        let intercept = (self.y - slope * self.x) / n;
+        let residual_std = ((syy - slope * sxy).max(0.0) / (n - 2.0)).sqrt();
+        Some(PowerLawFit { intercept, slope, residual_std, observations: self.n })
+    }
+}
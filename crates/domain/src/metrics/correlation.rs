@@ -0,0 +1,174 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::signals::MarketContext;
+
+/// The price series of `market_data`: crypto, commodities, equities and the dollar.
+pub const CORRELATION_SERIES: [MarketSymbol; 7] = [
+    MarketSymbol::BtcUsd,
+    MarketSymbol::EthUsd,
+    MarketSymbol::Gold,
+    MarketSymbol::Oil,
+    MarketSymbol::Sp500,
+    MarketSymbol::Nasdaq,
+    MarketSymbol::UsdIndex,
+];
+
+/// Fewer paired returns than this give no correlation rather than a noisy one.
+pub const MIN_CORRELATION_OBSERVATIONS: usize = 20;
+
+/// Day and the return of each series up to it.
+type PairedReturn = (NaiveDate, f64, f64);
+
+/// Pairwise return correlations of several series over the `window_days` up to `at`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CorrelationMatrix {
+    pub at: NaiveDate,
+    pub window_days: i64,
+    pub symbols: Vec<String>,
+    /// `values[i][j]` correlates `symbols[i]` with `symbols[j]`. `None` with too few paired returns.
+    pub values: Vec<Vec<Option<f64>>>,
+    /// Paired returns behind each value.
+    pub observations: Vec<Vec<usize>>,
+}
+
+/// Correlations of every pair of `symbols` as of `ctx`.
+pub fn correlation_matrix(ctx: &MarketContext, symbols: &[MarketSymbol], window_days: i64) -> CorrelationMatrix {
+    let from = ctx.as_of() - Duration::days(window_days);
+
+    let mut values = vec![vec![None; symbols.len()]; symbols.len()];
+    let mut observations = vec![vec![0; symbols.len()]; symbols.len()];
+    for i in 0..symbols.len() {
+        for j in i..symbols.len() {
+            let window: Vec<_> = paired_returns(ctx.series(&symbols[i]), ctx.series(&symbols[j]))
+                .into_iter()
+                .filter(|(date, _, _)| *date > from)
+                .collect();
+            let correlation = pearson(&window);
+            values[i][j] = correlation;
+            values[j][i] = correlation;
+            observations[i][j] = window.len();
+            observations[j][i] = window.len();
+        }
+    }
+
+    CorrelationMatrix {
+        at: ctx.as_of(),
+        window_days,
+        symbols: symbols.iter().map(|s| s.as_str().to_string()).collect(),
+        values,
+        observations,
+    }
+}
+
+/// Correlation of `a` with `b` over the `window_days` up to each day from `from` on where both
+/// have a close. Days with too few paired returns are left out.
+pub fn rolling_correlation(
+    a: &[(NaiveDate, f64)],
+    b: &[(NaiveDate, f64)],
+    from: NaiveDate,
+    window_days: i64,
+) -> Vec<(NaiveDate, f64)> {
+    rolling(a, b, from, window_days, pearson)
+}
+
+/// Beta of `asset` against `benchmark`, the slope of its returns on the benchmark returns, over
+/// the `window_days` up to each day from `from` on where both have a close.
+pub fn rolling_beta(
+    asset: &[(NaiveDate, f64)],
+    benchmark: &[(NaiveDate, f64)],
+    from: NaiveDate,
+    window_days: i64,
+) -> Vec<(NaiveDate, f64)> {
+    rolling(asset, benchmark, from, window_days, beta)
+}
+
+fn rolling(
+    a: &[(NaiveDate, f64)],
+    b: &[(NaiveDate, f64)],
+    from: NaiveDate,
+    window_days: i64,
+    statistic: fn(&[PairedReturn]) -> Option<f64>,
+) -> Vec<(NaiveDate, f64)> {
+    let pairs = paired_returns(a, b);
+    let mut start = 0;
+    let mut out = Vec::new();
+
+    for end in 0..pairs.len() {
+        let date = pairs[end].0;
+        while pairs[start].0 <= date - Duration::days(window_days) {
+            start += 1;
+        }
+        if date < from {
+            continue;
+        }
+        if let Some(value) = statistic(&pairs[start..=end]) {
+            out.push((date, value));
+        }
+    }
+    out
+}
+
+/// Simple returns of both series between consecutive days on which both have a close, dated by
+/// the later day. A weekday-only market is thereby compared with the crypto return over the same
+/// weekend.
+fn paired_returns(a: &[(NaiveDate, f64)], b: &[(NaiveDate, f64)]) -> Vec<PairedReturn> {
+    let mut common = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common.push((a[i].0, a[i].1, b[j].1));
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    common
+        .windows(2)
+        .filter(|w| w[0].1 != 0.0 && w[0].2 != 0.0)
+        .map(|w| (w[1].0, w[1].1 / w[0].1 - 1.0, w[1].2 / w[0].2 - 1.0))
+        .collect()
+}
+
+/// Pearson correlation of the paired returns. `None` with too few of them or when one side does
+/// not move at all.
+fn pearson(pairs: &[PairedReturn]) -> Option<f64> {
+    let (cov, var_a, var_b) = co_moments(pairs)?;
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+    Some((cov / (var_a * var_b).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Covariance of the paired returns over the variance of the second side. `None` with too few of
+/// them or when the second side does not move at all.
+fn beta(pairs: &[PairedReturn]) -> Option<f64> {
+    let (cov, _, var_b) = co_moments(pairs)?;
+    if var_b == 0.0 {
+        return None;
+    }
+    Some(cov / var_b)
+}
+
+/// Sums of the co-deviations and squared deviations from the means, enough observations given.
+fn co_moments(pairs: &[PairedReturn]) -> Option<(f64, f64, f64)> {
+    if pairs.len() < MIN_CORRELATION_OBSERVATIONS {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_a = pairs.iter().map(|(_, a, _)| a).sum::<f64>() / n;
+    let mean_b = pairs.iter().map(|(_, _, b)| b).sum::<f64>() / n;
+
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (_, a, b) in pairs {
+        cov += (a - mean_a) * (b - mean_b);
+        var_a += (a - mean_a).powi(2);
+        var_b += (b - mean_b).powi(2);
+    }
+    Some((cov, var_a, var_b))
+}
@@ -0,0 +1,302 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::performance::DAYS_PER_YEAR;
+
+/// Smoothed indicators (EMA, RSI, ATR) depend on all history before them. After this many
+/// periods the weight left on what came earlier is below 1e-8, which is what makes recomputing
+/// them from [`TechnicalIndicator::warmup`] observations back match a recomputation from the start.
+const SMOOTHING_WARMUP_PERIODS: usize = 20;
+
+/// Indicators the worker keeps up to date for every price series.
+pub const DEFAULT_INDICATORS: [TechnicalIndicator; 10] = [
+    TechnicalIndicator::Sma { window: 20 },
+    TechnicalIndicator::Sma { window: 50 },
+    TechnicalIndicator::Sma { window: 200 },
+    TechnicalIndicator::Ema { period: 12 },
+    TechnicalIndicator::Ema { period: 26 },
+    TechnicalIndicator::Rsi { period: 14 },
+    TechnicalIndicator::Macd { fast: 12, slow: 26, signal: 9 },
+    TechnicalIndicator::Bollinger { window: 20, width: 2.0 },
+    TechnicalIndicator::Atr { period: 14 },
+    TechnicalIndicator::Volatility { window: 30 },
+];
+
+/// Stored as `category` of the `indicators` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorCategory {
+    Trend,
+    Momentum,
+    Volatility,
+}
+
+impl IndicatorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndicatorCategory::Trend => "trend",
+            IndicatorCategory::Momentum => "momentum",
+            IndicatorCategory::Volatility => "volatility",
+        }
+    }
+}
+
+/// A technical indicator over a series of daily closes. Windows and periods count observations,
+/// not calendar days.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TechnicalIndicator {
+    Sma { window: usize },
+    Ema { period: usize },
+    /// Wilder's relative strength index, 0 to 100.
+    Rsi { period: usize },
+    /// Stored as three series: the MACD line, its signal line and the histogram between them.
+    Macd { fast: usize, slow: usize, signal: usize },
+    /// Stored as upper, middle and lower band, `width` standard deviations around the SMA.
+    Bollinger { window: usize, width: f64 },
+    /// Average true range. `market_data` only has closes, so the true range is the absolute
+    /// change from the previous close.
+    Atr { period: usize },
+    /// Annualized standard deviation of the simple returns over `window` returns.
+    Volatility { window: usize },
+}
+
+/// One output series of an indicator, named after the series it was computed over, e.g.
+/// `BTC_USD_RSI_14`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorSeries {
+    pub name: String,
+    pub category: IndicatorCategory,
+    pub values: Vec<(NaiveDate, f64)>,
+}
+
+impl TechnicalIndicator {
+    pub fn category(&self) -> IndicatorCategory {
+        match self {
+            Self::Sma { .. } | Self::Ema { .. } => IndicatorCategory::Trend,
+            Self::Rsi { .. } | Self::Macd { .. } => IndicatorCategory::Momentum,
+            Self::Bollinger { .. } | Self::Atr { .. } | Self::Volatility { .. } => IndicatorCategory::Volatility,
+        }
+    }
+
+    /// Observations needed before a date for the value on that date to come out the same as when
+    /// computed over the whole history.
+    pub fn warmup(&self) -> usize {
+        match *self {
+            Self::Sma { window } | Self::Bollinger { window, .. } => window.saturating_sub(1),
+            Self::Volatility { window } => window,
+            Self::Ema { period } | Self::Rsi { period } | Self::Atr { period } => {
+                period * (SMOOTHING_WARMUP_PERIODS + 1)
+            }
+            Self::Macd { slow, signal, .. } => slow * (SMOOTHING_WARMUP_PERIODS + 1) + signal,
+        }
+    }
+
+    /// Names of the output series over the series named `prefix`, in the order of [`Self::compute`].
+    pub fn series_names(&self, prefix: &str) -> Vec<String> {
+        let labels = match *self {
+            Self::Sma { window } => vec![format!("SMA_{window}")],
+            Self::Ema { period } => vec![format!("EMA_{period}")],
+            Self::Rsi { period } => vec![format!("RSI_{period}")],
+            Self::Macd { fast, slow, signal } => ["MACD", "MACD_SIGNAL", "MACD_HIST"]
+                .iter()
+                .map(|line| format!("{line}_{fast}_{slow}_{signal}"))
+                .collect(),
+            Self::Bollinger { window, width } => ["BB_UPPER", "BB_MIDDLE", "BB_LOWER"]
+                .iter()
+                .map(|band| format!("{band}_{window}_{width}"))
+                .collect(),
+            Self::Atr { period } => vec![format!("ATR_{period}")],
+            Self::Volatility { window } => vec![format!("VOL_{window}")],
+        };
+        labels.into_iter().map(|label| format!("{prefix}_{label}")).collect()
+    }
+
+    /// Output series over the date-sorted closes of the series named `prefix`. Values start once
+    /// there is enough history; an empty or too short series gives empty outputs.
+    pub fn compute(&self, prefix: &str, series: &[(NaiveDate, f64)]) -> Vec<IndicatorSeries> {
+        let outputs = match *self {
+            Self::Sma { window } => vec![sma(series, window)],
+            Self::Ema { period } => vec![ema(series, period)],
+            Self::Rsi { period } => vec![rsi(series, period)],
+            Self::Macd { fast, slow, signal } => {
+                let points = macd(series, fast, slow, signal);
+                vec![
+                    points.iter().map(|p| (p.date, p.macd)).collect(),
+                    points.iter().map(|p| (p.date, p.signal)).collect(),
+                    points.iter().map(|p| (p.date, p.histogram)).collect(),
+                ]
+            }
+            Self::Bollinger { window, width } => {
+                let bands = bollinger_bands(series, window, width);
+                vec![
+                    bands.iter().map(|b| (b.date, b.upper)).collect(),
+                    bands.iter().map(|b| (b.date, b.middle)).collect(),
+                    bands.iter().map(|b| (b.date, b.lower)).collect(),
+                ]
+            }
+            Self::Atr { period } => vec![atr(series, period)],
+            Self::Volatility { window } => vec![rolling_volatility(series, window)],
+        };
+        self.series_names(prefix)
+            .into_iter()
+            .zip(outputs)
+            .map(|(name, values)| IndicatorSeries { name, category: self.category(), values })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdPoint {
+    pub date: NaiveDate,
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerPoint {
+    pub date: NaiveDate,
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// Simple moving average, from the `window`-th observation on.
+pub fn sma(series: &[(NaiveDate, f64)], window: usize) -> Vec<(NaiveDate, f64)> {
+    if window == 0 {
+        return Vec::new();
+    }
+    series
+        .windows(window)
+        .map(|w| (w[window - 1].0, w.iter().map(|(_, v)| v).sum::<f64>() / window as f64))
+        .collect()
+}
+
+/// Exponential moving average seeded with the SMA of the first `period` observations.
+pub fn ema(series: &[(NaiveDate, f64)], period: usize) -> Vec<(NaiveDate, f64)> {
+    let values: Vec<f64> = series.iter().map(|(_, v)| *v).collect();
+    dated(series, period, ema_values(&values, period))
+}
+
+/// Wilder's RSI: average gains against average losses of the last `period` changes.
+pub fn rsi(series: &[(NaiveDate, f64)], period: usize) -> Vec<(NaiveDate, f64)> {
+    let changes: Vec<f64> = series.windows(2).map(|w| w[1].1 - w[0].1).collect();
+    let gains = wilder(&changes.iter().map(|c| c.max(0.0)).collect::<Vec<_>>(), period);
+    let losses = wilder(&changes.iter().map(|c| (-c).max(0.0)).collect::<Vec<_>>(), period);
+
+    let values = gains
+        .into_iter()
+        .zip(losses)
+        .map(|(gain, loss)| {
+            if loss == 0.0 {
+                // A flat stretch is neutral, one without losses as strong as it gets.
+                if gain == 0.0 { 50.0 } else { 100.0 }
+            } else {
+                100.0 - 100.0 / (1.0 + gain / loss)
+            }
+        })
+        .collect();
+    dated(series, period + 1, values)
+}
+
+/// MACD line (fast EMA minus slow EMA), its `signal`-period EMA and the difference of the two.
+pub fn macd(series: &[(NaiveDate, f64)], fast: usize, slow: usize, signal: usize) -> Vec<MacdPoint> {
+    if fast == 0 || fast >= slow || series.len() < slow {
+        return Vec::new();
+    }
+    let fast_ema = ema(series, fast);
+    let slow_ema = ema(series, slow);
+    // Both end on the last observation; the fast one starts `slow - fast` observations earlier.
+    let line: Vec<(NaiveDate, f64)> = slow_ema
+        .iter()
+        .zip(&fast_ema[slow - fast..])
+        .map(|((date, slow), (_, fast))| (*date, fast - slow))
+        .collect();
+    let signal_line = ema(&line, signal);
+
+    line[line.len().saturating_sub(signal_line.len())..]
+        .iter()
+        .zip(signal_line)
+        .map(|((date, macd), (_, signal))| MacdPoint { date: *date, macd: *macd, signal, histogram: macd - signal })
+        .collect()
+}
+
+/// SMA with bands `width` population standard deviations above and below it.
+pub fn bollinger_bands(series: &[(NaiveDate, f64)], window: usize, width: f64) -> Vec<BollingerPoint> {
+    if window == 0 {
+        return Vec::new();
+    }
+    series
+        .windows(window)
+        .map(|w| {
+            let middle = w.iter().map(|(_, v)| v).sum::<f64>() / window as f64;
+            let deviation = (w.iter().map(|(_, v)| (v - middle).powi(2)).sum::<f64>() / window as f64).sqrt();
+            BollingerPoint {
+                date: w[window - 1].0,
+                upper: middle + width * deviation,
+                middle,
+                lower: middle - width * deviation,
+            }
+        })
+        .collect()
+}
+
+/// Wilder-smoothed absolute change between consecutive closes, see [`TechnicalIndicator::Atr`].
+pub fn atr(series: &[(NaiveDate, f64)], period: usize) -> Vec<(NaiveDate, f64)> {
+    let ranges: Vec<f64> = series.windows(2).map(|w| (w[1].1 - w[0].1).abs()).collect();
+    dated(series, period + 1, wilder(&ranges, period))
+}
+
+/// Sample standard deviation of the last `window` simple returns, annualized by how many
+/// observations a year the series has, so markets closed on weekends are not understated.
+pub fn rolling_volatility(series: &[(NaiveDate, f64)], window: usize) -> Vec<(NaiveDate, f64)> {
+    if window < 2 {
+        return Vec::new();
+    }
+    series
+        .windows(window + 1)
+        .filter_map(|w| {
+            let returns: Vec<f64> = w.windows(2).filter(|p| p[0].1 != 0.0).map(|p| p[1].1 / p[0].1 - 1.0).collect();
+            let days = (w[window].0 - w[0].0).num_days();
+            if returns.len() < 2 || days <= 0 {
+                return None;
+            }
+            let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+            let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+            let per_year = window as f64 * DAYS_PER_YEAR / days as f64;
+            Some((w[window].0, (variance * per_year).sqrt()))
+        })
+        .collect()
+}
+
+fn ema_values(values: &[f64], period: usize) -> Vec<f64> {
+    smoothed(values, period, 2.0 / (period as f64 + 1.0))
+}
+
+fn wilder(values: &[f64], period: usize) -> Vec<f64> {
+    smoothed(values, period, 1.0 / period as f64)
+}
+
+/// Exponential smoothing with factor `alpha`, seeded with the mean of the first `period` values.
+fn smoothed(values: &[f64], period: usize, alpha: f64) -> Vec<f64> {
+    if period == 0 || values.len() < period {
+        return Vec::new();
+    }
+    let seed = values[..period].iter().sum::<f64>() / period as f64;
+    let mut out = Vec::with_capacity(values.len() - period + 1);
+    out.push(seed);
+    for value in &values[period..] {
+        let previous = out[out.len() - 1];
+        out.push(previous + alpha * (value - previous));
+    }
+    out
+}
+
+/// Dates `values` with the observations they end on, the first one being the `first`-th.
+fn dated(series: &[(NaiveDate, f64)], first: usize, values: Vec<f64>) -> Vec<(NaiveDate, f64)> {
+    if first == 0 {
+        return Vec::new();
+    }
+    series.iter().skip(first - 1).map(|(date, _)| *date).zip(values).collect()
+}
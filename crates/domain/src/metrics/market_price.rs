@@ -36,12 +36,43 @@ pub enum MarketSymbol {
     BtcReturn7d,
     BtcReturn30d,
     BtcReturn90d,
+    BtcMayerMultiple,
+    Btc200WeekMa,
+    Btc200WeekMaChange,
+    BtcPiCycleRatio,
+    BtcPiCycleTop,
+    BtcPowerLawFairValue,
+    BtcPowerLawLower,
+    BtcPowerLawUpper,
+    BtcPowerLawDeviation,
+    BtcPowerLawSlope,
+    BtcPowerLawIntercept,
+    BtcVolatility30d,
+    BtcVolatility90d,
+    BtcVolatility365d,
+    BtcBetaSp500,
+    BtcBetaNasdaq,
+    EthVolatility30d,
+    EthVolatility90d,
+    EthVolatility365d,
+    EthBetaSp500,
+    EthBetaNasdaq,
+    EthBtcRatio,
+    EthBtcStrength30d,
+    EthBtcStrength90d,
     GlobalTotalMarketCapUsd,
     GlobalTotalStableCapUsd,
     GlobalTotalBtcCapUsd,
     GlobalTotalEthCapUsd,
     GlobalTotalVolume24hUsd,
     FearGreedIndex,
+    MacroRegime,            // Regime code, see `MacroRegime::code`
+    MacroRegimeScore,
+    MacroYieldCurveScore,
+    MacroPolicyScore,
+    MacroDollarScore,
+    MacroInflationScore,
+    MacroLiquidityScore,
 }
 
 impl MarketSymbol {
@@ -83,12 +114,43 @@ impl MarketSymbol {
             MarketSymbol::BtcReturn7d => "BTC_RETURN_7D",
             MarketSymbol::BtcReturn30d => "BTC_RETURN_30D",
             MarketSymbol::BtcReturn90d => "BTC_RETURN_90D",
+            MarketSymbol::BtcMayerMultiple => "BTC_MAYER_MULTIPLE",
+            MarketSymbol::Btc200WeekMa => "BTC_200W_MA",
+            MarketSymbol::Btc200WeekMaChange => "BTC_200W_MA_CHANGE_4W",
+            MarketSymbol::BtcPiCycleRatio => "BTC_PI_CYCLE_RATIO",
+            MarketSymbol::BtcPiCycleTop => "BTC_PI_CYCLE_TOP",
+            MarketSymbol::BtcPowerLawFairValue => "BTC_POWER_LAW_FAIR_VALUE",
+            MarketSymbol::BtcPowerLawLower => "BTC_POWER_LAW_LOWER",
+            MarketSymbol::BtcPowerLawUpper => "BTC_POWER_LAW_UPPER",
+            MarketSymbol::BtcPowerLawDeviation => "BTC_POWER_LAW_DEVIATION",
+            MarketSymbol::BtcPowerLawSlope => "BTC_POWER_LAW_SLOPE",
+            MarketSymbol::BtcPowerLawIntercept => "BTC_POWER_LAW_INTERCEPT",
+            MarketSymbol::BtcVolatility30d => "BTC_VOLATILITY_30D",
+            MarketSymbol::BtcVolatility90d => "BTC_VOLATILITY_90D",
+            MarketSymbol::BtcVolatility365d => "BTC_VOLATILITY_365D",
+            MarketSymbol::BtcBetaSp500 => "BTC_BETA_SP500_90D",
+            MarketSymbol::BtcBetaNasdaq => "BTC_BETA_NASDAQ_90D",
+            MarketSymbol::EthVolatility30d => "ETH_VOLATILITY_30D",
+            MarketSymbol::EthVolatility90d => "ETH_VOLATILITY_90D",
+            MarketSymbol::EthVolatility365d => "ETH_VOLATILITY_365D",
+            MarketSymbol::EthBetaSp500 => "ETH_BETA_SP500_90D",
+            MarketSymbol::EthBetaNasdaq => "ETH_BETA_NASDAQ_90D",
+            MarketSymbol::EthBtcRatio => "ETH_BTC_RATIO",
+            MarketSymbol::EthBtcStrength30d => "ETH_BTC_STRENGTH_30D",
+            MarketSymbol::EthBtcStrength90d => "ETH_BTC_STRENGTH_90D",
             MarketSymbol::GlobalTotalMarketCapUsd => "GLOBAL_TOTAL_MARKET_CAP_USD",
             MarketSymbol::GlobalTotalStableCapUsd => "GLOBAL_TOTAL_STABLE_CAP_USD",
             MarketSymbol::GlobalTotalBtcCapUsd => "GLOBAL_TOTAL_BTC_CAP_USD",
             MarketSymbol::GlobalTotalEthCapUsd => "GLOBAL_TOTAL_ETH_CAP_USD",
             MarketSymbol::GlobalTotalVolume24hUsd => "GLOBAL_TOTAL_VOLUME_24H_USD",
             MarketSymbol::FearGreedIndex => "FEAR_GREED_INDEX",
+            MarketSymbol::MacroRegime => "MACRO_REGIME",
+            MarketSymbol::MacroRegimeScore => "MACRO_REGIME_SCORE",
+            MarketSymbol::MacroYieldCurveScore => "MACRO_YIELD_CURVE_SCORE",
+            MarketSymbol::MacroPolicyScore => "MACRO_POLICY_SCORE",
+            MarketSymbol::MacroDollarScore => "MACRO_DOLLAR_SCORE",
+            MarketSymbol::MacroInflationScore => "MACRO_INFLATION_SCORE",
+            MarketSymbol::MacroLiquidityScore => "MACRO_LIQUIDITY_SCORE",
         }
     }
 
@@ -96,7 +158,51 @@ impl MarketSymbol {
         matches!(self, MarketSymbol::BtcUsd)
     }
 
-    pub fn btc_metrics() -> [MarketSymbol; 6] {
+    /// Series stored as closes in `market_data` rather than in `market_metrics`.
+    pub fn is_price_series(&self) -> bool {
+        matches!(
+            self,
+            MarketSymbol::BtcUsd
+                | MarketSymbol::EthUsd
+                | MarketSymbol::Gold
+                | MarketSymbol::Oil
+                | MarketSymbol::Sp500
+                | MarketSymbol::Nasdaq
+                | MarketSymbol::UsdIndex
+        )
+    }
+
+    /// Ledger asset priced by this series (`BTC` for `BTC_USD`); the inverse of `price_symbol`.
+    pub fn ledger_asset(&self) -> Option<&'static str> {
+        if !self.is_price_series() {
+            return None;
+        }
+        self.as_str().strip_suffix("_USD")
+    }
+
+    /// Prices and caps quoted in USD. Rates, ratios, percentages and indices are not.
+    pub fn is_usd_denominated(&self) -> bool {
+        matches!(
+            self,
+            MarketSymbol::BtcUsd
+                | MarketSymbol::EthUsd
+                | MarketSymbol::Gold
+                | MarketSymbol::Oil
+                | MarketSymbol::Sp500
+                | MarketSymbol::Nasdaq
+                | MarketSymbol::Btc200WeekMa
+                | MarketSymbol::BtcPowerLawFairValue
+                | MarketSymbol::BtcPowerLawLower
+                | MarketSymbol::BtcPowerLawUpper
+                | MarketSymbol::GlobalTotalMarketCapUsd
+                | MarketSymbol::GlobalTotalStableCapUsd
+                | MarketSymbol::GlobalTotalBtcCapUsd
+                | MarketSymbol::GlobalTotalEthCapUsd
+                | MarketSymbol::GlobalTotalVolume24hUsd
+        )
+    }
+
+    pub fn btc_metrics() -> [MarketSymbol; 22] {
         [
             MarketSymbol::BtcDominance,
             MarketSymbol::BtcStableRatio,
@@ -104,13 +210,37 @@ impl MarketSymbol {
             MarketSymbol::BtcReturn7d,
             MarketSymbol::BtcReturn30d,
             MarketSymbol::BtcReturn90d,
+            MarketSymbol::BtcMayerMultiple,
+            MarketSymbol::Btc200WeekMa,
+            MarketSymbol::Btc200WeekMaChange,
+            MarketSymbol::BtcPiCycleRatio,
+            MarketSymbol::BtcPiCycleTop,
+            MarketSymbol::BtcPowerLawFairValue,
+            MarketSymbol::BtcPowerLawLower,
+            MarketSymbol::BtcPowerLawUpper,
+            MarketSymbol::BtcPowerLawDeviation,
+            MarketSymbol::BtcPowerLawSlope,
+            MarketSymbol::BtcPowerLawIntercept,
+            MarketSymbol::BtcVolatility30d,
+            MarketSymbol::BtcVolatility90d,
+            MarketSymbol::BtcVolatility365d,
+            MarketSymbol::BtcBetaSp500,
+            MarketSymbol::BtcBetaNasdaq,
         ]
     }
 
-    pub fn eth_metrics() -> [MarketSymbol; 2] {
+    pub fn eth_metrics() -> [MarketSymbol; 10] {
         [
             MarketSymbol::EthDominance,
             MarketSymbol::GlobalTotalEthCapUsd,
+            MarketSymbol::EthVolatility30d,
+            MarketSymbol::EthVolatility90d,
+            MarketSymbol::EthVolatility365d,
+            MarketSymbol::EthBetaSp500,
+            MarketSymbol::EthBetaNasdaq,
+            MarketSymbol::EthBtcRatio,
+            MarketSymbol::EthBtcStrength30d,
+            MarketSymbol::EthBtcStrength90d,
         ]
     }
 
@@ -133,6 +263,19 @@ impl MarketSymbol {
         ]
     }
 
+    /// The stored regime followed by its score and sub-scores.
+    pub fn macro_regime_metrics() -> [MarketSymbol; 7] {
+        [
+            MarketSymbol::MacroRegime,
+            MarketSymbol::MacroRegimeScore,
+            MarketSymbol::MacroYieldCurveScore,
+            MarketSymbol::MacroPolicyScore,
+            MarketSymbol::MacroDollarScore,
+            MarketSymbol::MacroInflationScore,
+            MarketSymbol::MacroLiquidityScore,
+        ]
+    }
+
     pub fn fred_metrics() -> [MarketSymbol; 9] {
         [
             MarketSymbol::DFF,
@@ -184,6 +327,34 @@ impl MarketSymbol {
             MarketSymbol::BtcReturn30d => "Bitcoin 30-Day Return (%)",
             MarketSymbol::BtcReturn90d => "Bitcoin 90-Day Return (%)",
 
+            // Bitcoin cycle
+            MarketSymbol::BtcMayerMultiple => "Bitcoin Mayer Multiple",
+            MarketSymbol::Btc200WeekMa => "Bitcoin 200-Week Moving Average",
+            MarketSymbol::Btc200WeekMaChange => "Bitcoin 200-Week MA 4-Week Change (%)",
+            MarketSymbol::BtcPiCycleRatio => "Bitcoin Pi Cycle Ratio (111DMA / 2×350DMA)",
+            MarketSymbol::BtcPiCycleTop => "Bitcoin Pi Cycle Top Crossover",
+            MarketSymbol::BtcPowerLawFairValue => "Bitcoin Power Law Fair Value",
+            MarketSymbol::BtcPowerLawLower => "Bitcoin Power Law Lower Band",
+            MarketSymbol::BtcPowerLawUpper => "Bitcoin Power Law Upper Band",
+            MarketSymbol::BtcPowerLawDeviation => "Bitcoin Power Law Deviation (σ)",
+            MarketSymbol::BtcPowerLawSlope => "Bitcoin Power Law Slope",
+            MarketSymbol::BtcPowerLawIntercept => "Bitcoin Power Law Intercept",
+
+            // Risk
+            MarketSymbol::BtcVolatility30d => "Bitcoin 30-Day Volatility (annualized %)",
+            MarketSymbol::BtcVolatility90d => "Bitcoin 90-Day Volatility (annualized %)",
+            MarketSymbol::BtcVolatility365d => "Bitcoin 365-Day Volatility (annualized %)",
+            MarketSymbol::BtcBetaSp500 => "Bitcoin 90-Day Beta vs S&P 500",
+            MarketSymbol::BtcBetaNasdaq => "Bitcoin 90-Day Beta vs NASDAQ",
+            MarketSymbol::EthVolatility30d => "Ethereum 30-Day Volatility (annualized %)",
+            MarketSymbol::EthVolatility90d => "Ethereum 90-Day Volatility (annualized %)",
+            MarketSymbol::EthVolatility365d => "Ethereum 365-Day Volatility (annualized %)",
+            MarketSymbol::EthBetaSp500 => "Ethereum 90-Day Beta vs S&P 500",
+            MarketSymbol::EthBetaNasdaq => "Ethereum 90-Day Beta vs NASDAQ",
+            MarketSymbol::EthBtcRatio => "ETH/BTC Ratio",
+            MarketSymbol::EthBtcStrength30d => "ETH/BTC 30-Day Relative Strength (%)",
+            MarketSymbol::EthBtcStrength90d => "ETH/BTC 90-Day Relative Strength (%)",
+
             // Global metrics
             MarketSymbol::GlobalTotalMarketCapUsd => "Global Crypto Market Cap (USD)",
             MarketSymbol::GlobalTotalStableCapUsd => "Global Stablecoin Market Cap (USD)",
@@ -191,6 +362,15 @@ impl MarketSymbol {
             MarketSymbol::GlobalTotalEthCapUsd => "Total Ethereum Market Cap (USD)",
             MarketSymbol::GlobalTotalVolume24hUsd => "Global 24H Trading Volume (USD)",
             MarketSymbol::FearGreedIndex => "Crypto Fear & Greed Index",
+
+            // Macro regime
+            MarketSymbol::MacroRegime => "Macro Regime",
+            MarketSymbol::MacroRegimeScore => "Macro Regime Score",
+            MarketSymbol::MacroYieldCurveScore => "Yield Curve Score",
+            MarketSymbol::MacroPolicyScore => "Monetary Policy Score",
+            MarketSymbol::MacroDollarScore => "U.S. Dollar Score",
+            MarketSymbol::MacroInflationScore => "Inflation Score",
+            MarketSymbol::MacroLiquidityScore => "Liquidity (M2) Score",
         }
     }
 }
@@ -235,12 +415,43 @@ impl FromStr for MarketSymbol {
             "BTC_RETURN_7D" => Ok(MarketSymbol::BtcReturn7d),
             "BTC_RETURN_30D" => Ok(MarketSymbol::BtcReturn30d),
             "BTC_RETURN_90D" => Ok(MarketSymbol::BtcReturn90d),
+            "BTC_MAYER_MULTIPLE" => Ok(MarketSymbol::BtcMayerMultiple),
+            "BTC_200W_MA" => Ok(MarketSymbol::Btc200WeekMa),
+            "BTC_200W_MA_CHANGE_4W" => Ok(MarketSymbol::Btc200WeekMaChange),
+            "BTC_PI_CYCLE_RATIO" => Ok(MarketSymbol::BtcPiCycleRatio),
+            "BTC_PI_CYCLE_TOP" => Ok(MarketSymbol::BtcPiCycleTop),
+            "BTC_POWER_LAW_FAIR_VALUE" => Ok(MarketSymbol::BtcPowerLawFairValue),
+            "BTC_POWER_LAW_LOWER" => Ok(MarketSymbol::BtcPowerLawLower),
+            "BTC_POWER_LAW_UPPER" => Ok(MarketSymbol::BtcPowerLawUpper),
+            "BTC_POWER_LAW_DEVIATION" => Ok(MarketSymbol::BtcPowerLawDeviation),
+            "BTC_POWER_LAW_SLOPE" => Ok(MarketSymbol::BtcPowerLawSlope),
+            "BTC_POWER_LAW_INTERCEPT" => Ok(MarketSymbol::BtcPowerLawIntercept),
+            "BTC_VOLATILITY_30D" => Ok(MarketSymbol::BtcVolatility30d),
+            "BTC_VOLATILITY_90D" => Ok(MarketSymbol::BtcVolatility90d),
+            "BTC_VOLATILITY_365D" => Ok(MarketSymbol::BtcVolatility365d),
+            "BTC_BETA_SP500_90D" => Ok(MarketSymbol::BtcBetaSp500),
+            "BTC_BETA_NASDAQ_90D" => Ok(MarketSymbol::BtcBetaNasdaq),
+            "ETH_VOLATILITY_30D" => Ok(MarketSymbol::EthVolatility30d),
+            "ETH_VOLATILITY_90D" => Ok(MarketSymbol::EthVolatility90d),
+            "ETH_VOLATILITY_365D" => Ok(MarketSymbol::EthVolatility365d),
+            "ETH_BETA_SP500_90D" => Ok(MarketSymbol::EthBetaSp500),
+            "ETH_BETA_NASDAQ_90D" => Ok(MarketSymbol::EthBetaNasdaq),
+            "ETH_BTC_RATIO" => Ok(MarketSymbol::EthBtcRatio),
+            "ETH_BTC_STRENGTH_30D" => Ok(MarketSymbol::EthBtcStrength30d),
+            "ETH_BTC_STRENGTH_90D" => Ok(MarketSymbol::EthBtcStrength90d),
             "GLOBAL_TOTAL_MARKET_CAP_USD" => Ok(MarketSymbol::GlobalTotalMarketCapUsd),
             "GLOBAL_TOTAL_STABLE_CAP_USD" => Ok(MarketSymbol::GlobalTotalStableCapUsd),
             "GLOBAL_TOTAL_BTC_CAP_USD" => Ok(MarketSymbol::GlobalTotalBtcCapUsd),
             "GLOBAL_TOTAL_ETH_CAP_USD" => Ok(MarketSymbol::GlobalTotalEthCapUsd),
             "GLOBAL_TOTAL_VOLUME_24H_USD" => Ok(MarketSymbol::GlobalTotalVolume24hUsd),
             "FEAR_GREED_INDEX" => Ok(MarketSymbol::FearGreedIndex),
+            "MACRO_REGIME" => Ok(MarketSymbol::MacroRegime),
+            "MACRO_REGIME_SCORE" => Ok(MarketSymbol::MacroRegimeScore),
+            "MACRO_YIELD_CURVE_SCORE" => Ok(MarketSymbol::MacroYieldCurveScore),
+            "MACRO_POLICY_SCORE" => Ok(MarketSymbol::MacroPolicyScore),
+            "MACRO_DOLLAR_SCORE" => Ok(MarketSymbol::MacroDollarScore),
+            "MACRO_INFLATION_SCORE" => Ok(MarketSymbol::MacroInflationScore),
+            "MACRO_LIQUIDITY_SCORE" => Ok(MarketSymbol::MacroLiquidityScore),
             other => Err(ParseMarketSymbolError(other.to_string())),
         }
     }
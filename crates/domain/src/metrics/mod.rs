@@ -1,6 +1,13 @@
 pub mod advanced_metrics;
+pub mod btc_cycle;
+pub mod correlation;
 pub mod utils;
 pub mod fear_greed;
 pub mod fred;
+pub mod indicators;
 pub mod market_price;
-pub mod global_crypto;
\ No newline at end of file
+pub mod global_crypto;
+pub mod regime;
+pub mod risk;
+
+pub mod tests;
@@ -0,0 +1,242 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::nav::value_as_of;
+use crate::portfolio::signals::{MarketContext, MarketDataSet};
+
+/// Series the classifier reads. `FEDFUNDS` stands in for `DFF` when the daily rate is missing.
+pub const MACRO_REGIME_INPUTS: [MarketSymbol; 6] = [
+    MarketSymbol::T10Y2Y,
+    MarketSymbol::DFF,
+    MarketSymbol::FEDFUNDS,
+    MarketSymbol::UsdIndex,
+    MarketSymbol::CPIAUCSL,
+    MarketSymbol::M2SL,
+];
+
+/// Inputs older than this are ignored. Monthly FRED series are published some six weeks after
+/// the month they describe, so they are usually 45 to 75 days old.
+pub const REGIME_MAX_STALENESS_DAYS: i64 = 100;
+
+/// Spread in percentage points at which the curve counts as fully steep (or fully inverted).
+const YIELD_CURVE_FULL_AT: f64 = 1.0;
+/// Change of the policy rate over the policy window, in percentage points, that saturates.
+const POLICY_FULL_AT: f64 = 1.0;
+const POLICY_WINDOW_DAYS: i64 = 182;
+/// Change of the dollar index over the dollar window, in percent, that saturates.
+const DOLLAR_FULL_AT: f64 = 5.0;
+const DOLLAR_WINDOW_DAYS: i64 = 91;
+/// Year-over-year CPI that neither helps nor hurts, and the distance from it that saturates.
+const INFLATION_NEUTRAL: f64 = 2.5;
+const INFLATION_FULL_AT: f64 = 2.5;
+/// Year-over-year M2 growth that neither helps nor hurts, and the distance from it that saturates.
+const LIQUIDITY_NEUTRAL: f64 = 3.0;
+const LIQUIDITY_FULL_AT: f64 = 5.0;
+
+/// Where the macro backdrop stands for risk assets. Policy and liquidity (what the central bank
+/// does) are read against the yield curve, the dollar and inflation (how conditions look):
+///
+/// | monetary \ conditions | supportive | hostile     |
+/// |-----------------------|------------|-------------|
+/// | supportive            | risk-on    | easing      |
+/// | hostile               | tightening | risk-off    |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MacroRegime {
+    RiskOn,
+    /// Policy turned supportive while conditions are still poor, typically early in a cycle.
+    Easing,
+    /// Policy turned against risk while conditions still look fine, typically late in a cycle.
+    Tightening,
+    RiskOff,
+}
+
+impl MacroRegime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MacroRegime::RiskOn => "RISK_ON",
+            MacroRegime::Easing => "EASING",
+            MacroRegime::Tightening => "TIGHTENING",
+            MacroRegime::RiskOff => "RISK_OFF",
+        }
+    }
+
+    pub fn formatted_name(&self) -> &'static str {
+        match self {
+            MacroRegime::RiskOn => "Risk-on",
+            MacroRegime::Easing => "Easing",
+            MacroRegime::Tightening => "Tightening",
+            MacroRegime::RiskOff => "Risk-off",
+        }
+    }
+
+    /// Value stored under `MACRO_REGIME`, from most (2) to least (-2) supportive of risk.
+    pub fn code(&self) -> f64 {
+        match self {
+            MacroRegime::RiskOn => 2.0,
+            MacroRegime::Easing => 1.0,
+            MacroRegime::Tightening => -1.0,
+            MacroRegime::RiskOff => -2.0,
+        }
+    }
+
+    pub fn from_code(code: f64) -> Option<Self> {
+        [MacroRegime::RiskOn, MacroRegime::Easing, MacroRegime::Tightening, MacroRegime::RiskOff]
+            .into_iter()
+            .find(|regime| regime.code() == code)
+    }
+}
+
+impl FromStr for MacroRegime {
+    type Err = ParseMacroRegimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().replace('-', "_").as_str() {
+            "RISK_ON" => Ok(MacroRegime::RiskOn),
+            "EASING" => Ok(MacroRegime::Easing),
+            "TIGHTENING" => Ok(MacroRegime::Tightening),
+            "RISK_OFF" => Ok(MacroRegime::RiskOff),
+            other => Err(ParseMacroRegimeError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMacroRegimeError(pub String);
+
+impl fmt::Display for ParseMacroRegimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid macro regime: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseMacroRegimeError {}
+
+/// Contributions to the regime, each from -1 (hostile to risk assets) to 1 (supportive). `None`
+/// when the inputs are missing or stale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MacroSubScores {
+    /// 10Y-2Y spread: positive when the curve is steep, negative when inverted.
+    pub yield_curve: Option<f64>,
+    /// Six-month change of the policy rate: positive when cutting, negative when hiking.
+    pub policy: Option<f64>,
+    /// Three-month change of the dollar index: positive when the dollar weakens.
+    pub dollar: Option<f64>,
+    /// CPI year over year against 2.5%: positive below it.
+    pub inflation: Option<f64>,
+    /// M2 growth year over year against 3%: positive above it.
+    pub liquidity: Option<f64>,
+}
+
+impl MacroSubScores {
+    /// What the central bank is doing: policy and liquidity.
+    pub fn monetary(&self) -> Option<f64> {
+        mean(&[self.policy, self.liquidity])
+    }
+
+    /// How conditions look: the yield curve, the dollar and inflation.
+    pub fn conditions(&self) -> Option<f64> {
+        mean(&[self.yield_curve, self.dollar, self.inflation])
+    }
+
+    pub fn overall(&self) -> Option<f64> {
+        mean(&[self.yield_curve, self.policy, self.dollar, self.inflation, self.liquidity])
+    }
+
+    /// Each sub-score with the metric it is stored under.
+    pub fn by_symbol(&self) -> [(MarketSymbol, Option<f64>); 5] {
+        [
+            (MarketSymbol::MacroYieldCurveScore, self.yield_curve),
+            (MarketSymbol::MacroPolicyScore, self.policy),
+            (MarketSymbol::MacroDollarScore, self.dollar),
+            (MarketSymbol::MacroInflationScore, self.inflation),
+            (MarketSymbol::MacroLiquidityScore, self.liquidity),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroRegimeReading {
+    pub date: NaiveDate,
+    pub regime: MacroRegime,
+    /// Mean of the available sub-scores.
+    pub score: f64,
+    pub sub_scores: MacroSubScores,
+}
+
+impl MacroRegimeReading {
+    /// The `market_metrics` values of this day: regime code, score and available sub-scores.
+    pub fn metrics(&self) -> Vec<(MarketSymbol, f64)> {
+        let mut metrics =
+            vec![(MarketSymbol::MacroRegime, self.regime.code()), (MarketSymbol::MacroRegimeScore, self.score)];
+        metrics.extend(self.sub_scores.by_symbol().into_iter().filter_map(|(symbol, v)| v.map(|v| (symbol, v))));
+        metrics
+    }
+}
+
+/// Sub-scores from what was known at `ctx`.
+pub fn macro_sub_scores(ctx: &MarketContext) -> MacroSubScores {
+    let as_of = ctx.as_of();
+    let latest = |symbol: &MarketSymbol| fresh(ctx.series(symbol), as_of);
+    // Latest value and the one `days` before it.
+    let change = |symbol: &MarketSymbol, days: i64| {
+        let series = ctx.series(symbol);
+        let now = fresh(series, as_of)?;
+        let (_, then) = value_as_of(series, as_of - Duration::days(days))?;
+        Some((now, then))
+    };
+    let year_over_year = |symbol: &MarketSymbol| {
+        change(symbol, 365).and_then(|(now, then)| Some((now / then - 1.0) * 100.0).filter(|v| v.is_finite()))
+    };
+
+    let policy_rate = if latest(&MarketSymbol::DFF).is_some() { MarketSymbol::DFF } else { MarketSymbol::FEDFUNDS };
+
+    MacroSubScores {
+        yield_curve: latest(&MarketSymbol::T10Y2Y).map(|spread| scale(spread, YIELD_CURVE_FULL_AT)),
+        policy: change(&policy_rate, POLICY_WINDOW_DAYS).map(|(now, then)| scale(then - now, POLICY_FULL_AT)),
+        dollar: change(&MarketSymbol::UsdIndex, DOLLAR_WINDOW_DAYS)
+            .map(|(now, then)| scale((1.0 - now / then) * 100.0, DOLLAR_FULL_AT))
+            .filter(|v| v.is_finite()),
+        inflation: year_over_year(&MarketSymbol::CPIAUCSL).map(|cpi| scale(INFLATION_NEUTRAL - cpi, INFLATION_FULL_AT)),
+        liquidity: year_over_year(&MarketSymbol::M2SL).map(|m2| scale(m2 - LIQUIDITY_NEUTRAL, LIQUIDITY_FULL_AT)),
+    }
+}
+
+/// The regime at `ctx`, or `None` while either side of the table has no sub-score.
+pub fn classify_regime(ctx: &MarketContext) -> Option<MacroRegimeReading> {
+    let sub_scores = macro_sub_scores(ctx);
+    let regime = match (sub_scores.monetary()? >= 0.0, sub_scores.conditions()? >= 0.0) {
+        (true, true) => MacroRegime::RiskOn,
+        (true, false) => MacroRegime::Easing,
+        (false, true) => MacroRegime::Tightening,
+        (false, false) => MacroRegime::RiskOff,
+    };
+
+    Some(MacroRegimeReading { date: ctx.as_of(), regime, score: sub_scores.overall()?, sub_scores })
+}
+
+/// One reading per calendar day from `from` through `to`, skipping days that cannot be classified.
+pub fn regime_history(data: &MarketDataSet, from: NaiveDate, to: NaiveDate) -> Vec<MacroRegimeReading> {
+    from.iter_days().take_while(|d| *d <= to).filter_map(|d| classify_regime(&data.at(d))).collect()
+}
+
+/// Latest value, unless it is older than [`REGIME_MAX_STALENESS_DAYS`].
+fn fresh(series: &[(NaiveDate, f64)], as_of: NaiveDate) -> Option<f64> {
+    series
+        .last()
+        .filter(|(date, _)| as_of - *date <= Duration::days(REGIME_MAX_STALENESS_DAYS))
+        .map(|(_, v)| *v)
+}
+
+fn scale(value: f64, full_at: f64) -> f64 {
+    (value / full_at).clamp(-1.0, 1.0)
+}
+
+fn mean(scores: &[Option<f64>]) -> Option<f64> {
+    let known: Vec<f64> = scores.iter().flatten().copied().collect();
+    (!known.is_empty()).then(|| known.iter().sum::<f64>() / known.len() as f64)
+}
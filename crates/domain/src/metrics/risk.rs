@@ -0,0 +1,83 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::metrics::correlation::rolling_beta;
+use crate::metrics::indicators::rolling_volatility;
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::nav::value_as_of;
+use crate::portfolio::signals::MarketContext;
+
+/// Returns in each realized volatility window; crypto trades every day, so these are also days.
+pub const VOLATILITY_WINDOWS: [usize; 3] = [30, 90, 365];
+/// Days of paired returns behind each beta.
+pub const BETA_WINDOW_DAYS: i64 = 90;
+/// Lookbacks of the ETH/BTC relative strength, in days.
+pub const RELATIVE_STRENGTH_DAYS: [i64; 2] = [30, 90];
+/// Price series the risk metrics are derived from.
+pub const RISK_INPUTS: [MarketSymbol; 4] =
+    [MarketSymbol::BtcUsd, MarketSymbol::EthUsd, MarketSymbol::Sp500, MarketSymbol::Nasdaq];
+
+/// A derived series and its values, one per day.
+#[derive(Debug, Clone)]
+pub struct DerivedSeries {
+    pub symbol: MarketSymbol,
+    pub values: Vec<(NaiveDate, f64)>,
+}
+
+/// Realized volatility, beta against the stock indices and ETH/BTC relative strength of the days
+/// from `from` on, each from the closes up to that day.
+pub fn risk_series(ctx: &MarketContext, from: NaiveDate) -> Vec<DerivedSeries> {
+    let btc = ctx.series(&MarketSymbol::BtcUsd);
+    let eth = ctx.series(&MarketSymbol::EthUsd);
+    let sp500 = ctx.series(&MarketSymbol::Sp500);
+    let nasdaq = ctx.series(&MarketSymbol::Nasdaq);
+    let since = |values: Vec<(NaiveDate, f64)>| values.into_iter().filter(|(d, _)| *d >= from).collect();
+
+    let mut series = Vec::new();
+    for (closes, symbols) in [
+        (btc, [MarketSymbol::BtcVolatility30d, MarketSymbol::BtcVolatility90d, MarketSymbol::BtcVolatility365d]),
+        (eth, [MarketSymbol::EthVolatility30d, MarketSymbol::EthVolatility90d, MarketSymbol::EthVolatility365d]),
+    ] {
+        for (window, symbol) in VOLATILITY_WINDOWS.into_iter().zip(symbols) {
+            let percent = rolling_volatility(closes, window).into_iter().map(|(d, v)| (d, v * 100.0)).collect();
+            series.push(DerivedSeries { symbol, values: since(percent) });
+        }
+    }
+
+    for (asset, benchmark, symbol) in [
+        (btc, sp500, MarketSymbol::BtcBetaSp500),
+        (btc, nasdaq, MarketSymbol::BtcBetaNasdaq),
+        (eth, sp500, MarketSymbol::EthBetaSp500),
+        (eth, nasdaq, MarketSymbol::EthBetaNasdaq),
+    ] {
+        series.push(DerivedSeries { symbol, values: rolling_beta(asset, benchmark, from, BETA_WINDOW_DAYS) });
+    }
+
+    let ratio = price_ratio(eth, btc);
+    for (days, symbol) in RELATIVE_STRENGTH_DAYS.into_iter().zip([
+        MarketSymbol::EthBtcStrength30d,
+        MarketSymbol::EthBtcStrength90d,
+    ]) {
+        let values = ratio
+            .iter()
+            .filter(|(d, _)| *d >= from)
+            .filter_map(|&(date, now)| {
+                let (_, before) = value_as_of(&ratio, date - Duration::days(days))?;
+                (before > 0.0).then(|| (date, (now / before - 1.0) * 100.0))
+            })
+            .collect();
+        series.push(DerivedSeries { symbol, values });
+    }
+    series.push(DerivedSeries { symbol: MarketSymbol::EthBtcRatio, values: since(ratio) });
+
+    series
+}
+
+/// `a` over `b` on the days both have a close.
+pub fn price_ratio(a: &[(NaiveDate, f64)], b: &[(NaiveDate, f64)]) -> Vec<(NaiveDate, f64)> {
+    a.iter()
+        .filter_map(|&(date, price)| {
+            let i = b.binary_search_by_key(&date, |(d, _)| *d).ok()?;
+            (b[i].1 != 0.0).then(|| (date, price / b[i].1))
+        })
+        .collect()
+}
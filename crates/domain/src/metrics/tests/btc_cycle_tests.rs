@@ -0,0 +1,85 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{btc_cycle_history, MarketSymbol, BTC_GENESIS};
+
+fn day(i: usize) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2012, 1, 1).unwrap() + Duration::days(i as i64)
+}
+
+fn series(n: usize, price: impl Fn(usize) -> f64) -> Vec<(NaiveDate, f64)> {
+    (0..n).map(|i| (day(i), price(i))).collect()
+}
+
+#[test]
+fn test_power_law_recovers_the_fitted_parameters() {
+    // An exact power law with a small wobble so the residuals are not all zero.
+    let closes = series(3000, |i| {
+        let days = (day(i) - BTC_GENESIS).num_days() as f64;
+        10f64.powf(-17.0 + 5.8 * days.log10() + 0.01 * (i as f64 / 10.0).sin())
+    });
+
+    let readings = btc_cycle_history(&closes, day(0), day(2999));
+    assert_eq!(readings.len(), 3000);
+    assert!(readings[728].power_law.is_none());
+
+    let latest = readings.last().unwrap();
+    let fit = latest.power_law.unwrap();
+    assert_eq!(fit.observations, 3000);
+    assert!((fit.slope - 5.8).abs() < 0.01, "{}", fit.slope);
+    assert!((fit.intercept + 17.0).abs() < 0.05, "{}", fit.intercept);
+    assert!(fit.residual_std > 0.0 && fit.residual_std < 0.01);
+
+    let fair = fit.fair_value(latest.date);
+    assert!((latest.close / fair - 1.0).abs() < 0.05);
+    assert!(fit.band(latest.date, -2.0) < fair && fair < fit.band(latest.date, 2.0));
+    assert!(fit.deviation(latest.date, latest.close).abs() < 3.0);
+}
+
+#[test]
+fn test_moving_average_indicators_on_a_flat_market() {
+    let closes = series(1500, |_| 100.0);
+    let readings = btc_cycle_history(&closes, day(0), day(1499));
+
+    assert!(readings[198].mayer_multiple.is_none());
+    assert_eq!(readings[199].mayer_multiple, Some(1.0));
+    assert!(readings[1398].two_hundred_week_ma.is_none());
+    assert_eq!(readings[1399].two_hundred_week_ma, Some(100.0));
+    // Four weeks after the first 200-week value there is a change to report.
+    assert!(readings[1426].two_hundred_week_ma_change.is_none());
+    assert_eq!(readings[1427].two_hundred_week_ma_change, Some(0.0));
+    assert_eq!(readings[349].pi_cycle_ratio, Some(0.5));
+    assert!(readings.iter().all(|r| !r.pi_cycle_top));
+}
+
+#[test]
+fn test_pi_cycle_top_fires_once_on_the_crossover() {
+    // Flat for a year, then a parabolic run that drags the 111-day SMA over twice the 350-day one.
+    let closes = series(900, |i| if i < 400 { 100.0 } else { 100.0 * 1.01f64.powi((i - 400) as i32) });
+    let readings = btc_cycle_history(&closes, day(0), day(899));
+
+    let tops: Vec<_> = readings.iter().filter(|r| r.pi_cycle_top).collect();
+    assert_eq!(tops.len(), 1);
+    let top = tops[0];
+    assert!(top.pi_cycle_ratio.unwrap() >= 1.0);
+    let before = readings.iter().find(|r| r.date == top.date - Duration::days(1)).unwrap();
+    assert!(before.pi_cycle_ratio.unwrap() < 1.0);
+}
+
+#[test]
+fn test_readings_only_cover_the_requested_days() {
+    let closes = series(1500, |i| 100.0 + i as f64);
+    let readings = btc_cycle_history(&closes, day(1490), day(1495));
+    assert_eq!(readings.len(), 6);
+    assert_eq!(readings[0].date, day(1490));
+
+    // History before `from` still counts: the averages are the same as in a full run.
+    let full = btc_cycle_history(&closes, day(0), day(1499));
+    assert_eq!(readings[0], full[1490]);
+
+    let symbols: Vec<&str> = readings[0].metrics().iter().map(|(s, _)| s.as_str()).collect();
+    assert!(symbols.contains(&"BTC_MAYER_MULTIPLE") && symbols.contains(&"BTC_POWER_LAW_UPPER"));
+    assert!(symbols.contains(&"BTC_PI_CYCLE_TOP"));
+    assert_eq!(readings[0].metrics().len(), 11);
+    assert!(MarketSymbol::btc_metrics().iter().any(|s| s.as_str() == "BTC_200W_MA_CHANGE_4W"));
+    assert_eq!("btc_power_law_slope".parse::<MarketSymbol>().unwrap().as_str(), "BTC_POWER_LAW_SLOPE");
+}
@@ -0,0 +1,78 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::{correlation_matrix, rolling_correlation, MarketDataSet, MarketSymbol, MIN_CORRELATION_OBSERVATIONS};
+
+fn day(i: usize) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + Duration::days(i as i64)
+}
+
+/// Prices with a different return every day.
+fn noisy(n: usize, scale: f64) -> Vec<(NaiveDate, f64)> {
+    (0..n).map(|i| (day(i), 100.0 + scale * ((i as f64) * 1.3).sin() + 0.1 * i as f64)).collect()
+}
+
+#[test]
+fn test_perfect_and_inverse_correlation() {
+    let a = noisy(120, 5.0);
+    // Same returns, only the level differs.
+    let b: Vec<_> = a.iter().map(|(d, v)| (*d, v * 3.0)).collect();
+    // Opposite returns.
+    let mut c = vec![(day(0), 100.0)];
+    for w in a.windows(2) {
+        let last = c.last().unwrap().1;
+        c.push((w[1].0, last * (1.0 - (w[1].1 / w[0].1 - 1.0))));
+    }
+
+    let same = rolling_correlation(&a, &b, day(60), 30);
+    let opposite = rolling_correlation(&a, &c, day(60), 30);
+    assert_eq!(same.len(), 60);
+    assert_eq!(same[0].0, day(60));
+    assert!(same.iter().all(|(_, v)| (v - 1.0).abs() < 1e-9));
+    assert!(opposite.iter().all(|(_, v)| (v + 1.0).abs() < 1e-9));
+}
+
+#[test]
+fn test_weekday_series_are_compared_on_common_days() {
+    let crypto = noisy(200, 5.0);
+    // The stock trades on weekdays only and follows crypto from close to close.
+    let stock: Vec<_> = crypto
+        .iter()
+        .filter(|(d, _)| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .map(|(d, v)| (*d, v / 2.0))
+        .collect();
+
+    let series = rolling_correlation(&crypto, &stock, day(0), 60);
+    assert!(series.iter().all(|(d, _)| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun)));
+    assert!(series.iter().all(|(_, v)| (v - 1.0).abs() < 1e-9));
+}
+
+#[test]
+fn test_too_few_returns_give_no_correlation() {
+    let a = noisy(MIN_CORRELATION_OBSERVATIONS, 5.0);
+    let b = noisy(MIN_CORRELATION_OBSERVATIONS, 2.0);
+    // One close fewer than needed for enough returns.
+    assert!(rolling_correlation(&a, &b, day(0), 365).is_empty());
+
+    let flat: Vec<_> = (0..100).map(|i| (day(i), 50.0)).collect();
+    assert!(rolling_correlation(&noisy(100, 5.0), &flat, day(0), 90).is_empty());
+}
+
+#[test]
+fn test_matrix_is_symmetric_with_a_unit_diagonal() {
+    let mut data = MarketDataSet::default();
+    data.prices.insert("BTC".into(), noisy(200, 5.0));
+    // The BTC path run backwards.
+    let reversed = noisy(200, 5.0).into_iter().rev().enumerate().map(|(i, (_, v))| (day(i), v)).collect();
+    data.prices.insert("ETH".into(), reversed);
+    data.prices.insert("GOLD".into(), noisy(10, 1.0));
+
+    let symbols = [MarketSymbol::BtcUsd, MarketSymbol::EthUsd, MarketSymbol::Gold];
+    let matrix = correlation_matrix(&data.at(day(199)), &symbols, 90);
+    assert_eq!(matrix.symbols, ["BTC_USD", "ETH_USD", "GOLD_USD"]);
+    assert_eq!(matrix.observations[0][1], 90);
+    assert!((matrix.values[0][0].unwrap() - 1.0).abs() < 1e-9);
+    assert!((matrix.values[1][1].unwrap() - 1.0).abs() < 1e-9);
+    assert_eq!(matrix.values[0][1], matrix.values[1][0]);
+    assert!(matrix.values[0][1].unwrap().abs() < 1.0);
+    assert!(matrix.values[2].iter().all(Option::is_none));
+}
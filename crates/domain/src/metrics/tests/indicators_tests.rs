@@ -0,0 +1,139 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    atr, bollinger_bands, ema, macd, rolling_volatility, rsi, sma, IndicatorCategory, TechnicalIndicator,
+    DEFAULT_INDICATORS,
+};
+
+fn day(i: usize) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2023, 1, 1).unwrap() + Duration::days(i as i64)
+}
+
+fn daily(values: &[f64]) -> Vec<(NaiveDate, f64)> {
+    values.iter().enumerate().map(|(i, v)| (day(i), *v)).collect()
+}
+
+/// Trending, cycling and noisy enough that every indicator moves.
+fn wavy(n: usize) -> Vec<(NaiveDate, f64)> {
+    (0..n)
+        .map(|i| {
+            let t = i as f64;
+            (day(i), 100.0 + 0.2 * t + 15.0 * (t / 9.0).sin() + 4.0 * (t * 1.7).cos())
+        })
+        .collect()
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9 * b.abs().max(1.0), "{a} != {b}");
+}
+
+#[test]
+fn test_moving_averages() {
+    let series = daily(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+
+    let sma3 = sma(&series, 3);
+    assert_eq!(sma3.len(), 8);
+    assert_eq!(sma3[0], (day(2), 2.0));
+    assert_eq!(sma3[7], (day(9), 9.0));
+
+    // Seeded with the mean of 1, 2, 3 and then lagging a linear series by one step.
+    let ema3 = ema(&series, 3);
+    assert_eq!(ema3[0], (day(2), 2.0));
+    assert_eq!(ema3.last().unwrap(), &(day(9), 9.0));
+
+    assert!(sma(&series, 11).is_empty() && ema(&series, 0).is_empty());
+}
+
+#[test]
+fn test_rsi_bounds() {
+    let rising = daily(&(0..30).map(|i| 100.0 + i as f64).collect::<Vec<_>>());
+    let falling = daily(&(0..30).map(|i| 100.0 - i as f64).collect::<Vec<_>>());
+    let flat = daily(&[100.0; 30]);
+
+    let up = rsi(&rising, 14);
+    assert_eq!(up.len(), 30 - 14);
+    assert_eq!(up[0].0, day(14));
+    assert!(up.iter().all(|(_, v)| *v == 100.0));
+    assert!(rsi(&falling, 14).iter().all(|(_, v)| *v == 0.0));
+    assert!(rsi(&flat, 14).iter().all(|(_, v)| *v == 50.0));
+
+    let alternating = daily(&(0..30).map(|i| if i % 2 == 0 { 100.0 } else { 101.0 }).collect::<Vec<_>>());
+    assert!(rsi(&alternating, 14).iter().all(|(_, v)| (v - 50.0).abs() < 5.0));
+}
+
+#[test]
+fn test_macd_and_bollinger_bands() {
+    let flat = daily(&[50.0; 60]);
+    let points = macd(&flat, 12, 26, 9);
+    assert_eq!(points.len(), 60 - 26 - 9 + 2);
+    assert_eq!(points[0].date, day(26 + 9 - 2));
+    assert!(points.iter().all(|p| p.macd == 0.0 && p.signal == 0.0 && p.histogram == 0.0));
+
+    let rising = daily(&(0..60).map(|i| 100.0 + i as f64).collect::<Vec<_>>());
+    let last = macd(&rising, 12, 26, 9).pop().unwrap();
+    assert!(last.macd > 0.0);
+    assert_close(last.histogram, last.macd - last.signal);
+    assert!(macd(&rising[..20], 12, 26, 9).is_empty());
+
+    let bands = bollinger_bands(&daily(&[1.0, 2.0, 3.0]), 3, 2.0);
+    assert_eq!(bands.len(), 1);
+    assert_eq!(bands[0].middle, 2.0);
+    assert_close(bands[0].upper, 2.0 + 2.0 * (2.0f64 / 3.0).sqrt());
+    assert_close(bands[0].lower, 2.0 - 2.0 * (2.0f64 / 3.0).sqrt());
+}
+
+#[test]
+fn test_atr_and_volatility() {
+    let zigzag = daily(&(0..40).map(|i| if i % 2 == 0 { 10.0 } else { 11.0 }).collect::<Vec<_>>());
+    let ranges = atr(&zigzag, 14);
+    assert_eq!(ranges[0].0, day(14));
+    assert!(ranges.iter().all(|(_, v)| *v == 1.0));
+
+    let compounding = daily(&(0..40).map(|i| 100.0 * 1.01f64.powi(i)).collect::<Vec<_>>());
+    assert!(rolling_volatility(&compounding, 30).iter().all(|(_, v)| v.abs() < 1e-9));
+
+    // The same closes observed every other day are half as many returns a year.
+    let every_day = rolling_volatility(&zigzag, 30);
+    let every_other_day: Vec<_> = zigzag.iter().enumerate().map(|(i, (_, v))| (day(2 * i), *v)).collect();
+    let sparse = rolling_volatility(&every_other_day, 30);
+    assert_eq!(every_day.len(), 10);
+    assert_close(sparse[0].1, every_day[0].1 / 2f64.sqrt());
+}
+
+#[test]
+fn test_series_names_and_categories() {
+    let macd = TechnicalIndicator::Macd { fast: 12, slow: 26, signal: 9 };
+    assert_eq!(
+        macd.series_names("BTC_USD"),
+        ["BTC_USD_MACD_12_26_9", "BTC_USD_MACD_SIGNAL_12_26_9", "BTC_USD_MACD_HIST_12_26_9"]
+    );
+    let bands = TechnicalIndicator::Bollinger { window: 20, width: 2.0 };
+    assert_eq!(bands.series_names("ETH_USD")[2], "ETH_USD_BB_LOWER_20_2");
+    assert_eq!(macd.category().as_str(), "momentum");
+    assert_eq!(TechnicalIndicator::Atr { period: 14 }.category(), IndicatorCategory::Volatility);
+
+    let outputs = macd.compute("BTC_USD", &wavy(100));
+    assert_eq!(outputs.len(), 3);
+    assert!(outputs.iter().all(|o| o.values.len() == 100 - 26 - 9 + 2));
+}
+
+#[test]
+fn test_recomputing_from_the_warmup_matches_full_history() {
+    let series = wavy(1200);
+    let resume = 1000;
+
+    for indicator in DEFAULT_INDICATORS {
+        let full = indicator.compute("X", &series);
+        let tail = indicator.compute("X", &series[resume - indicator.warmup()..]);
+
+        for (full, tail) in full.iter().zip(&tail) {
+            let full: Vec<_> = full.values.iter().filter(|(d, _)| *d >= day(resume)).collect();
+            let tail: Vec<_> = tail.values.iter().filter(|(d, _)| *d >= day(resume)).collect();
+            assert_eq!(full.len(), 200, "{indicator:?}");
+            assert_eq!(full.len(), tail.len(), "{indicator:?}");
+            for ((date, a), (_, b)) in full.iter().zip(tail) {
+                assert!((a - b).abs() < 1e-6 * a.abs().max(1.0), "{indicator:?} on {date}: {a} != {b}");
+            }
+        }
+    }
+}
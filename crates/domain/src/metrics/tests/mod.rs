@@ -0,0 +1,10 @@
+#[cfg(test)]
+pub mod btc_cycle_tests;
+#[cfg(test)]
+pub mod correlation_tests;
+#[cfg(test)]
+pub mod indicators_tests;
+#[cfg(test)]
+pub mod regime_tests;
+#[cfg(test)]
+pub mod risk_tests;
@@ -0,0 +1,121 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::{classify_regime, regime_history, MacroRegime, MarketDataSet, MarketSymbol};
+
+const DAYS: i64 = 540;
+
+fn start() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2021, 1, 1).unwrap()
+}
+
+fn end() -> NaiveDate {
+    start() + Duration::days(DAYS)
+}
+
+/// A daily series moving linearly from `first` to `last` over the last `over` days, flat before.
+fn daily(first: f64, last: f64, over: i64) -> Vec<(NaiveDate, f64)> {
+    (0..=DAYS)
+        .map(|i| {
+            let progress = ((i - (DAYS - over)) as f64 / over as f64).clamp(0.0, 1.0);
+            (start() + Duration::days(i), first + (last - first) * progress)
+        })
+        .collect()
+}
+
+/// A monthly series, dated on the first of the month, growing by `yearly_pct` a year.
+fn monthly(level: f64, yearly_pct: f64) -> Vec<(NaiveDate, f64)> {
+    let monthly_growth = (1.0 + yearly_pct / 100.0).powf(1.0 / 12.0);
+    (0..=DAYS)
+        .map(|i| start() + Duration::days(i))
+        .filter(|d| d.day() == 1)
+        .enumerate()
+        .map(|(n, d)| (d, level * monthly_growth.powi(n as i32)))
+        .collect()
+}
+
+fn market(curve: f64, rate: (f64, f64), dollar: (f64, f64), cpi_pct: f64, m2_pct: f64) -> MarketDataSet {
+    let mut data = MarketDataSet::default();
+    data.metrics.insert(MarketSymbol::T10Y2Y.as_str().to_string(), daily(curve, curve, 1));
+    data.metrics.insert(MarketSymbol::DFF.as_str().to_string(), daily(rate.0, rate.1, 180));
+    data.metrics.insert(MarketSymbol::CPIAUCSL.as_str().to_string(), monthly(260.0, cpi_pct));
+    data.metrics.insert(MarketSymbol::M2SL.as_str().to_string(), monthly(20_000.0, m2_pct));
+    data.prices.insert("USD_INDEX".to_string(), daily(dollar.0, dollar.1, 90));
+    data
+}
+
+#[test]
+fn test_hiking_into_inflation_is_risk_off() {
+    let data = market(-0.5, (0.1, 3.1), (95.0, 105.0), 8.0, 0.0);
+    let reading = classify_regime(&data.at(end())).unwrap();
+
+    assert_eq!(reading.regime, MacroRegime::RiskOff);
+    assert_eq!(reading.sub_scores.yield_curve, Some(-0.5));
+    assert_eq!(reading.sub_scores.policy, Some(-1.0));
+    assert_eq!(reading.sub_scores.dollar, Some(-1.0));
+    assert_eq!(reading.sub_scores.inflation, Some(-1.0));
+    assert!((reading.sub_scores.liquidity.unwrap() + 0.6).abs() < 1e-9);
+    assert!(reading.score < -0.8);
+}
+
+#[test]
+fn test_cuts_with_a_steep_curve_are_risk_on() {
+    let data = market(1.5, (5.0, 4.0), (100.0, 100.0), 2.0, 8.0);
+    let reading = classify_regime(&data.at(end())).unwrap();
+
+    assert_eq!(reading.regime, MacroRegime::RiskOn);
+    assert_eq!(reading.sub_scores.yield_curve, Some(1.0));
+    assert_eq!(reading.sub_scores.dollar, Some(0.0));
+    assert!((reading.sub_scores.inflation.unwrap() - 0.2).abs() < 1e-9);
+}
+
+#[test]
+fn test_quadrants_between_policy_and_conditions() {
+    let tightening = market(1.0, (2.0, 3.0), (100.0, 100.0), 2.5, 3.0);
+    assert_eq!(classify_regime(&tightening.at(end())).unwrap().regime, MacroRegime::Tightening);
+
+    let easing = market(-1.0, (5.0, 4.0), (100.0, 104.0), 5.0, 3.0);
+    assert_eq!(classify_regime(&easing.at(end())).unwrap().regime, MacroRegime::Easing);
+}
+
+#[test]
+fn test_fed_funds_stands_in_for_a_missing_daily_rate() {
+    let mut data = market(1.0, (2.0, 3.0), (100.0, 100.0), 2.5, 3.0);
+    let dff = data.metrics.remove(MarketSymbol::DFF.as_str()).unwrap();
+    data.metrics.insert(MarketSymbol::FEDFUNDS.as_str().to_string(), dff);
+
+    assert_eq!(classify_regime(&data.at(end())).unwrap().sub_scores.policy, Some(-1.0));
+}
+
+#[test]
+fn test_stale_inputs_are_ignored() {
+    let data = market(1.0, (2.0, 3.0), (100.0, 100.0), 2.5, 3.0);
+
+    let month_later = classify_regime(&data.at(end() + Duration::days(30))).unwrap();
+    assert!(month_later.sub_scores.yield_curve.is_some());
+    // Every input is more than 100 days old by then.
+    assert!(classify_regime(&data.at(end() + Duration::days(150))).is_none());
+}
+
+#[test]
+fn test_needs_both_monetary_and_conditions() {
+    let mut data = market(1.0, (2.0, 3.0), (100.0, 100.0), 2.5, 3.0);
+    data.metrics.remove(MarketSymbol::DFF.as_str());
+    data.metrics.remove(MarketSymbol::M2SL.as_str());
+
+    assert!(classify_regime(&data.at(end())).is_none());
+}
+
+#[test]
+fn test_history_and_stored_metrics() {
+    let data = market(-0.5, (0.1, 3.1), (95.0, 105.0), 8.0, 0.0);
+
+    let history = regime_history(&data, end() - Duration::days(9), end());
+    assert_eq!(history.len(), 10);
+    assert!(history.iter().all(|r| r.regime == MacroRegime::RiskOff));
+
+    let metrics = history.last().unwrap().metrics();
+    assert_eq!(metrics.len(), 7);
+    assert_eq!(metrics[0].0.as_str(), "MACRO_REGIME");
+    assert_eq!(MacroRegime::from_code(metrics[0].1), Some(MacroRegime::RiskOff));
+    assert_eq!("risk-off".parse::<MacroRegime>().unwrap(), MacroRegime::RiskOff);
+}
@@ -0,0 +1,90 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::{price_ratio, risk_series, rolling_beta, MarketDataSet, MarketSymbol};
+
+fn day(i: usize) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2022, 1, 1).unwrap() + Duration::days(i as i64)
+}
+
+fn series(n: usize, price: impl Fn(usize) -> f64) -> Vec<(NaiveDate, f64)> {
+    (0..n).map(|i| (day(i), price(i))).collect()
+}
+
+/// A weekday-only index with a different return every day.
+fn index(n: usize) -> Vec<(NaiveDate, f64)> {
+    series(n, |i| 4000.0 + 40.0 * (i as f64 * 1.3).sin() + i as f64)
+        .into_iter()
+        .filter(|(d, _)| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .collect()
+}
+
+/// An asset whose every return between two index closes is `beta` times the index return.
+fn levered(index: &[(NaiveDate, f64)], beta: f64) -> Vec<(NaiveDate, f64)> {
+    let mut out = vec![(index[0].0, 100.0)];
+    for w in index.windows(2) {
+        let last = out.last().unwrap().1;
+        out.push((w[1].0, last * (1.0 + beta * (w[1].1 / w[0].1 - 1.0))));
+    }
+    out
+}
+
+#[test]
+fn test_beta_is_the_return_multiple() {
+    let nasdaq = index(300);
+    let btc = levered(&nasdaq, 2.5);
+
+    let betas = rolling_beta(&btc, &nasdaq, day(120), 90);
+    assert!(!betas.is_empty());
+    assert!(betas.iter().all(|(d, _)| *d >= day(120)));
+    assert!(betas.iter().all(|(_, b)| (b - 2.5).abs() < 1e-9));
+
+    // Against itself and against something that does not move.
+    assert!(rolling_beta(&nasdaq, &nasdaq, day(120), 90).iter().all(|(_, b)| (b - 1.0).abs() < 1e-9));
+    let flat = series(300, |_| 10.0);
+    assert!(rolling_beta(&nasdaq, &flat, day(0), 90).is_empty());
+}
+
+#[test]
+fn test_price_ratio_on_common_days() {
+    let eth = series(10, |i| 100.0 + i as f64);
+    let btc: Vec<_> = series(10, |_| 50.0).into_iter().filter(|(d, _)| *d != day(3)).collect();
+
+    let ratio = price_ratio(&eth, &btc);
+    assert_eq!(ratio.len(), 9);
+    assert_eq!(ratio[0], (day(0), 2.0));
+    assert!(ratio.iter().all(|(d, _)| *d != day(3)));
+}
+
+#[test]
+fn test_risk_series_cover_every_derived_symbol() {
+    let sp500 = index(800);
+    let btc = levered(&series(800, |i| 4000.0 + 40.0 * (i as f64 * 1.3).sin() + i as f64), 2.0);
+    // ETH doubles against BTC over the whole run.
+    let eth: Vec<_> = btc.iter().enumerate().map(|(i, (d, v))| (*d, v * 0.05 * 2f64.powf(i as f64 / 799.0))).collect();
+
+    let mut data = MarketDataSet::default();
+    data.prices.insert("BTC".into(), btc);
+    data.prices.insert("ETH".into(), eth);
+    data.prices.insert("SP500".into(), sp500.clone());
+    data.prices.insert("NASDAQ".into(), sp500);
+
+    let derived = risk_series(&data.at(day(799)), day(700));
+    let get = |symbol: MarketSymbol| {
+        derived.iter().find(|s| s.symbol.as_str() == symbol.as_str()).map(|s| &s.values).unwrap()
+    };
+    assert_eq!(derived.len(), 13);
+    assert!(derived.iter().all(|s| !s.values.is_empty() && s.values.iter().all(|(d, _)| *d >= day(700))));
+    // All of them reach the asset snapshots.
+    let snapshot_metrics: Vec<_> = MarketSymbol::btc_metrics().into_iter().chain(MarketSymbol::eth_metrics()).collect();
+    assert!(derived.iter().all(|s| snapshot_metrics.iter().any(|m| m.as_str() == s.symbol.as_str())));
+
+    assert_eq!(get(MarketSymbol::BtcVolatility30d).len(), 100);
+    assert!(get(MarketSymbol::BtcVolatility365d).iter().all(|(_, v)| *v > 1.0 && *v < 1000.0));
+    assert!(get(MarketSymbol::EthBtcRatio).iter().all(|(_, v)| *v > 0.05 && *v <= 0.1));
+    // A steady 2^(1/799) a day: 30 days of it is a little under 3%.
+    let strength = get(MarketSymbol::EthBtcStrength30d).last().unwrap().1;
+    assert!((strength - (2f64.powf(30.0 / 799.0) - 1.0) * 100.0).abs() < 1e-6, "{strength}");
+    // Twice the daily moves behind the index, compounded over the weekends the index skips.
+    assert!(get(MarketSymbol::BtcBetaSp500).iter().all(|(_, b)| (b - 2.0).abs() < 0.5));
+    assert_eq!("eth_beta_nasdaq_90d".parse::<MarketSymbol>().unwrap().as_str(), "ETH_BETA_NASDAQ_90D");
+}
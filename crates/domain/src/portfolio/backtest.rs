@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::nav::NavPoint;
+use crate::portfolio::performance::{performance, PerformancePeriod, PerformanceReport, DAYS_PER_YEAR};
+use crate::portfolio::rebalance::TradeSide;
+use crate::portfolio::signals::{MarketContext, MarketDataSet, Signal, SignalAction, Strategy};
+
+/// Trades smaller than this share of equity are not worth a fill.
+const MIN_TRADE_FRACTION: f64 = 1e-4;
+
+/// How a replay turns signals into fills. Rates are fractions (`0.001` is 10 bps).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub initial_cash: f64,
+    /// Fee per fill as a share of the traded notional.
+    pub fee_rate: f64,
+    /// Adverse price move per fill: buys fill this much above the close, sells below it.
+    pub slippage: f64,
+    /// Annual rate earned by uninvested cash. Zero models pure cash drag.
+    pub cash_rate: f64,
+    /// Share of equity a buy signal adds to the asset's target weight, and a sell removes.
+    pub position_step: f64,
+    /// Signals below this confidence are ignored.
+    pub min_confidence: f64,
+}
+
+impl BacktestConfig {
+    pub fn new(from: NaiveDate, to: NaiveDate, initial_cash: f64) -> Self {
+        Self {
+            from,
+            to,
+            initial_cash,
+            fee_rate: 0.001,
+            slippage: 0.0005,
+            cash_rate: 0.0,
+            position_step: 0.25,
+            min_confidence: 0.0,
+        }
+    }
+
+    fn validate(&self) -> Result<(), BacktestError> {
+        let fraction = |v: f64| v.is_finite() && (0.0..=1.0).contains(&v);
+        if self.from > self.to {
+            return Err(BacktestError::InvalidConfig(format!("from {} is after to {}", self.from, self.to)));
+        }
+        if !(self.initial_cash.is_finite() && self.initial_cash > 0.0) {
+            return Err(BacktestError::InvalidConfig("initial cash must be positive".to_string()));
+        }
+        if !fraction(self.fee_rate) || !fraction(self.slippage) {
+            return Err(BacktestError::InvalidConfig("fee rate and slippage must be between 0 and 1".to_string()));
+        }
+        if !self.cash_rate.is_finite() {
+            return Err(BacktestError::InvalidConfig("cash rate must be a number".to_string()));
+        }
+        if !fraction(self.position_step) || !fraction(self.min_confidence) {
+            return Err(BacktestError::InvalidConfig(
+                "position step and minimum confidence must be between 0 and 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BacktestError {
+    InvalidConfig(String),
+    NoData { from: NaiveDate, to: NaiveDate },
+}
+
+impl fmt::Display for BacktestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BacktestError::InvalidConfig(reason) => write!(f, "Invalid backtest configuration: {}", reason),
+            BacktestError::NoData { from, to } => write!(f, "No closes between {} and {}", from, to),
+        }
+    }
+}
+
+impl std::error::Error for BacktestError {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub date: NaiveDate,
+    pub equity: f64,
+    pub cash: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    pub date: NaiveDate,
+    pub asset_symbol: String,
+    pub side: TradeSide,
+    pub quantity: f64,
+    /// Fill price, slippage included.
+    pub price_usd: f64,
+    pub fee_usd: f64,
+    /// What slippage cost compared with filling at the close.
+    pub slippage_usd: f64,
+    /// Reason of the signal that moved the target.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestStats {
+    pub final_equity: f64,
+    pub total_return: f64,
+    /// Risk figures of the equity curve over the whole replay.
+    pub performance: Option<PerformanceReport>,
+    pub trade_count: usize,
+    pub total_fees_usd: f64,
+    pub total_slippage_usd: f64,
+    pub cash_interest_usd: f64,
+    /// Average share of equity held in assets rather than cash.
+    pub average_exposure: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub strategy: String,
+    pub config: BacktestConfig,
+    pub equity_curve: Vec<EquityPoint>,
+    pub trades: Vec<BacktestTrade>,
+    pub stats: BacktestStats,
+}
+
+impl BacktestResult {
+    /// Equity curve as CSV.
+    pub fn equity_csv(&self) -> String {
+        let mut csv = String::from("date,equity_usd,cash_usd\n");
+        for point in &self.equity_curve {
+            csv.push_str(&format!("{},{:.2},{:.2}\n", point.date, point.equity, point.cash));
+        }
+        csv
+    }
+}
+
+/// Target weights decided on one day, filled at the next day's close.
+struct PendingOrders {
+    targets: BTreeMap<String, f64>,
+    reasons: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+struct Book {
+    cash: f64,
+    holdings: BTreeMap<String, f64>,
+    trades: Vec<BacktestTrade>,
+    fees: f64,
+    slippage: f64,
+}
+
+impl Book {
+    fn invested(&self, ctx: &MarketContext) -> f64 {
+        self.holdings
+            .iter()
+            .filter_map(|(asset, quantity)| ctx.price(asset).map(|price| quantity * price))
+            .sum()
+    }
+
+    /// Moves holdings to `orders.targets` at today's closes, sells first so buys can use the
+    /// proceeds. Assets without a close yet are left alone.
+    fn rebalance(&mut self, ctx: &MarketContext, orders: &PendingOrders, config: &BacktestConfig) {
+        let equity = self.cash + self.invested(ctx);
+        let min_trade = equity * MIN_TRADE_FRACTION;
+
+        let mut deltas = Vec::new();
+        for (asset, target) in &orders.targets {
+            let Some(price) = ctx.price(asset) else { continue };
+            let held = self.holdings.get(asset).copied().unwrap_or(0.0);
+            let delta = target * equity - held * price;
+            if delta.abs() >= min_trade {
+                deltas.push((asset.clone(), price, delta));
+            }
+        }
+        deltas.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+        for (asset, price, delta) in deltas {
+            let reason = orders.reasons.get(&asset).cloned().unwrap_or_default();
+            let held = self.holdings.entry(asset.clone()).or_insert(0.0);
+
+            let (side, quantity, fill) = if delta < 0.0 {
+                let fill = price * (1.0 - config.slippage);
+                let quantity = (-delta / price).min(*held);
+                self.cash += quantity * fill * (1.0 - config.fee_rate);
+                *held -= quantity;
+                (TradeSide::Sell, quantity, fill)
+            } else {
+                let fill = price * (1.0 + config.slippage);
+                let notional = delta.min(self.cash / (1.0 + config.fee_rate));
+                if notional < min_trade {
+                    continue;
+                }
+                let quantity = notional / fill;
+                self.cash -= notional * (1.0 + config.fee_rate);
+                *held += quantity;
+                (TradeSide::Buy, quantity, fill)
+            };
+
+            let fee = quantity * fill * config.fee_rate;
+            let slippage = quantity * (fill - price).abs();
+            self.fees += fee;
+            self.slippage += slippage;
+            self.trades.push(BacktestTrade {
+                date: ctx.as_of(),
+                asset_symbol: asset,
+                side,
+                quantity,
+                price_usd: fill,
+                fee_usd: fee,
+                slippage_usd: slippage,
+                reason,
+            });
+        }
+
+        self.holdings.retain(|_, quantity| *quantity > 0.0);
+    }
+}
+
+/// New target weights after the day's signals, or `None` when they leave the targets unchanged.
+///
+/// Weights replace the target, buys and sells move it by `position_step`. When the targets add
+/// up to more than the whole portfolio they are scaled down proportionally.
+fn apply_signals(
+    targets: &BTreeMap<String, f64>,
+    signals: &[Signal],
+    config: &BacktestConfig,
+) -> Option<PendingOrders> {
+    let mut next = targets.clone();
+    let mut reasons = BTreeMap::new();
+
+    for signal in signals.iter().filter(|s| s.confidence >= config.min_confidence) {
+        let current = next.get(&signal.asset_symbol).copied().unwrap_or(0.0);
+        let target = match signal.action {
+            SignalAction::Weight(weight) => weight.clamp(0.0, 1.0),
+            SignalAction::Buy => (current + config.position_step).min(1.0),
+            SignalAction::Sell => (current - config.position_step).max(0.0),
+            SignalAction::Hold => continue,
+        };
+        next.insert(signal.asset_symbol.clone(), target);
+        reasons.insert(signal.asset_symbol.clone(), format!("{}: {}", signal.strategy, signal.reason));
+    }
+
+    let total: f64 = next.values().sum();
+    if total > 1.0 {
+        next.values_mut().for_each(|w| *w /= total);
+    }
+
+    (next != *targets).then_some(PendingOrders { targets: next, reasons })
+}
+
+/// Replays `strategy` over every day with a close between `config.from` and `config.to`.
+///
+/// Each day the strategy sees only data up to that day's close, and whatever it decides is
+/// filled at the next day's close. Uninvested cash earns `cash_rate` between days. Warm-up data
+/// before `from` is visible to the strategy but not traded.
+pub fn backtest(
+    strategy: &dyn Strategy,
+    data: &MarketDataSet,
+    config: &BacktestConfig,
+) -> Result<BacktestResult, BacktestError> {
+    config.validate()?;
+
+    let dates: Vec<NaiveDate> = data
+        .price_dates()
+        .into_iter()
+        .filter(|d| (config.from..=config.to).contains(d))
+        .collect();
+    if dates.is_empty() {
+        return Err(BacktestError::NoData { from: config.from, to: config.to });
+    }
+
+    let mut book = Book { cash: config.initial_cash, ..Book::default() };
+    let mut targets = BTreeMap::new();
+    let mut pending: Option<PendingOrders> = None;
+    let mut equity_curve = Vec::with_capacity(dates.len());
+    let mut exposure = 0.0;
+    let mut cash_interest = 0.0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for date in dates {
+        let ctx = data.at(date);
+
+        if let Some(previous) = previous {
+            let years = (date - previous).num_days() as f64 / DAYS_PER_YEAR;
+            let interest = book.cash * ((1.0 + config.cash_rate).powf(years) - 1.0);
+            book.cash += interest;
+            cash_interest += interest;
+        }
+
+        if let Some(orders) = pending.take() {
+            book.rebalance(&ctx, &orders, config);
+        }
+
+        let invested = book.invested(&ctx);
+        let equity = book.cash + invested;
+        if equity > 0.0 {
+            exposure += invested / equity;
+        }
+        equity_curve.push(EquityPoint { date, equity, cash: book.cash });
+
+        if let Some(orders) = apply_signals(&targets, &strategy.evaluate(&ctx), config) {
+            targets = orders.targets.clone();
+            pending = Some(orders);
+        }
+        previous = Some(date);
+    }
+
+    let stats = stats(data, config, &equity_curve, &book, exposure, cash_interest);
+    Ok(BacktestResult {
+        strategy: strategy.name().to_string(),
+        config: config.clone(),
+        equity_curve,
+        trades: book.trades,
+        stats,
+    })
+}
+
+fn stats(
+    data: &MarketDataSet,
+    config: &BacktestConfig,
+    equity_curve: &[EquityPoint],
+    book: &Book,
+    exposure: f64,
+    cash_interest: f64,
+) -> BacktestStats {
+    let nav: Vec<NavPoint> = equity_curve
+        .iter()
+        .enumerate()
+        .map(|(i, point)| NavPoint {
+            date: point.date,
+            nav: point.equity,
+            net_flow: if i == 0 { config.initial_cash } else { 0.0 },
+            forward_filled: false,
+        })
+        .collect();
+    let risk_free = data.metrics.get(MarketSymbol::DFF.as_str()).map(Vec::as_slice).unwrap_or_default();
+    let final_equity = equity_curve.last().map_or(config.initial_cash, |p| p.equity);
+
+    BacktestStats {
+        final_equity,
+        total_return: final_equity / config.initial_cash - 1.0,
+        performance: performance(&nav, risk_free, PerformancePeriod::Inception, config.to),
+        trade_count: book.trades.len(),
+        total_fees_usd: book.fees,
+        total_slippage_usd: book.slippage,
+        cash_interest_usd: cash_interest,
+        average_exposure: exposure / equity_curve.len().max(1) as f64,
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::signals::{trailing_mean, MarketContext, MarketDataSet, MayerMultiple};
+
+/// Value averaging never buys more than this many regular amounts at once.
+pub const MAX_VALUE_AVERAGING_MULTIPLE: f64 = 3.0;
+
+/// How much of the regular amount goes into each buy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DcaMethod {
+    /// The same amount every time.
+    #[default]
+    Fixed,
+    /// Tops the position up to `amount x buys so far`; buys nothing when it is already above.
+    ValueAveraging,
+    /// More on fear, less on greed, following the stored Fear & Greed Index.
+    FearGreed,
+    /// More when the Mayer multiple is low, less when it is high.
+    MayerMultiple,
+}
+
+impl DcaMethod {
+    pub const ALL: [DcaMethod; 4] =
+        [DcaMethod::Fixed, DcaMethod::ValueAveraging, DcaMethod::FearGreed, DcaMethod::MayerMultiple];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DcaMethod::Fixed => "FIXED",
+            DcaMethod::ValueAveraging => "VALUE_AVERAGING",
+            DcaMethod::FearGreed => "FEAR_GREED",
+            DcaMethod::MayerMultiple => "MAYER_MULTIPLE",
+        }
+    }
+
+    /// Multiplier of the regular amount given what is known at `ctx`. Sentiment-weighted
+    /// methods fall back to 1 when their input is not available yet.
+    pub fn multiplier(&self, asset: &str, ctx: &MarketContext) -> f64 {
+        match self {
+            DcaMethod::Fixed | DcaMethod::ValueAveraging => 1.0,
+            DcaMethod::FearGreed => ctx.metric(MarketSymbol::FearGreedIndex).map_or(1.0, fear_greed_multiplier),
+            DcaMethod::MayerMultiple => mayer_multiple(asset, ctx).map_or(1.0, mayer_multiplier),
+        }
+    }
+}
+
+impl FromStr for DcaMethod {
+    type Err = ParseDcaMethodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "FIXED" => Ok(DcaMethod::Fixed),
+            "VALUE_AVERAGING" | "VA" => Ok(DcaMethod::ValueAveraging),
+            "FEAR_GREED" => Ok(DcaMethod::FearGreed),
+            "MAYER_MULTIPLE" | "MAYER" => Ok(DcaMethod::MayerMultiple),
+            other => Err(ParseDcaMethodError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDcaMethodError(pub String);
+
+impl fmt::Display for ParseDcaMethodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid DCA method: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDcaMethodError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DcaFrequency {
+    Daily,
+    #[default]
+    Weekly,
+    Monthly,
+}
+
+impl DcaFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DcaFrequency::Daily => "DAILY",
+            DcaFrequency::Weekly => "WEEKLY",
+            DcaFrequency::Monthly => "MONTHLY",
+        }
+    }
+
+    /// Date of the `n`th buy (from zero) of a schedule starting on `start`. Monthly buys keep the
+    /// day of month, clamped to shorter months.
+    pub fn nth(&self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            DcaFrequency::Daily => start.checked_add_signed(Duration::days(n as i64)),
+            DcaFrequency::Weekly => start.checked_add_signed(Duration::weeks(n as i64)),
+            DcaFrequency::Monthly => start.checked_add_months(Months::new(n)),
+        }
+    }
+}
+
+impl FromStr for DcaFrequency {
+    type Err = ParseDcaFrequencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "DAILY" => Ok(DcaFrequency::Daily),
+            "WEEKLY" => Ok(DcaFrequency::Weekly),
+            "MONTHLY" => Ok(DcaFrequency::Monthly),
+            other => Err(ParseDcaFrequencyError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDcaFrequencyError(pub String);
+
+impl fmt::Display for ParseDcaFrequencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid DCA frequency: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDcaFrequencyError {}
+
+/// `amount` into `asset` every `frequency`, starting on `start`. Amounts of the plan and its
+/// outcome are in the currency of the closes it is replayed against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcaPlan {
+    pub asset: String,
+    pub amount: f64,
+    pub frequency: DcaFrequency,
+    pub start: NaiveDate,
+    pub method: DcaMethod,
+}
+
+impl DcaPlan {
+    /// Buy dates from `start` through `to`.
+    pub fn dates(&self, to: NaiveDate) -> Vec<NaiveDate> {
+        (0..)
+            .map_while(|n| self.frequency.nth(self.start, n))
+            .take_while(|d| *d <= to)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcaBuy {
+    pub date: NaiveDate,
+    pub price: f64,
+    pub amount: f64,
+    pub quantity: f64,
+    pub multiplier: f64,
+}
+
+/// Outcome of one plan over history, valued at the last close on or before `to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DcaSimulation {
+    pub method: DcaMethod,
+    pub buys: Vec<DcaBuy>,
+    pub invested: f64,
+    pub quantity: f64,
+    pub average_cost: Option<f64>,
+    pub final_value: f64,
+    /// Gain over the amount invested, as a fraction.
+    pub total_return: Option<f64>,
+}
+
+/// Replays `plan` against stored closes. Every buy fills at the last close on or before its
+/// date and sees only what was known by then; dates before the first close are skipped.
+pub fn simulate_dca(plan: &DcaPlan, data: &MarketDataSet, to: NaiveDate) -> DcaSimulation {
+    let mut buys = Vec::new();
+    let mut quantity = 0.0;
+    let mut invested = 0.0;
+    let mut periods = 0;
+
+    for date in plan.dates(to) {
+        let ctx = data.at(date);
+        let Some(price) = ctx.price(&plan.asset) else { continue };
+        periods += 1;
+
+        let (amount, multiplier) = match plan.method {
+            DcaMethod::ValueAveraging => {
+                let target = plan.amount * periods as f64;
+                let gap = (target - quantity * price).clamp(0.0, plan.amount * MAX_VALUE_AVERAGING_MULTIPLE);
+                (gap, gap / plan.amount)
+            }
+            method => {
+                let multiplier = method.multiplier(&plan.asset, &ctx);
+                (plan.amount * multiplier, multiplier)
+            }
+        };
+        if amount <= 0.0 {
+            continue;
+        }
+
+        quantity += amount / price;
+        invested += amount;
+        buys.push(DcaBuy { date, price, amount, quantity: amount / price, multiplier });
+    }
+
+    let final_value = data.at(to).price(&plan.asset).map_or(0.0, |price| quantity * price);
+    DcaSimulation {
+        method: plan.method,
+        buys,
+        invested,
+        quantity,
+        average_cost: (quantity > 0.0).then(|| invested / quantity),
+        final_value,
+        total_return: (invested > 0.0).then(|| final_value / invested - 1.0),
+    }
+}
+
+/// The same plan simulated with every [`DcaMethod`].
+pub fn compare_dca(plan: &DcaPlan, data: &MarketDataSet, to: NaiveDate) -> Vec<DcaSimulation> {
+    DcaMethod::ALL
+        .iter()
+        .map(|method| simulate_dca(&DcaPlan { method: *method, ..plan.clone() }, data, to))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedBuy {
+    pub date: NaiveDate,
+    pub amount: f64,
+    pub multiplier: f64,
+}
+
+/// The next `count` buys after `ctx.as_of()`, sized with what is known today. Value averaging
+/// depends on prices at the time of each buy and is planned at the regular amount.
+pub fn dca_schedule(plan: &DcaPlan, ctx: &MarketContext, count: usize) -> Vec<PlannedBuy> {
+    let multiplier = plan.method.multiplier(&plan.asset, ctx);
+    (0..)
+        .map_while(|n| plan.frequency.nth(plan.start, n))
+        .filter(|d| *d > ctx.as_of())
+        .take(count)
+        .map(|date| PlannedBuy { date, amount: plan.amount * multiplier, multiplier })
+        .collect()
+}
+
+/// Extreme fear doubles the buy, extreme greed halves it.
+fn fear_greed_multiplier(value: f64) -> f64 {
+    match value {
+        v if v <= 20.0 => 2.0,
+        v if v <= 40.0 => 1.5,
+        v if v < 60.0 => 1.0,
+        v if v < 80.0 => 0.75,
+        _ => 0.5,
+    }
+}
+
+/// Doubles the buy below the Mayer undervalued band and halves it above the overheated one.
+fn mayer_multiplier(multiple: f64) -> f64 {
+    match multiple {
+        m if m < MayerMultiple::UNDERVALUED_BELOW => 2.0,
+        m if m < 1.0 => 1.5,
+        m if m < 1.5 => 1.0,
+        m if m < MayerMultiple::OVERHEATED_ABOVE => 0.75,
+        _ => 0.5,
+    }
+}
+
+fn mayer_multiple(asset: &str, ctx: &MarketContext) -> Option<f64> {
+    let closes = ctx.prices(asset);
+    let (_, close) = *closes.last()?;
+    trailing_mean(closes, MayerMultiple::WINDOW_DAYS).map(|ma| close / ma)
+}
@@ -0,0 +1,64 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::portfolio::nav::{price_as_of, PriceHistory};
+use crate::portfolio::portfolio_state::{LedgerError, LedgerTransaction, TransactionKind};
+use crate::portfolio::rebalance::AssetClass;
+
+/// Unit fair-market value of `asset_symbol` on `date`: the last stored close on or before that
+/// day. Stablecoins without a price series of their own are valued at one dollar.
+pub fn fair_market_value(asset_symbol: &str, date: NaiveDate, prices: &PriceHistory) -> Option<f64> {
+    price_as_of(prices, asset_symbol, date)
+        .map(|(_, price)| price)
+        .or_else(|| (AssetClass::of(asset_symbol) == AssetClass::Stablecoin).then_some(1.0))
+}
+
+/// Values income transactions at their fair-market value on the receipt date. A value recorded
+/// on the transaction is only kept for assets `prices` cannot value. Returns the ids of the
+/// transactions that end up without a value.
+pub fn value_income(transactions: &mut [LedgerTransaction], prices: &PriceHistory) -> Vec<Uuid> {
+    let mut unvalued = Vec::new();
+    for tx in transactions.iter_mut().filter(|tx| tx.kind.is_income()) {
+        tx.price_usd = fair_market_value(&tx.asset_symbol, tx.timestamp.date(), prices).or(tx.price_usd);
+        if tx.price_usd.is_none() {
+            unvalued.push(tx.id);
+        }
+    }
+    unvalued
+}
+
+/// One staking reward, interest payment, airdrop or fork credit, valued when it was received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomeRow {
+    pub tx_id: Uuid,
+    pub asset_symbol: String,
+    pub kind: TransactionKind,
+    pub received_on: NaiveDate,
+    pub quantity: f64,
+    pub unit_value: f64,
+    pub value: f64,
+}
+
+/// Income received in `year`, ordered by date, asset and transaction id.
+pub fn income_in_year(transactions: &[LedgerTransaction], year: i32) -> Result<Vec<IncomeRow>, LedgerError> {
+    let mut rows = transactions
+        .iter()
+        .filter(|tx| tx.kind.is_income() && tx.timestamp.year() == year)
+        .map(|tx| {
+            let unit_value = tx.price_usd.ok_or(LedgerError::MissingPrice(tx.id))?;
+            Ok(IncomeRow {
+                tx_id: tx.id,
+                asset_symbol: tx.asset_symbol.clone(),
+                kind: tx.kind,
+                received_on: tx.timestamp.date(),
+                quantity: tx.quantity,
+                unit_value,
+                value: tx.quantity * unit_value,
+            })
+        })
+        .collect::<Result<Vec<_>, LedgerError>>()?;
+
+    rows.sort_by(|a, b| (a.received_on, &a.asset_symbol, a.tx_id).cmp(&(b.received_on, &b.asset_symbol, b.tx_id)));
+    Ok(rows)
+}
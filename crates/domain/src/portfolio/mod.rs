@@ -0,0 +1,15 @@
+pub mod backtest;
+pub mod dca;
+pub mod income;
+pub mod nav;
+pub mod performance;
+pub mod portfolio_state;
+pub mod portfolio_utils;
+pub mod rebalance;
+pub mod signal_rules;
+pub mod signals;
+pub mod tax_report;
+pub mod tax_rules;
+pub mod walk_forward;
+
+pub mod tests;
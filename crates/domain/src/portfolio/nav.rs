@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::portfolio_state::{LedgerError, LedgerTransaction, PortfolioState, TransactionKind, CASH_ASSET};
+
+/// Daily closes per ledger asset, sorted by date.
+pub type PriceHistory = BTreeMap<String, Vec<(NaiveDate, f64)>>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NavPoint {
+    pub date: NaiveDate,
+    pub nav: f64,
+    /// External deposits minus withdrawals booked on this day, valued like `nav`.
+    pub net_flow: f64,
+    /// True when at least one held asset had no close on this day and an older one was used.
+    pub forward_filled: bool,
+}
+
+/// Net asset value for every day in `from..=to`.
+///
+/// Holdings are replayed up to the end of each day and valued at that day's close.
+/// Missing closes (weekends for equities, ingestion gaps) are forward-filled from the last
+/// known close and the day is flagged; assets that were never priced count as zero.
+pub fn daily_nav(
+    transactions: &[LedgerTransaction],
+    prices: &PriceHistory,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<NavPoint>, LedgerError> {
+    let mut sorted: Vec<&LedgerTransaction> = transactions.iter().collect();
+    sorted.sort_by_key(|tx| tx.timestamp);
+
+    let mut state = PortfolioState::new();
+    let mut pending = sorted.into_iter().peekable();
+    let mut points = Vec::new();
+
+    for date in from.iter_days().take_while(|d| *d <= to) {
+        let mut net_flow = 0.0;
+
+        while let Some(tx) = pending.next_if(|tx| tx.timestamp.date() <= date) {
+            state.apply(tx)?;
+            if tx.timestamp.date() >= from && tx.kind.is_external_flow() {
+                let unit = flow_unit_value(tx, prices, date);
+                let sign = if tx.kind == TransactionKind::Deposit { 1.0 } else { -1.0 };
+                net_flow += sign * tx.quantity * unit;
+            }
+        }
+
+        let mut nav = 0.0;
+        let mut forward_filled = false;
+        for (asset, quantity) in state.holdings() {
+            if asset == CASH_ASSET {
+                nav += quantity;
+                continue;
+            }
+            match price_as_of(prices, &asset, date) {
+                Some((price_date, price)) => {
+                    nav += quantity * price;
+                    forward_filled |= price_date != date;
+                }
+                None => forward_filled = true,
+            }
+        }
+
+        points.push(NavPoint { date, nav, net_flow, forward_filled });
+    }
+
+    Ok(points)
+}
+
+/// Last close of `asset` on or before `date`.
+pub fn price_as_of(prices: &PriceHistory, asset: &str, date: NaiveDate) -> Option<(NaiveDate, f64)> {
+    value_as_of(prices.get(asset)?, date)
+}
+
+/// Last observation of a date-sorted series on or before `date`.
+pub(crate) fn value_as_of(series: &[(NaiveDate, f64)], date: NaiveDate) -> Option<(NaiveDate, f64)> {
+    let idx = series.partition_point(|(d, _)| *d <= date);
+    idx.checked_sub(1).map(|i| series[i])
+}
+
+fn flow_unit_value(tx: &LedgerTransaction, prices: &PriceHistory, date: NaiveDate) -> f64 {
+    if tx.asset_symbol == CASH_ASSET {
+        return 1.0;
+    }
+    tx.price_usd
+        .or_else(|| price_as_of(prices, &tx.asset_symbol, date).map(|(_, p)| p))
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::nav::{value_as_of, NavPoint};
+
+/// NAV is valued every calendar day, so returns annualize over 365 days.
+pub const DAYS_PER_YEAR: f64 = 365.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PerformancePeriod {
+    #[serde(rename = "MTD")]
+    MonthToDate,
+    #[serde(rename = "QTD")]
+    QuarterToDate,
+    #[serde(rename = "YTD")]
+    YearToDate,
+    #[serde(rename = "1Y")]
+    OneYear,
+    #[serde(rename = "INCEPTION")]
+    Inception,
+}
+
+impl PerformancePeriod {
+    pub const ALL: [PerformancePeriod; 5] = [
+        PerformancePeriod::MonthToDate,
+        PerformancePeriod::QuarterToDate,
+        PerformancePeriod::YearToDate,
+        PerformancePeriod::OneYear,
+        PerformancePeriod::Inception,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PerformancePeriod::MonthToDate => "MTD",
+            PerformancePeriod::QuarterToDate => "QTD",
+            PerformancePeriod::YearToDate => "YTD",
+            PerformancePeriod::OneYear => "1Y",
+            PerformancePeriod::Inception => "INCEPTION",
+        }
+    }
+
+    /// First day of the period ending on `as_of`, never earlier than `inception`.
+    pub fn start(&self, as_of: NaiveDate, inception: NaiveDate) -> NaiveDate {
+        let start = match self {
+            PerformancePeriod::MonthToDate => as_of.with_day(1),
+            PerformancePeriod::QuarterToDate => {
+                NaiveDate::from_ymd_opt(as_of.year(), (as_of.month0() / 3) * 3 + 1, 1)
+            }
+            PerformancePeriod::YearToDate => NaiveDate::from_ymd_opt(as_of.year(), 1, 1),
+            PerformancePeriod::OneYear => as_of.checked_sub_months(Months::new(12)).map(|d| d + Duration::days(1)),
+            PerformancePeriod::Inception => Some(inception),
+        };
+        start.unwrap_or(inception).max(inception)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsePerformancePeriodError(pub String);
+
+impl fmt::Display for ParsePerformancePeriodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid PerformancePeriod: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParsePerformancePeriodError {}
+
+impl FromStr for PerformancePeriod {
+    type Err = ParsePerformancePeriodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "MTD" => Ok(PerformancePeriod::MonthToDate),
+            "QTD" => Ok(PerformancePeriod::QuarterToDate),
+            "YTD" => Ok(PerformancePeriod::YearToDate),
+            "1Y" => Ok(PerformancePeriod::OneYear),
+            "INCEPTION" | "ITD" => Ok(PerformancePeriod::Inception),
+            other => Err(ParsePerformancePeriodError(other.to_string())),
+        }
+    }
+}
+
+/// Return and risk figures of one portfolio over one period. Returns and drawdowns are
+/// fractions (`0.12` is 12%); drawdowns are negative.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub period: PerformancePeriod,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Cumulative return with external flows neutralised.
+    pub time_weighted_return: f64,
+    /// Annualized XIRR of the opening value, the flows and the closing value.
+    pub money_weighted_return: Option<f64>,
+    pub annualized_volatility: f64,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub max_drawdown: f64,
+    /// Longest stretch in days spent below a previous high of the time-weighted index.
+    pub max_drawdown_days: i64,
+}
+
+/// Computes the report for `period` ending on `as_of` from a date-sorted NAV series.
+///
+/// `risk_free` is the annual rate in percent (the `DFF` series), forward-filled per day.
+/// Returns `None` when the series has no point inside the period.
+pub fn performance(
+    nav: &[NavPoint],
+    risk_free: &[(NaiveDate, f64)],
+    period: PerformancePeriod,
+    as_of: NaiveDate,
+) -> Option<PerformanceReport> {
+    let inception = nav.first()?.date;
+    let to = nav.last()?.date.min(as_of);
+    let from = period.start(to, inception);
+
+    let start = nav.partition_point(|p| p.date < from);
+    let end = nav.partition_point(|p| p.date <= to);
+    let window = nav.get(start..end).filter(|w| !w.is_empty())?;
+    let opening = start.checked_sub(1).map(|i| &nav[i]);
+
+    let returns = daily_returns(opening.map_or(0.0, |p| p.nav), window);
+    let excess: Vec<f64> = returns
+        .iter()
+        .map(|(date, r)| r - value_as_of(risk_free, *date).map_or(0.0, |(_, rate)| rate / 100.0 / DAYS_PER_YEAR))
+        .collect();
+
+    let time_weighted_return = returns.iter().fold(1.0, |acc, (_, r)| acc * (1.0 + r)) - 1.0;
+    let daily_volatility = std_dev(returns.iter().map(|(_, r)| *r));
+    let annualized_volatility = daily_volatility * DAYS_PER_YEAR.sqrt();
+
+    let mean_excess = mean(&excess);
+    let sharpe_ratio = (excess.len() > 1 && daily_volatility > 0.0)
+        .then(|| mean_excess / daily_volatility * DAYS_PER_YEAR.sqrt());
+    let downside = downside_deviation(&excess);
+    let sortino_ratio = (excess.len() > 1 && downside > 0.0)
+        .then(|| mean_excess / downside * DAYS_PER_YEAR.sqrt());
+
+    let (max_drawdown, max_drawdown_days) = max_drawdown(from, &returns);
+
+    let mut flows = Vec::with_capacity(window.len() + 2);
+    if let Some(opening) = opening.filter(|p| p.nav > 0.0) {
+        flows.push((opening.date, -opening.nav));
+    }
+    flows.extend(window.iter().filter(|p| p.net_flow != 0.0).map(|p| (p.date, -p.net_flow)));
+    flows.push((to, window[window.len() - 1].nav));
+
+    Some(PerformanceReport {
+        period,
+        from,
+        to,
+        time_weighted_return,
+        money_weighted_return: xirr(&flows),
+        annualized_volatility,
+        sharpe_ratio,
+        sortino_ratio,
+        max_drawdown,
+        max_drawdown_days,
+    })
+}
+
+/// Daily returns with each day's flow treated as arriving before the close it is valued at.
+/// Days without invested capital are left out.
+fn daily_returns(opening_nav: f64, window: &[NavPoint]) -> Vec<(NaiveDate, f64)> {
+    let mut previous = opening_nav;
+    let mut returns = Vec::with_capacity(window.len());
+
+    for point in window {
+        let invested = previous + point.net_flow;
+        if invested > 0.0 {
+            returns.push((point.date, point.nav / invested - 1.0));
+        }
+        previous = point.nav;
+    }
+
+    returns
+}
+
+/// Deepest fall of the time-weighted index below its running high, and the longest time
+/// spent under water.
+fn max_drawdown(from: NaiveDate, returns: &[(NaiveDate, f64)]) -> (f64, i64) {
+    let mut index = 1.0;
+    let mut peak = 1.0;
+    let mut peak_date = from;
+    let mut deepest: f64 = 0.0;
+    let mut longest = 0;
+
+    for (date, r) in returns {
+        index *= 1.0 + r;
+        if index >= peak {
+            peak = index;
+            peak_date = *date;
+        } else {
+            deepest = deepest.min(index / peak - 1.0);
+            longest = longest.max((*date - peak_date).num_days());
+        }
+    }
+
+    (deepest, longest)
+}
+
+/// Annualized internal rate of return of dated cash flows (negative = paid in).
+pub fn xirr(flows: &[(NaiveDate, f64)]) -> Option<f64> {
+    let first = flows.iter().map(|(d, _)| *d).min()?;
+    let has_inflow = flows.iter().any(|(_, v)| *v > 0.0);
+    let has_outflow = flows.iter().any(|(_, v)| *v < 0.0);
+    if !has_inflow || !has_outflow {
+        return None;
+    }
+
+    let years: Vec<(f64, f64)> = flows
+        .iter()
+        .map(|(d, v)| ((*d - first).num_days() as f64 / DAYS_PER_YEAR, *v))
+        .collect();
+    let npv = |rate: f64| years.iter().map(|(t, v)| v / (1.0 + rate).powf(*t)).sum::<f64>();
+    let d_npv = |rate: f64| years.iter().map(|(t, v)| -t * v / (1.0 + rate).powf(t + 1.0)).sum::<f64>();
+
+    let mut rate = 0.1;
+    for _ in 0..100 {
+        let value = npv(rate);
+        let slope = d_npv(rate);
+        if !value.is_finite() || !slope.is_finite() || slope == 0.0 {
+            break;
+        }
+        let next = rate - value / slope;
+        if next <= -1.0 || !next.is_finite() {
+            break;
+        }
+        if (next - rate).abs() < 1e-10 {
+            return Some(next);
+        }
+        rate = next;
+    }
+
+    // Newton can overshoot on short or lopsided periods; fall back to bisection.
+    let (mut low, mut high) = (-0.999_999, 1e6);
+    if npv(low).signum() == npv(high).signum() {
+        return None;
+    }
+    for _ in 0..300 {
+        let mid = (low + high) / 2.0;
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn std_dev(values: impl Iterator<Item = f64>) -> f64 {
+    let values: Vec<f64> = values.collect();
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(&values);
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    variance.sqrt()
+}
+
+fn downside_deviation(excess: &[f64]) -> f64 {
+    if excess.is_empty() {
+        return 0.0;
+    }
+    let squares = excess.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>();
+    (squares / excess.len() as f64).sqrt()
+}
@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::portfolio::portfolio_utils::CostBasisMethod;
+use crate::portfolio::tax_rules::Jurisdiction;
+
+/// Ledger asset used for fiat cash. Buys are paid from it and sells settle into it.
+pub const CASH_ASSET: &str = "USD";
+/// Width of `ledger_transactions.asset_symbol`.
+pub const MAX_ASSET_SYMBOL_LEN: usize = 16;
+/// Width of `ledger_transactions.external_id`.
+pub const MAX_EXTERNAL_ID_LEN: usize = 128;
+/// Width of `accounts.name`.
+pub const MAX_ACCOUNT_NAME_LEN: usize = 128;
+/// Width of `accounts.venue`.
+pub const MAX_ACCOUNT_VENUE_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub id: Uuid,
+    pub name: String,
+    pub cost_basis_method: CostBasisMethod,
+    pub tax_jurisdiction: Jurisdiction,
+    /// Portfolios without an owner are only reachable from the worker and the CLI.
+    pub owner_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+/// An account is a place where assets are held (an exchange, a wallet, a bank).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub id: Uuid,
+    pub portfolio_id: Uuid,
+    pub name: String,
+    pub venue: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TransactionKind {
+    Buy,
+    Sell,
+    Transfer,
+    Fee,
+    Deposit,
+    Withdrawal,
+    StakingReward,
+    LendingInterest,
+    Airdrop,
+    Fork,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Buy => "BUY",
+            TransactionKind::Sell => "SELL",
+            TransactionKind::Transfer => "TRANSFER",
+            TransactionKind::Fee => "FEE",
+            TransactionKind::Deposit => "DEPOSIT",
+            TransactionKind::Withdrawal => "WITHDRAWAL",
+            TransactionKind::StakingReward => "STAKING_REWARD",
+            TransactionKind::LendingInterest => "LENDING_INTEREST",
+            TransactionKind::Airdrop => "AIRDROP",
+            TransactionKind::Fork => "FORK",
+        }
+    }
+
+    /// Deposits and withdrawals move value across the portfolio boundary.
+    pub fn is_external_flow(&self) -> bool {
+        matches!(self, TransactionKind::Deposit | TransactionKind::Withdrawal)
+    }
+
+    /// Assets received for nothing. They are income at their fair-market value on the day they
+    /// arrive, and that value is also their cost basis.
+    pub fn is_income(&self) -> bool {
+        matches!(
+            self,
+            TransactionKind::StakingReward | TransactionKind::LendingInterest | TransactionKind::Airdrop | TransactionKind::Fork
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseTransactionKindError(pub String);
+
+impl fmt::Display for ParseTransactionKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid TransactionKind: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTransactionKindError {}
+
+impl FromStr for TransactionKind {
+    type Err = ParseTransactionKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "BUY" => Ok(TransactionKind::Buy),
+            "SELL" => Ok(TransactionKind::Sell),
+            "TRANSFER" => Ok(TransactionKind::Transfer),
+            "FEE" => Ok(TransactionKind::Fee),
+            "DEPOSIT" => Ok(TransactionKind::Deposit),
+            "WITHDRAWAL" => Ok(TransactionKind::Withdrawal),
+            "STAKING_REWARD" | "STAKING" => Ok(TransactionKind::StakingReward),
+            "LENDING_INTEREST" | "INTEREST" => Ok(TransactionKind::LendingInterest),
+            "AIRDROP" => Ok(TransactionKind::Airdrop),
+            "FORK" => Ok(TransactionKind::Fork),
+            other => Err(ParseTransactionKindError(other.to_string())),
+        }
+    }
+}
+
+/// A single ledger entry.
+///
+/// `quantity` is always positive; the direction comes from `kind`.
+/// `price_usd` is the unit price for buys and sells and the unit fair-market value for income,
+/// `fee_usd` is charged in cash.
+/// Transfers move `quantity` from `account_id` to `counterparty_account_id`.
+/// `external_id` identifies imported rows so that re-importing a file is a no-op.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerTransaction {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub timestamp: NaiveDateTime,
+    pub kind: TransactionKind,
+    pub asset_symbol: String,
+    pub quantity: f64,
+    pub price_usd: Option<f64>,
+    pub fee_usd: Option<f64>,
+    pub counterparty_account_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub external_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerError {
+    NonPositiveQuantity(Uuid),
+    MissingPrice(Uuid),
+    MissingCounterparty(Uuid),
+    InsufficientBalance { tx_id: Uuid, asset: String, available: f64, requested: f64 },
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::NonPositiveQuantity(id) => write!(f, "Transaction {id} has a non-positive quantity"),
+            LedgerError::MissingPrice(id) => write!(f, "Transaction {id} is a trade or income without a price"),
+            LedgerError::MissingCounterparty(id) => write!(f, "Transfer {id} has no counterparty account"),
+            LedgerError::InsufficientBalance { tx_id, asset, available, requested } => write!(
+                f,
+                "Transaction {tx_id} needs {requested} {asset} but only {available} is available"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub account_id: Uuid,
+    pub asset_symbol: String,
+    pub quantity: f64,
+}
+
+/// Holdings derived by replaying ledger transactions in order.
+#[derive(Debug, Clone, Default)]
+pub struct PortfolioState {
+    balances: BTreeMap<(Uuid, String), f64>,
+}
+
+impl PortfolioState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replays `transactions` in timestamp order.
+    pub fn from_transactions(transactions: &[LedgerTransaction]) -> Result<Self, LedgerError> {
+        let mut sorted: Vec<&LedgerTransaction> = transactions.iter().collect();
+        sorted.sort_by_key(|tx| tx.timestamp);
+
+        let mut state = Self::new();
+        for tx in sorted {
+            state.apply(tx)?;
+        }
+        Ok(state)
+    }
+
+    pub fn apply(&mut self, tx: &LedgerTransaction) -> Result<(), LedgerError> {
+        if tx.quantity <= 0.0 {
+            return Err(LedgerError::NonPositiveQuantity(tx.id));
+        }
+        let fee = tx.fee_usd.unwrap_or(0.0);
+
+        match tx.kind {
+            TransactionKind::Buy => {
+                let price = tx.price_usd.ok_or(LedgerError::MissingPrice(tx.id))?;
+                self.credit(tx.account_id, &tx.asset_symbol, tx.quantity);
+                self.credit(tx.account_id, CASH_ASSET, -(tx.quantity * price + fee));
+            }
+            TransactionKind::Sell => {
+                let price = tx.price_usd.ok_or(LedgerError::MissingPrice(tx.id))?;
+                self.debit(tx, tx.account_id, &tx.asset_symbol, tx.quantity)?;
+                self.credit(tx.account_id, CASH_ASSET, tx.quantity * price - fee);
+            }
+            TransactionKind::Transfer => {
+                let to = tx.counterparty_account_id.ok_or(LedgerError::MissingCounterparty(tx.id))?;
+                self.debit(tx, tx.account_id, &tx.asset_symbol, tx.quantity)?;
+                self.credit(to, &tx.asset_symbol, tx.quantity);
+                self.credit(tx.account_id, CASH_ASSET, -fee);
+            }
+            TransactionKind::Fee | TransactionKind::Withdrawal => {
+                self.debit(tx, tx.account_id, &tx.asset_symbol, tx.quantity)?;
+                self.credit(tx.account_id, CASH_ASSET, -fee);
+            }
+            TransactionKind::Deposit
+            | TransactionKind::StakingReward
+            | TransactionKind::LendingInterest
+            | TransactionKind::Airdrop
+            | TransactionKind::Fork => {
+                self.credit(tx.account_id, &tx.asset_symbol, tx.quantity);
+                self.credit(tx.account_id, CASH_ASSET, -fee);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn balance(&self, account_id: Uuid, asset_symbol: &str) -> f64 {
+        self.balances
+            .get(&(account_id, asset_symbol.to_string()))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Per-account positions, skipping empty balances.
+    pub fn positions(&self) -> Vec<Position> {
+        self.balances
+            .iter()
+            .filter(|(_, qty)| qty.abs() > f64::EPSILON)
+            .map(|((account_id, asset), qty)| Position {
+                account_id: *account_id,
+                asset_symbol: asset.clone(),
+                quantity: *qty,
+            })
+            .collect()
+    }
+
+    /// Quantities aggregated over all accounts, keyed by asset symbol.
+    pub fn holdings(&self) -> BTreeMap<String, f64> {
+        let mut holdings = BTreeMap::new();
+        for ((_, asset), qty) in &self.balances {
+            *holdings.entry(asset.clone()).or_insert(0.0) += qty;
+        }
+        holdings.retain(|_, qty| qty.abs() > f64::EPSILON);
+        holdings
+    }
+
+    fn credit(&mut self, account_id: Uuid, asset_symbol: &str, quantity: f64) {
+        if quantity == 0.0 {
+            return;
+        }
+        *self.balances.entry((account_id, asset_symbol.to_string())).or_insert(0.0) += quantity;
+    }
+
+    /// Cash may go negative (trades recorded without the funding deposit), other assets may not.
+    fn debit(&mut self, tx: &LedgerTransaction, account_id: Uuid, asset_symbol: &str, quantity: f64) -> Result<(), LedgerError> {
+        let available = self.balance(account_id, asset_symbol);
+        if asset_symbol != CASH_ASSET && available + 1e-9 < quantity {
+            return Err(LedgerError::InsufficientBalance {
+                tx_id: tx.id,
+                asset: asset_symbol.to_string(),
+                available,
+                requested: quantity,
+            });
+        }
+        self.credit(account_id, asset_symbol, -quantity);
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::MarketSymbol;
+use crate::currency::{ConversionError, CurrencyConverter};
+use crate::portfolio::portfolio_state::{LedgerError, LedgerTransaction, PortfolioState, TransactionKind, CASH_ASSET};
+
+/// Aggregated holdings after replaying every transaction up to and including `as_of`.
+pub fn holdings_as_of(
+    transactions: &[LedgerTransaction],
+    as_of: NaiveDate,
+) -> Result<BTreeMap<String, f64>, LedgerError> {
+    let until: Vec<LedgerTransaction> = transactions
+        .iter()
+        .filter(|tx| tx.timestamp.date() <= as_of)
+        .cloned()
+        .collect();
+
+    Ok(PortfolioState::from_transactions(&until)?.holdings())
+}
+
+/// Maps a ledger asset (`BTC`, `GOLD`) to the `market_data` series that prices it in USD.
+pub fn price_symbol(asset_symbol: &str) -> Option<MarketSymbol> {
+    MarketSymbol::from_str(&format!("{}_USD", asset_symbol)).ok()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CostBasisMethod {
+    #[default]
+    Fifo,
+    Lifo,
+    Hifo,
+    AverageCost,
+}
+
+impl CostBasisMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CostBasisMethod::Fifo => "FIFO",
+            CostBasisMethod::Lifo => "LIFO",
+            CostBasisMethod::Hifo => "HIFO",
+            CostBasisMethod::AverageCost => "AVERAGE_COST",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseCostBasisMethodError(pub String);
+
+impl fmt::Display for ParseCostBasisMethodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid CostBasisMethod: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCostBasisMethodError {}
+
+impl FromStr for CostBasisMethod {
+    type Err = ParseCostBasisMethodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "FIFO" => Ok(CostBasisMethod::Fifo),
+            "LIFO" => Ok(CostBasisMethod::Lifo),
+            "HIFO" => Ok(CostBasisMethod::Hifo),
+            "AVERAGE_COST" | "AVG" => Ok(CostBasisMethod::AverageCost),
+            other => Err(ParseCostBasisMethodError(other.to_string())),
+        }
+    }
+}
+
+/// An open acquisition that has not been fully disposed of yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lot {
+    pub tx_id: Uuid,
+    pub asset_symbol: String,
+    pub acquired_at: NaiveDateTime,
+    pub quantity: f64,
+    pub unit_cost_usd: f64,
+}
+
+/// The part of a disposal matched against a single lot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedGain {
+    pub asset_symbol: String,
+    pub acquisition_tx_id: Uuid,
+    pub disposal_tx_id: Uuid,
+    pub acquired_at: NaiveDateTime,
+    pub disposed_at: NaiveDateTime,
+    pub quantity: f64,
+    pub proceeds_usd: f64,
+    pub cost_basis_usd: f64,
+    pub gain_usd: f64,
+}
+
+/// Amounts are in USD unless converted; the response carrying them names the currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetPnl {
+    pub asset_symbol: String,
+    pub quantity: f64,
+    pub cost_basis: f64,
+    pub realized_pnl: f64,
+    pub price: Option<f64>,
+    pub market_value: Option<f64>,
+    pub unrealized_pnl: Option<f64>,
+}
+
+/// Lot tracking over the whole portfolio. Transfers between accounts keep their lots.
+#[derive(Debug, Clone, Default)]
+pub struct LotBook {
+    method: CostBasisMethod,
+    lots: BTreeMap<String, Vec<Lot>>,
+    realized: Vec<RealizedGain>,
+}
+
+impl LotBook {
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self { method, ..Self::default() }
+    }
+
+    /// Replays `transactions` in timestamp order under `method`.
+    pub fn from_transactions(transactions: &[LedgerTransaction], method: CostBasisMethod) -> Result<Self, LedgerError> {
+        let mut sorted: Vec<&LedgerTransaction> = transactions.iter().collect();
+        sorted.sort_by_key(|tx| tx.timestamp);
+
+        let mut book = Self::new(method);
+        for tx in sorted {
+            book.apply(tx)?;
+        }
+        Ok(book)
+    }
+
+    pub fn method(&self) -> CostBasisMethod {
+        self.method
+    }
+
+    pub fn apply(&mut self, tx: &LedgerTransaction) -> Result<(), LedgerError> {
+        if tx.asset_symbol == CASH_ASSET {
+            return Ok(());
+        }
+        if tx.quantity <= 0.0 {
+            return Err(LedgerError::NonPositiveQuantity(tx.id));
+        }
+        let fee = tx.fee_usd.unwrap_or(0.0);
+
+        match tx.kind {
+            TransactionKind::Buy => {
+                let price = tx.price_usd.ok_or(LedgerError::MissingPrice(tx.id))?;
+                self.acquire(tx, (tx.quantity * price + fee) / tx.quantity);
+            }
+            TransactionKind::Deposit => {
+                // Deposits carry their fair-market value when known, otherwise a zero basis.
+                self.acquire(tx, tx.price_usd.unwrap_or(0.0));
+            }
+            TransactionKind::StakingReward
+            | TransactionKind::LendingInterest
+            | TransactionKind::Airdrop
+            | TransactionKind::Fork => {
+                // The fair-market value taxed as income is the basis of the new lot.
+                let value = tx.price_usd.ok_or(LedgerError::MissingPrice(tx.id))?;
+                self.acquire(tx, value);
+            }
+            TransactionKind::Sell => {
+                let price = tx.price_usd.ok_or(LedgerError::MissingPrice(tx.id))?;
+                self.dispose(tx, Some(tx.quantity * price - fee))?;
+            }
+            TransactionKind::Fee => {
+                let proceeds = tx.price_usd.unwrap_or(0.0) * tx.quantity;
+                self.dispose(tx, Some(proceeds))?;
+            }
+            TransactionKind::Withdrawal => {
+                self.dispose(tx, None)?;
+            }
+            TransactionKind::Transfer => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn open_lots(&self, asset_symbol: &str) -> &[Lot] {
+        self.lots.get(asset_symbol).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn realized(&self) -> &[RealizedGain] {
+        &self.realized
+    }
+
+    /// Realized and unrealized PnL per asset. `prices` maps asset symbols to their latest USD close;
+    /// assets without a price get no unrealized figure.
+    pub fn pnl(&self, prices: &BTreeMap<String, f64>) -> Vec<AssetPnl> {
+        let mut assets: Vec<&String> = self.lots.keys().collect();
+        for gain in &self.realized {
+            if !self.lots.contains_key(&gain.asset_symbol) {
+                assets.push(&gain.asset_symbol);
+            }
+        }
+        assets.sort();
+        assets.dedup();
+
+        assets
+            .into_iter()
+            .map(|asset| {
+                let lots = self.open_lots(asset);
+                let quantity: f64 = lots.iter().map(|l| l.quantity).sum();
+                let cost_basis: f64 = lots.iter().map(|l| l.quantity * l.unit_cost_usd).sum();
+                let realized_pnl = self.realized
+                    .iter()
+                    .filter(|g| &g.asset_symbol == asset)
+                    .map(|g| g.gain_usd)
+                    .sum();
+                let price = prices.get(asset).copied();
+                let market_value = price.map(|p| p * quantity);
+
+                AssetPnl {
+                    asset_symbol: asset.clone(),
+                    quantity,
+                    cost_basis,
+                    realized_pnl,
+                    price,
+                    market_value,
+                    unrealized_pnl: market_value.map(|v| v - cost_basis),
+                }
+            })
+            .collect()
+    }
+
+    /// [`Self::pnl`] in the currency of `converter`. Lot costs are converted at the rate of their
+    /// acquisition date and realized proceeds at that of the disposal, like the tax report does;
+    /// prices and market values at the rate of `as_of`.
+    pub fn pnl_in_currency(
+        &self,
+        prices: &BTreeMap<String, f64>,
+        converter: &CurrencyConverter,
+        as_of: NaiveDate,
+    ) -> Result<Vec<AssetPnl>, ConversionError> {
+        let rate = converter.rate_as_of(as_of)?;
+        self.pnl(prices)
+            .into_iter()
+            .map(|pnl| {
+                let cost_basis = self
+                    .open_lots(&pnl.asset_symbol)
+                    .iter()
+                    .map(|l| converter.convert(l.quantity * l.unit_cost_usd, l.acquired_at.date()))
+                    .sum::<Result<f64, _>>()?;
+                let realized_pnl = self.realized
+                    .iter()
+                    .filter(|g| g.asset_symbol == pnl.asset_symbol)
+                    .map(|g| {
+                        Ok(converter.convert(g.proceeds_usd, g.disposed_at.date())?
+                            - converter.convert(g.cost_basis_usd, g.acquired_at.date())?)
+                    })
+                    .sum::<Result<f64, _>>()?;
+                let market_value = pnl.market_value.map(|v| v / rate);
+
+                Ok(AssetPnl {
+                    cost_basis,
+                    realized_pnl,
+                    price: pnl.price.map(|p| p / rate),
+                    market_value,
+                    unrealized_pnl: market_value.map(|v| v - cost_basis),
+                    ..pnl
+                })
+            })
+            .collect()
+    }
+
+    fn acquire(&mut self, tx: &LedgerTransaction, unit_cost_usd: f64) {
+        self.lots.entry(tx.asset_symbol.clone()).or_default().push(Lot {
+            tx_id: tx.id,
+            asset_symbol: tx.asset_symbol.clone(),
+            acquired_at: tx.timestamp,
+            quantity: tx.quantity,
+            unit_cost_usd,
+        });
+    }
+
+    /// Consumes lots in the order given by the method. `proceeds` is `None` for disposals that
+    /// leave the portfolio without realizing anything (withdrawals).
+    fn dispose(&mut self, tx: &LedgerTransaction, proceeds: Option<f64>) -> Result<(), LedgerError> {
+        let method = self.method;
+        let lots = self.lots.entry(tx.asset_symbol.clone()).or_default();
+
+        let available: f64 = lots.iter().map(|l| l.quantity).sum();
+        if available + 1e-9 < tx.quantity {
+            return Err(LedgerError::InsufficientBalance {
+                tx_id: tx.id,
+                asset: tx.asset_symbol.clone(),
+                available,
+                requested: tx.quantity,
+            });
+        }
+
+        match method {
+            CostBasisMethod::Fifo | CostBasisMethod::AverageCost => lots.sort_by_key(|l| l.acquired_at),
+            CostBasisMethod::Lifo => lots.sort_by_key(|l| std::cmp::Reverse(l.acquired_at)),
+            CostBasisMethod::Hifo => lots.sort_by(|a, b| b.unit_cost_usd.total_cmp(&a.unit_cost_usd)),
+        }
+
+        // Average cost pools every open lot at one unit cost, dates still follow FIFO.
+        if method == CostBasisMethod::AverageCost && available > 0.0 {
+            let average = lots.iter().map(|l| l.quantity * l.unit_cost_usd).sum::<f64>() / available;
+            lots.iter_mut().for_each(|l| l.unit_cost_usd = average);
+        }
+
+        let mut remaining = tx.quantity;
+        for lot in lots.iter_mut() {
+            if remaining <= 1e-12 {
+                break;
+            }
+            let matched = remaining.min(lot.quantity);
+            lot.quantity -= matched;
+            remaining -= matched;
+
+            if let Some(total_proceeds) = proceeds {
+                let proceeds_usd = total_proceeds * matched / tx.quantity;
+                let cost_basis_usd = matched * lot.unit_cost_usd;
+                self.realized.push(RealizedGain {
+                    asset_symbol: tx.asset_symbol.clone(),
+                    acquisition_tx_id: lot.tx_id,
+                    disposal_tx_id: tx.id,
+                    acquired_at: lot.acquired_at,
+                    disposed_at: tx.timestamp,
+                    quantity: matched,
+                    proceeds_usd,
+                    cost_basis_usd,
+                    gain_usd: proceeds_usd - cost_basis_usd,
+                });
+            }
+        }
+
+        lots.retain(|l| l.quantity > 1e-12);
+        if lots.is_empty() {
+            self.lots.remove(&tx.asset_symbol);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,577 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::currency::{ConversionError, Currency, CurrencyConverter};
+use crate::portfolio::portfolio_state::CASH_ASSET;
+use crate::portfolio::portfolio_utils::{price_symbol, LotBook};
+use crate::portfolio::tax_rules::{HoldingPeriodRules, HoldingTerm, Jurisdiction};
+use crate::utils::{is_usd_quote, normalize_symbol};
+use crate::MarketSymbol;
+
+/// Tolerance used when a target is given without a band.
+pub const DEFAULT_BAND: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AssetClass {
+    Cash,
+    Stablecoin,
+    Crypto,
+    Equity,
+    Commodity,
+}
+
+impl AssetClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetClass::Cash => "CASH",
+            AssetClass::Stablecoin => "STABLECOIN",
+            AssetClass::Crypto => "CRYPTO",
+            AssetClass::Equity => "EQUITY",
+            AssetClass::Commodity => "COMMODITY",
+        }
+    }
+
+    /// Classifies a ledger asset by the `MarketSymbol` that prices it. Assets outside the
+    /// symbol universe are treated as crypto.
+    pub fn of(asset_symbol: &str) -> Self {
+        if asset_symbol == CASH_ASSET {
+            return AssetClass::Cash;
+        }
+        if is_usd_quote(asset_symbol) {
+            return AssetClass::Stablecoin;
+        }
+        match price_symbol(asset_symbol) {
+            Some(MarketSymbol::Gold | MarketSymbol::Oil) => AssetClass::Commodity,
+            Some(MarketSymbol::Sp500 | MarketSymbol::Nasdaq) => AssetClass::Equity,
+            _ => AssetClass::Crypto,
+        }
+    }
+
+    /// Asset bought for a class target that has no holdings yet.
+    pub fn default_asset(&self) -> &'static str {
+        match self {
+            AssetClass::Cash => CASH_ASSET,
+            AssetClass::Stablecoin => "USDC",
+            AssetClass::Crypto => "BTC",
+            AssetClass::Equity => "SP500",
+            AssetClass::Commodity => "GOLD",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseAssetClassError(pub String);
+
+impl fmt::Display for ParseAssetClassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid AssetClass: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAssetClassError {}
+
+impl FromStr for AssetClass {
+    type Err = ParseAssetClassError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "CASH" => Ok(AssetClass::Cash),
+            "STABLECOIN" => Ok(AssetClass::Stablecoin),
+            "CRYPTO" => Ok(AssetClass::Crypto),
+            "EQUITY" => Ok(AssetClass::Equity),
+            "COMMODITY" => Ok(AssetClass::Commodity),
+            other => Err(ParseAssetClassError(other.to_string())),
+        }
+    }
+}
+
+/// What a target weight applies to: a single asset (`BTC`) or a whole class (`CLASS:EQUITY`).
+/// An asset with its own target is not counted in its class target.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AllocationBucket {
+    Asset(String),
+    Class(AssetClass),
+}
+
+impl AllocationBucket {
+    const CLASS_PREFIX: &'static str = "CLASS:";
+
+    pub fn key(&self) -> String {
+        match self {
+            AllocationBucket::Asset(asset) => asset.clone(),
+            AllocationBucket::Class(class) => format!("{}{}", Self::CLASS_PREFIX, class.as_str()),
+        }
+    }
+}
+
+impl FromStr for AllocationBucket {
+    type Err = ParseAssetClassError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.get(..Self::CLASS_PREFIX.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(Self::CLASS_PREFIX) => {
+                AssetClass::from_str(&s[Self::CLASS_PREFIX.len()..]).map(AllocationBucket::Class)
+            }
+            _ if s.is_empty() => Err(ParseAssetClassError(s.to_string())),
+            _ => Ok(AllocationBucket::Asset(normalize_symbol(s))),
+        }
+    }
+}
+
+impl TryFrom<String> for AllocationBucket {
+    type Error = ParseAssetClassError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        AllocationBucket::from_str(&value)
+    }
+}
+
+impl From<AllocationBucket> for String {
+    fn from(bucket: AllocationBucket) -> Self {
+        bucket.key()
+    }
+}
+
+/// Target weight of a bucket and the tolerance around it, both as fractions of the portfolio
+/// (`weight = 0.6, band = 0.05` keeps the bucket between 55% and 65%).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AllocationTarget {
+    pub bucket: AllocationBucket,
+    pub weight: f64,
+    #[serde(default = "default_band")]
+    pub band: f64,
+}
+
+fn default_band() -> f64 {
+    DEFAULT_BAND
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebalanceError {
+    InvalidTarget(String),
+    DuplicateTarget(String),
+    WeightsDoNotSumToOne(f64),
+    MissingPrice(String),
+    InvalidPrice(String, f64),
+    EmptyPortfolio,
+}
+
+impl fmt::Display for RebalanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebalanceError::InvalidTarget(bucket) => write!(f, "Target {} needs a weight and a band between 0 and 1", bucket),
+            RebalanceError::DuplicateTarget(bucket) => write!(f, "Target {} is defined more than once", bucket),
+            RebalanceError::WeightsDoNotSumToOne(sum) => write!(f, "Target weights sum to {:.4}, expected 1", sum),
+            RebalanceError::MissingPrice(asset) => write!(f, "No price for {}", asset),
+            RebalanceError::InvalidPrice(asset, price) => write!(f, "Price of {} must be positive, got {}", asset, price),
+            RebalanceError::EmptyPortfolio => write!(f, "Portfolio has no value to rebalance"),
+        }
+    }
+}
+
+impl std::error::Error for RebalanceError {}
+
+pub fn validate_targets(targets: &[AllocationTarget]) -> Result<(), RebalanceError> {
+    let mut seen = Vec::with_capacity(targets.len());
+    for target in targets {
+        let valid = |v: f64| v.is_finite() && (0.0..=1.0).contains(&v);
+        if !valid(target.weight) || !valid(target.band) {
+            return Err(RebalanceError::InvalidTarget(target.bucket.key()));
+        }
+        if seen.contains(&&target.bucket) {
+            return Err(RebalanceError::DuplicateTarget(target.bucket.key()));
+        }
+        seen.push(&target.bucket);
+    }
+
+    let sum: f64 = targets.iter().map(|t| t.weight).sum();
+    if (sum - 1.0).abs() > 1e-6 {
+        return Err(RebalanceError::WeightsDoNotSumToOne(sum));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// A lot suggested for a sale and the gain that selling it at the current price realizes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LotSale {
+    pub tx_id: Uuid,
+    pub acquired_at: NaiveDateTime,
+    pub quantity: f64,
+    pub unit_cost: f64,
+    pub gain: f64,
+    pub long_term: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RebalanceTrade {
+    pub asset_symbol: String,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub price: f64,
+    pub value: f64,
+    /// Lots to sell, in the order they were picked; empty for buys.
+    pub lots: Vec<LotSale>,
+    pub estimated_gain: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketDrift {
+    pub bucket: AllocationBucket,
+    pub value: f64,
+    pub weight: f64,
+    pub target_weight: f64,
+    pub band: f64,
+    pub in_band: bool,
+    pub weight_after: f64,
+}
+
+/// Amounts are in `currency`, USD unless converted with [`RebalancePlan::in_currency`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RebalancePlan {
+    pub as_of: NaiveDate,
+    #[serde(default)]
+    pub currency: Currency,
+    pub total_value: f64,
+    pub buckets: Vec<BucketDrift>,
+    /// Sells first, then the buys they fund.
+    pub trades: Vec<RebalanceTrade>,
+    pub estimated_gain: f64,
+}
+
+impl RebalancePlan {
+    /// The same plan in another currency, at the rate of `as_of`. Lot costs are converted at that
+    /// rate too, so the gains are the ones of the trades rather than tax figures.
+    pub fn in_currency(&self, converter: &CurrencyConverter) -> Result<Self, ConversionError> {
+        let rate = converter.rate_as_of(self.as_of)?;
+        Ok(RebalancePlan {
+            as_of: self.as_of,
+            currency: converter.currency(),
+            total_value: self.total_value / rate,
+            buckets: self
+                .buckets
+                .iter()
+                .map(|bucket| BucketDrift { value: bucket.value / rate, ..bucket.clone() })
+                .collect(),
+            trades: self
+                .trades
+                .iter()
+                .map(|trade| RebalanceTrade {
+                    price: trade.price / rate,
+                    value: trade.value / rate,
+                    lots: trade
+                        .lots
+                        .iter()
+                        .map(|lot| LotSale {
+                            unit_cost: lot.unit_cost / rate,
+                            gain: lot.gain / rate,
+                            ..lot.clone()
+                        })
+                        .collect(),
+                    estimated_gain: trade.estimated_gain / rate,
+                    ..trade.clone()
+                })
+                .collect(),
+            estimated_gain: self.estimated_gain / rate,
+        })
+    }
+}
+
+struct Slot {
+    bucket: AllocationBucket,
+    target: f64,
+    band: f64,
+    /// (asset, quantity, price)
+    assets: Vec<(String, f64, f64)>,
+    value: f64,
+    delta: f64,
+}
+
+impl Slot {
+    fn new(bucket: AllocationBucket, target: f64, band: f64) -> Self {
+        Self { bucket, target, band, assets: Vec::new(), value: 0.0, delta: 0.0 }
+    }
+}
+
+/// Proposes the trades that bring every bucket back inside its band.
+///
+/// Out-of-band buckets are traded only to the nearest band edge; USD cash settles every
+/// trade. Cash left above its own band is spread over underweight buckets, a cash shortfall
+/// is raised from overweight ones. Holdings without a target have a target of zero.
+/// Sales pick lots with losses first, then lots that are long-term under the `jurisdiction`'s
+/// holding-period rules, then the lots with the smallest gain per dollar sold. Fees are not modelled.
+pub fn rebalance(
+    holdings: &BTreeMap<String, f64>,
+    prices: &BTreeMap<String, f64>,
+    book: &LotBook,
+    targets: &[AllocationTarget],
+    jurisdiction: Jurisdiction,
+    as_of: NaiveDate,
+) -> Result<RebalancePlan, RebalanceError> {
+    validate_targets(targets)?;
+
+    let mut slots: Vec<Slot> = targets.iter().map(|t| Slot::new(t.bucket.clone(), t.weight, t.band)).collect();
+    let cash_bucket = bucket_for(targets, CASH_ASSET).unwrap_or_else(|| AllocationBucket::Asset(CASH_ASSET.to_string()));
+    let cash = slot_index(&mut slots, &cash_bucket);
+
+    for (asset, quantity) in holdings.iter().filter(|(_, q)| q.abs() > 1e-12) {
+        let price = asset_price(prices, asset)?;
+        let bucket = bucket_for(targets, asset).unwrap_or_else(|| AllocationBucket::Asset(asset.clone()));
+        let slot = slot_index(&mut slots, &bucket);
+        slots[slot].assets.push((asset.clone(), *quantity, price));
+        slots[slot].value += quantity * price;
+    }
+
+    let total: f64 = slots.iter().map(|s| s.value).sum();
+    if total <= 0.0 {
+        return Err(RebalanceError::EmptyPortfolio);
+    }
+
+    for (i, slot) in slots.iter_mut().enumerate() {
+        if i == cash {
+            continue;
+        }
+        let lower = (slot.target - slot.band) * total;
+        let upper = (slot.target + slot.band) * total;
+        slot.delta = if slot.value > upper {
+            upper - slot.value
+        } else if slot.value < lower {
+            lower - slot.value
+        } else {
+            0.0
+        };
+    }
+
+    let net_trades: f64 = slots.iter().map(|s| s.delta).sum();
+    let cash_after = slots[cash].value - net_trades;
+    let cash_lower = (slots[cash].target - slots[cash].band) * total;
+    let cash_upper = (slots[cash].target + slots[cash].band) * total;
+
+    // Fill towards the targets first and only then up to the far band edge.
+    if cash_after > cash_upper {
+        let mut excess = cash_after - cash_upper;
+        for edge in [0.0, 1.0] {
+            let rooms: Vec<f64> = slots
+                .iter()
+                .enumerate()
+                .map(|(i, s)| if i == cash { 0.0 } else { ((s.target + edge * s.band) * total - s.value - s.delta).max(0.0) })
+                .collect();
+            for (slot, fill) in slots.iter_mut().zip(spread(excess, &rooms)) {
+                slot.delta += fill;
+                excess -= fill;
+            }
+        }
+    } else if cash_after < cash_lower {
+        let mut shortfall = cash_lower - cash_after;
+        for edge in [0.0, 1.0] {
+            let rooms: Vec<f64> = slots
+                .iter()
+                .enumerate()
+                .map(|(i, s)| if i == cash { 0.0 } else { (s.value + s.delta - (s.target - edge * s.band) * total).max(0.0) })
+                .collect();
+            for (slot, cut) in slots.iter_mut().zip(spread(shortfall, &rooms)) {
+                slot.delta -= cut;
+                shortfall -= cut;
+            }
+        }
+    }
+    slots[cash].delta = -slots.iter().enumerate().filter(|(i, _)| *i != cash).map(|(_, s)| s.delta).sum::<f64>();
+
+    let rules = jurisdiction.rules();
+    let mut sells = Vec::new();
+    let mut buys = Vec::new();
+    for (i, slot) in slots.iter().enumerate() {
+        if i == cash || slot.delta.abs() <= 1e-9 * total {
+            continue;
+        }
+        if slot.delta < 0.0 {
+            sells.extend(sell_trades(slot, -slot.delta, book, rules.as_ref(), as_of));
+        } else {
+            buys.extend(buy_trades(slot, slot.delta, prices)?);
+        }
+    }
+
+    let buckets = slots
+        .iter()
+        .map(|s| BucketDrift {
+            bucket: s.bucket.clone(),
+            value: s.value,
+            weight: s.value / total,
+            target_weight: s.target,
+            band: s.band,
+            in_band: (s.value / total - s.target).abs() <= s.band + 1e-9,
+            weight_after: (s.value + s.delta) / total,
+        })
+        .collect();
+
+    let mut trades = sells;
+    trades.extend(buys);
+    let estimated_gain = trades.iter().map(|t| t.estimated_gain).sum();
+
+    Ok(RebalancePlan {
+        as_of,
+        currency: Currency::Usd,
+        total_value: total,
+        buckets,
+        trades,
+        estimated_gain,
+    })
+}
+
+fn bucket_for(targets: &[AllocationTarget], asset: &str) -> Option<AllocationBucket> {
+    let by_asset = AllocationBucket::Asset(asset.to_string());
+    let by_class = AllocationBucket::Class(AssetClass::of(asset));
+
+    [by_asset, by_class].into_iter().find(|bucket| targets.iter().any(|t| &t.bucket == bucket))
+}
+
+fn slot_index(slots: &mut Vec<Slot>, bucket: &AllocationBucket) -> usize {
+    match slots.iter().position(|s| &s.bucket == bucket) {
+        Some(i) => i,
+        None => {
+            slots.push(Slot::new(bucket.clone(), 0.0, 0.0));
+            slots.len() - 1
+        }
+    }
+}
+
+fn asset_price(prices: &BTreeMap<String, f64>, asset: &str) -> Result<f64, RebalanceError> {
+    match prices.get(asset) {
+        Some(price) if price.is_finite() && *price > 0.0 => Ok(*price),
+        Some(price) => Err(RebalanceError::InvalidPrice(asset.to_string(), *price)),
+        None if is_usd_quote(asset) => Ok(1.0),
+        None => Err(RebalanceError::MissingPrice(asset.to_string())),
+    }
+}
+
+/// Splits `amount` over the slots in proportion to their room, never past it.
+fn spread(amount: f64, rooms: &[f64]) -> Vec<f64> {
+    let total: f64 = rooms.iter().sum();
+    if amount <= 0.0 || total <= 0.0 {
+        return vec![0.0; rooms.len()];
+    }
+    let share = (amount / total).min(1.0);
+    rooms.iter().map(|room| room * share).collect()
+}
+
+/// Buys are split over the bucket's current holdings by value.
+fn buy_trades(slot: &Slot, amount: f64, prices: &BTreeMap<String, f64>) -> Result<Vec<RebalanceTrade>, RebalanceError> {
+    let held: Vec<(String, f64, f64)> = slot.assets.iter().filter(|(_, q, p)| q * p > 0.0).cloned().collect();
+
+    let legs: Vec<(String, f64, f64)> = if held.is_empty() {
+        let asset = match &slot.bucket {
+            AllocationBucket::Asset(asset) => asset.clone(),
+            AllocationBucket::Class(class) => class.default_asset().to_string(),
+        };
+        let price = asset_price(prices, &asset)?;
+        vec![(asset, price, amount)]
+    } else {
+        held.iter().map(|(asset, q, p)| (asset.clone(), *p, amount * q * p / slot.value)).collect()
+    };
+
+    Ok(legs
+        .into_iter()
+        .map(|(asset_symbol, price, value)| RebalanceTrade {
+            asset_symbol,
+            side: TradeSide::Buy,
+            quantity: value / price,
+            price,
+            value,
+            lots: Vec::new(),
+            estimated_gain: 0.0,
+        })
+        .collect())
+}
+
+/// Sells `amount` worth of the bucket, picking lots across all of its assets.
+fn sell_trades(
+    slot: &Slot,
+    amount: f64,
+    book: &LotBook,
+    rules: &dyn HoldingPeriodRules,
+    as_of: NaiveDate,
+) -> Vec<RebalanceTrade> {
+    let disposed_at = as_of.and_time(NaiveTime::MIN);
+    // (rank, gain per dollar, asset index, lot)
+    let mut candidates = Vec::new();
+    for (index, (asset, _, price)) in slot.assets.iter().enumerate() {
+        for lot in book.open_lots(asset) {
+            let long_term = rules.classify(lot.acquired_at, disposed_at).term == HoldingTerm::LongTerm;
+            let gain_ratio = (price - lot.unit_cost_usd) / price;
+            let rank = if gain_ratio < 0.0 { 0 } else if long_term { 1 } else { 2 };
+            candidates.push((rank, gain_ratio, index, long_term, lot));
+        }
+    }
+    candidates.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let mut trades: Vec<RebalanceTrade> = slot
+        .assets
+        .iter()
+        .map(|(asset, _, price)| RebalanceTrade {
+            asset_symbol: asset.clone(),
+            side: TradeSide::Sell,
+            quantity: 0.0,
+            price: *price,
+            value: 0.0,
+            lots: Vec::new(),
+            estimated_gain: 0.0,
+        })
+        .collect();
+
+    let mut remaining = amount;
+    for (_, _, index, long_term, lot) in candidates {
+        if remaining <= 1e-9 {
+            break;
+        }
+        let (_, held, price) = slot.assets[index];
+        let trade = &mut trades[index];
+        let quantity = (remaining / price).min(lot.quantity).min(held - trade.quantity);
+        if quantity <= 0.0 {
+            continue;
+        }
+        let gain = (price - lot.unit_cost_usd) * quantity;
+
+        trade.lots.push(LotSale {
+            tx_id: lot.tx_id,
+            acquired_at: lot.acquired_at,
+            quantity,
+            unit_cost: lot.unit_cost_usd,
+            gain,
+            long_term,
+        });
+        trade.quantity += quantity;
+        trade.value += quantity * price;
+        trade.estimated_gain += gain;
+        remaining -= quantity * price;
+    }
+
+    // Quantities without a lot (e.g. opening balances) are sold last, pro rata.
+    if remaining > 1e-9 {
+        let unlotted: Vec<f64> = slot
+            .assets
+            .iter()
+            .zip(&trades)
+            .map(|((_, held, price), trade)| (held - trade.quantity).max(0.0) * price)
+            .collect();
+        for ((trade, value), (_, _, price)) in trades.iter_mut().zip(spread(remaining, &unlotted)).zip(&slot.assets) {
+            trade.quantity += value / price;
+            trade.value += value;
+        }
+    }
+
+    trades.retain(|t| t.quantity > 1e-12);
+    trades
+}
@@ -0,0 +1,821 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use toml::Spanned;
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::signals::{
+    trailing_mean, MarketContext, MarketDataSet, Signal, SignalAction, SignalExplanation, Strategy,
+};
+
+/// Longest rule name that still fits `strategy_signals.signal_type` once the action is appended.
+pub const MAX_RULE_NAME_LEN: usize = 50;
+/// Width of `strategy_signals.asset_symbol`.
+pub const MAX_RULE_ASSET_LEN: usize = 16;
+/// Largest window a rule function accepts, ten years of daily observations.
+pub const MAX_RULE_WINDOW: usize = 3650;
+
+/// A problem in a rules file, located by its 1-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// One `[[rule]]` of a rules file: emits `action` for `asset` on every day `when` holds.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub asset: String,
+    pub action: SignalAction,
+    pub confidence: f64,
+    pub description: Option<String>,
+    /// The condition as written.
+    pub when: String,
+    condition: Expr,
+}
+
+impl Rule {
+    /// Whether the condition holds at `ctx`. A rule whose inputs are missing does not fire.
+    pub fn fires(&self, ctx: &MarketContext) -> bool {
+        self.condition.holds(ctx) == Some(true)
+    }
+
+    /// Series the condition reads.
+    pub fn inputs(&self) -> Vec<MarketSymbol> {
+        let mut inputs = Vec::new();
+        self.condition.collect_inputs(&mut inputs);
+        inputs
+    }
+
+    /// Observations the condition looks back over, counting the latest one.
+    pub fn lookback(&self) -> usize {
+        self.condition.lookback()
+    }
+
+    /// Short hash of everything that decides what the rule emits, so a stored signal can be tied
+    /// to the definition that produced it even after the file was edited.
+    pub fn version(&self) -> String {
+        let definition = format!("{}|{}|{:?}|{}|{}", self.name, self.asset, self.action, self.confidence, self.when);
+        hex::encode(Sha256::digest(definition.as_bytes()))[..12].to_string()
+    }
+
+    /// Every series and function the condition reads with its value at `ctx`, and every number
+    /// one of them is compared against, keyed like `sma(BTC_USD, 200)`.
+    pub fn explain(&self, ctx: &MarketContext) -> SignalExplanation {
+        let mut explanation = SignalExplanation::new(self.version());
+        self.condition.explain(ctx, &mut explanation);
+        explanation
+    }
+}
+
+/// User-defined rules loaded from TOML, run as one strategy. Each rule signs its signals with
+/// its own name, so `signal_type` reads e.g. `DEEP_FEAR_BUY`.
+///
+/// ```toml
+/// [[rule]]
+/// name = "deep_fear"
+/// asset = "BTC"
+/// action = "BUY"
+/// confidence = 0.8
+/// when = "FEAR_GREED_INDEX < 20 AND BTC_USD < sma(BTC_USD, 200)"
+/// ```
+///
+/// Conditions combine comparisons (`< <= > >= == !=`) with `AND`, `OR`, `NOT` and parentheses.
+/// Operands are numbers, arithmetic (`+ - * /`), series named like [`MarketSymbol`] (their latest
+/// value) and `sma`, `min`, `max`, `lag` and `change` of a series over its last `n` observations.
+/// `change` is fractional: `change(BTC_USD, 30) < -0.2` is a 20% drop.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub const NAME: &'static str = "rules";
+
+    /// Parses and validates a whole file, reporting every problem rather than the first one.
+    pub fn parse(source: &str) -> Result<Self, Vec<RuleError>> {
+        let file: RulesFile = toml::from_str(source).map_err(|e| {
+            let line = e.span().map_or(1, |span| line_of(source, span.start));
+            vec![RuleError { line, message: e.message().trim().to_string() }]
+        })?;
+
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+        let mut names = BTreeSet::new();
+        for spec in file.rule {
+            let name_line = line_of(source, spec.name.span().start);
+            match spec.into_rule(source) {
+                Ok(rule) if !names.insert(rule.name.clone()) => errors.push(RuleError {
+                    line: name_line,
+                    message: format!("duplicate rule name `{}`", rule.name),
+                }),
+                Ok(rule) => rules.push(rule),
+                Err(mut rule_errors) => errors.append(&mut rule_errors),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self { rules })
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Longest lookback of any rule, in observations.
+    pub fn lookback(&self) -> usize {
+        self.rules.iter().map(Rule::lookback).max().unwrap_or(1)
+    }
+
+    /// History to load before the first evaluated day so every rule sees its full lookback. Sized
+    /// for weekday-only series such as the stock indices; monthly FRED series need more.
+    pub fn warm_up(&self) -> Duration {
+        // Windows are bounded when parsed, the clamp only keeps the arithmetic in range.
+        let observations = self.lookback().min(MAX_RULE_WINDOW + 1) as i64;
+        Duration::days(observations * 7 / 5 + 30)
+    }
+
+    /// What the rules would have emitted on every day from `from` through `to`, in date order.
+    pub fn dry_run(&self, data: &MarketDataSet, from: NaiveDate, to: NaiveDate) -> Vec<Signal> {
+        from.iter_days()
+            .take_while(|d| *d <= to)
+            .flat_map(|d| self.evaluate(&data.at(d)))
+            .collect()
+    }
+}
+
+impl Strategy for RuleSet {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn description(&self) -> &'static str {
+        "User-defined rules from a TOML file"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        let mut inputs: Vec<MarketSymbol> = Vec::new();
+        for input in self.rules.iter().flat_map(Rule::inputs) {
+            if !inputs.iter().any(|known| known.as_str() == input.as_str()) {
+                inputs.push(input);
+            }
+        }
+        inputs
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.fires(ctx))
+            .map(|rule| {
+                let reason = match &rule.description {
+                    Some(description) => format!("{description} (when {})", rule.when),
+                    None => format!("when {}", rule.when),
+                };
+                Signal::new(&rule.name, &rule.asset, ctx, rule.action, rule.confidence, reason)
+                    .explained(rule.explain(ctx))
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: Spanned<String>,
+    asset: Spanned<String>,
+    action: Spanned<String>,
+    weight: Option<Spanned<f64>>,
+    confidence: Option<Spanned<f64>>,
+    description: Option<String>,
+    when: Spanned<String>,
+}
+
+impl RuleSpec {
+    fn into_rule(self, source: &str) -> Result<Rule, Vec<RuleError>> {
+        let mut errors = Vec::new();
+        let mut error = |at: usize, message: String| errors.push(RuleError { line: line_of(source, at), message });
+
+        let name = self.name.get_ref().trim().to_string();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            error(self.name.span().start, format!("rule name `{name}` may only use letters, digits and `_`"));
+        } else if name.len() > MAX_RULE_NAME_LEN {
+            error(self.name.span().start, format!("rule name `{name}` is longer than {MAX_RULE_NAME_LEN} characters"));
+        }
+
+        let asset = self.asset.get_ref().trim().to_uppercase();
+        if asset.is_empty() || asset.len() > MAX_RULE_ASSET_LEN {
+            error(self.asset.span().start, format!("asset must be 1 to {MAX_RULE_ASSET_LEN} characters"));
+        }
+
+        let action = match (self.action.get_ref().trim().to_uppercase().as_str(), &self.weight) {
+            ("WEIGHT", Some(weight)) if (0.0..=1.0).contains(weight.get_ref()) => {
+                Some(SignalAction::Weight(*weight.get_ref()))
+            }
+            ("WEIGHT", Some(weight)) => {
+                error(weight.span().start, "weight must be between 0 and 1".to_string());
+                None
+            }
+            ("WEIGHT", None) => {
+                error(self.action.span().start, "action WEIGHT needs a `weight`".to_string());
+                None
+            }
+            (_, Some(weight)) => {
+                error(weight.span().start, "`weight` only applies to action WEIGHT".to_string());
+                None
+            }
+            (other, None) => match SignalAction::from_str(other) {
+                Ok(action) => Some(action),
+                Err(e) => {
+                    error(self.action.span().start, format!("{e}; expected BUY, SELL, HOLD or WEIGHT"));
+                    None
+                }
+            },
+        };
+
+        let confidence = match &self.confidence {
+            Some(c) if !(0.0..=1.0).contains(c.get_ref()) => {
+                error(c.span().start, "confidence must be between 0 and 1".to_string());
+                1.0
+            }
+            Some(c) => *c.get_ref(),
+            None => 1.0,
+        };
+
+        // Offsets inside the expression are shifted past the opening quote(s) of the TOML string,
+        // and past the newline a multi-line string drops right after them.
+        let span = self.when.span();
+        let raw = &source[span.clone()];
+        let quote = match raw.get(..3) {
+            Some("\"\"\"" | "'''") if raw[3..].starts_with("\r\n") => 5,
+            Some("\"\"\"" | "'''") if raw[3..].starts_with('\n') => 4,
+            Some("\"\"\"" | "'''") => 3,
+            _ => 1,
+        };
+        let condition = match parse_condition(self.when.get_ref()) {
+            Ok(condition) => Some(condition),
+            Err(e) => {
+                error(span.start + quote + e.offset, e.message);
+                None
+            }
+        };
+
+        match (action, condition) {
+            (Some(action), Some(condition)) if errors.is_empty() => Ok(Rule {
+                name,
+                asset,
+                action,
+                confidence,
+                description: self.description,
+                when: self.when.into_inner().trim().to_string(),
+                condition,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    /// Mean of the last `n` observations.
+    Sma,
+    Min,
+    Max,
+    /// The observation `n` before the latest.
+    Lag,
+    /// Latest over the observation `n` before it, minus one.
+    Change,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "sma" => Some(Function::Sma),
+            "min" => Some(Function::Min),
+            "max" => Some(Function::Max),
+            "lag" => Some(Function::Lag),
+            "change" => Some(Function::Change),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Function::Sma => "sma",
+            Function::Min => "min",
+            Function::Max => "max",
+            Function::Lag => "lag",
+            Function::Change => "change",
+        }
+    }
+
+    fn lookback(&self, n: usize) -> usize {
+        match self {
+            Function::Sma | Function::Min | Function::Max => n,
+            Function::Lag | Function::Change => n.saturating_add(1),
+        }
+    }
+
+    fn apply(&self, series: &[(NaiveDate, f64)], n: usize) -> Option<f64> {
+        if series.len() < self.lookback(n) {
+            return None;
+        }
+        let tail = series[series.len() - n..].iter().map(|(_, v)| *v);
+        match self {
+            Function::Sma => trailing_mean(series, n),
+            Function::Min => tail.reduce(f64::min),
+            Function::Max => tail.reduce(f64::max),
+            Function::Lag => Some(series[series.len() - 1 - n].1),
+            Function::Change => {
+                let (latest, then) = (series[series.len() - 1].1, series[series.len() - 1 - n].1);
+                Some(latest / then - 1.0).filter(|v| v.is_finite())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Arithmetic {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    fn from_op(op: &str) -> Option<Self> {
+        match op {
+            "<" => Some(Comparison::Lt),
+            "<=" => Some(Comparison::Le),
+            ">" => Some(Comparison::Gt),
+            ">=" => Some(Comparison::Ge),
+            "==" => Some(Comparison::Eq),
+            "!=" => Some(Comparison::Ne),
+            _ => None,
+        }
+    }
+
+    fn holds(&self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Comparison::Lt => lhs < rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Gt => lhs > rhs,
+            Comparison::Ge => lhs >= rhs,
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+        }
+    }
+}
+
+/// A parsed condition. Numeric and boolean nodes are told apart while parsing, so evaluation
+/// never meets a number where it expects a condition or the other way round.
+#[derive(Debug, Clone)]
+enum Expr {
+    Number(f64),
+    Series(MarketSymbol),
+    Call(Function, MarketSymbol, usize),
+    Neg(Box<Expr>),
+    Arithmetic(Arithmetic, Box<Expr>, Box<Expr>),
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// `None` when an input has no data yet or a division has no finite result.
+    fn value(&self, ctx: &MarketContext) -> Option<f64> {
+        match self {
+            Expr::Number(n) => Some(*n),
+            Expr::Series(symbol) => ctx.series(symbol).last().map(|(_, v)| *v),
+            Expr::Call(function, symbol, n) => function.apply(ctx.series(symbol), *n),
+            Expr::Neg(e) => e.value(ctx).map(|v| -v),
+            Expr::Arithmetic(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.value(ctx)?, rhs.value(ctx)?);
+                let v = match op {
+                    Arithmetic::Add => lhs + rhs,
+                    Arithmetic::Sub => lhs - rhs,
+                    Arithmetic::Mul => lhs * rhs,
+                    Arithmetic::Div => lhs / rhs,
+                };
+                v.is_finite().then_some(v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Three-valued: `None` when it depends on missing data. `AND` is still false when one side
+    /// is false and `OR` still true when one side is true.
+    fn holds(&self, ctx: &MarketContext) -> Option<bool> {
+        match self {
+            Expr::Compare(op, lhs, rhs) => Some(op.holds(lhs.value(ctx)?, rhs.value(ctx)?)),
+            Expr::Not(e) => e.holds(ctx).map(|b| !b),
+            Expr::And(lhs, rhs) => match (lhs.holds(ctx), rhs.holds(ctx)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Expr::Or(lhs, rhs) => match (lhs.holds(ctx), rhs.holds(ctx)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn collect_inputs(&self, inputs: &mut Vec<MarketSymbol>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Series(symbol) | Expr::Call(_, symbol, _) => {
+                if !inputs.iter().any(|known| known.as_str() == symbol.as_str()) {
+                    inputs.push(symbol.clone());
+                }
+            }
+            Expr::Neg(e) | Expr::Not(e) => e.collect_inputs(inputs),
+            Expr::Arithmetic(_, lhs, rhs) | Expr::Compare(_, lhs, rhs) | Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.collect_inputs(inputs);
+                rhs.collect_inputs(inputs);
+            }
+        }
+    }
+
+    /// How a series or function call reads in the condition; `None` for anything else.
+    fn label(&self) -> Option<String> {
+        match self {
+            Expr::Series(symbol) => Some(symbol.as_str().to_string()),
+            Expr::Call(function, symbol, n) => Some(format!("{}({}, {})", function.name(), symbol.as_str(), n)),
+            _ => None,
+        }
+    }
+
+    fn explain(&self, ctx: &MarketContext, explanation: &mut SignalExplanation) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Series(_) | Expr::Call(..) => {
+                if let (Some(label), Some(value)) = (self.label(), self.value(ctx)) {
+                    explanation.inputs.insert(label, value);
+                }
+            }
+            Expr::Compare(_, lhs, rhs) => {
+                if let (side, Expr::Number(n)) | (Expr::Number(n), side) = (lhs.as_ref(), rhs.as_ref()) {
+                    if let Some(label) = side.label() {
+                        explanation.thresholds.insert(label, *n);
+                    }
+                }
+                lhs.explain(ctx, explanation);
+                rhs.explain(ctx, explanation);
+            }
+            Expr::Neg(e) | Expr::Not(e) => e.explain(ctx, explanation),
+            Expr::Arithmetic(_, lhs, rhs) | Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.explain(ctx, explanation);
+                rhs.explain(ctx, explanation);
+            }
+        }
+    }
+
+    fn lookback(&self) -> usize {
+        match self {
+            Expr::Number(_) | Expr::Series(_) => 1,
+            Expr::Call(function, _, n) => function.lookback(*n),
+            Expr::Neg(e) | Expr::Not(e) => e.lookback(),
+            Expr::Arithmetic(_, lhs, rhs) | Expr::Compare(_, lhs, rhs) | Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.lookback().max(rhs.lookback())
+            }
+        }
+    }
+}
+
+/// A syntax or type error at a byte offset of the expression.
+#[derive(Debug)]
+struct ExprError {
+    offset: usize,
+    message: String,
+}
+
+impl ExprError {
+    fn new(offset: usize, message: impl Into<String>) -> Self {
+        Self { offset, message: message.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Word(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{n}"),
+            Token::Word(w) => write!(f, "{w}"),
+            Token::Op(op) => write!(f, "{op}"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+const OPERATORS: [&str; 10] = ["<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/"];
+
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some(&(at, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let end = src[at..].find(|c: char| !(c.is_ascii_digit() || c == '.')).map_or(src.len(), |n| at + n);
+            let number =
+                src[at..end].parse().map_err(|_| ExprError::new(at, format!("invalid number `{}`", &src[at..end])))?;
+            tokens.push((at, Token::Number(number)));
+            while chars.peek().is_some_and(|(i, _)| *i < end) {
+                chars.next();
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = src[at..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).map_or(src.len(), |n| at + n);
+            tokens.push((at, Token::Word(src[at..end].to_string())));
+            while chars.peek().is_some_and(|(i, _)| *i < end) {
+                chars.next();
+            }
+        } else if let Some(op) = OPERATORS.iter().find(|op| src[at..].starts_with(**op)) {
+            tokens.push((at, Token::Op(op)));
+            chars.nth(op.len() - 1);
+        } else {
+            let token = match c {
+                '(' => Token::Open,
+                ')' => Token::Close,
+                ',' => Token::Comma,
+                other => return Err(ExprError::new(at, format!("unexpected character `{other}`"))),
+            };
+            tokens.push((at, token));
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Condition,
+}
+
+fn parse_condition(src: &str) -> Result<Expr, ExprError> {
+    let mut parser = Parser { tokens: tokenize(src)?, pos: 0, end: src.len() };
+    let (expr, kind) = parser.or()?;
+    if let Some((at, token)) = parser.tokens.get(parser.pos) {
+        return Err(ExprError::new(*at, format!("unexpected `{token}`")));
+    }
+    if kind != Kind::Condition {
+        return Err(ExprError::new(0, "`when` must be a condition such as `FEAR_GREED_INDEX < 20`"));
+    }
+    Ok(expr)
+}
+
+/// Recursive descent, loosest binding first: `OR`, `AND`, `NOT`, comparisons, `+ -`, `* /`,
+/// unary minus.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(at, _)| *at)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn op(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), ExprError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            return Ok(());
+        }
+        let found = self.peek().map_or("end of expression".to_string(), |t| format!("`{t}`"));
+        Err(ExprError::new(self.offset(), format!("expected `{token}`, found {found}")))
+    }
+
+    fn or(&mut self) -> Result<(Expr, Kind), ExprError> {
+        let at = self.offset();
+        let (mut lhs, kind) = self.and()?;
+        while self.keyword("OR") {
+            require(kind, Kind::Condition, at, "OR")?;
+            let at = self.offset();
+            let (rhs, kind) = self.and()?;
+            require(kind, Kind::Condition, at, "OR")?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok((lhs, kind))
+    }
+
+    fn and(&mut self) -> Result<(Expr, Kind), ExprError> {
+        let at = self.offset();
+        let (mut lhs, kind) = self.not()?;
+        while self.keyword("AND") {
+            require(kind, Kind::Condition, at, "AND")?;
+            let at = self.offset();
+            let (rhs, kind) = self.not()?;
+            require(kind, Kind::Condition, at, "AND")?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok((lhs, kind))
+    }
+
+    fn not(&mut self) -> Result<(Expr, Kind), ExprError> {
+        if self.keyword("NOT") {
+            let at = self.offset();
+            let (e, kind) = self.not()?;
+            require(kind, Kind::Condition, at, "NOT")?;
+            return Ok((Expr::Not(Box::new(e)), Kind::Condition));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<(Expr, Kind), ExprError> {
+        let at = self.offset();
+        let (lhs, kind) = self.sum()?;
+        let Some(op) = self.op(&["<", "<=", ">", ">=", "==", "!="]) else { return Ok((lhs, kind)) };
+        require(kind, Kind::Number, at, op)?;
+        let rhs_at = self.offset();
+        let (rhs, rhs_kind) = self.sum()?;
+        require(rhs_kind, Kind::Number, rhs_at, op)?;
+        if matches!(self.peek(), Some(Token::Op(next)) if Comparison::from_op(next).is_some()) {
+            return Err(ExprError::new(self.offset(), "comparisons cannot be chained; join them with AND"));
+        }
+        let comparison = Comparison::from_op(op).expect("only comparison operators are accepted here");
+        Ok((Expr::Compare(comparison, Box::new(lhs), Box::new(rhs)), Kind::Condition))
+    }
+
+    fn sum(&mut self) -> Result<(Expr, Kind), ExprError> {
+        let at = self.offset();
+        let (mut lhs, kind) = self.product()?;
+        while let Some(op) = self.op(&["+", "-"]) {
+            require(kind, Kind::Number, at, op)?;
+            let rhs_at = self.offset();
+            let (rhs, rhs_kind) = self.product()?;
+            require(rhs_kind, Kind::Number, rhs_at, op)?;
+            let op = if op == "+" { Arithmetic::Add } else { Arithmetic::Sub };
+            lhs = Expr::Arithmetic(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok((lhs, kind))
+    }
+
+    fn product(&mut self) -> Result<(Expr, Kind), ExprError> {
+        let at = self.offset();
+        let (mut lhs, kind) = self.unary()?;
+        while let Some(op) = self.op(&["*", "/"]) {
+            require(kind, Kind::Number, at, op)?;
+            let rhs_at = self.offset();
+            let (rhs, rhs_kind) = self.unary()?;
+            require(rhs_kind, Kind::Number, rhs_at, op)?;
+            let op = if op == "*" { Arithmetic::Mul } else { Arithmetic::Div };
+            lhs = Expr::Arithmetic(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok((lhs, kind))
+    }
+
+    fn unary(&mut self) -> Result<(Expr, Kind), ExprError> {
+        if self.op(&["-"]).is_some() {
+            let at = self.offset();
+            let (e, kind) = self.unary()?;
+            require(kind, Kind::Number, at, "-")?;
+            return Ok((Expr::Neg(Box::new(e)), Kind::Number));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<(Expr, Kind), ExprError> {
+        let at = self.offset();
+        let Some(token) = self.peek().cloned() else {
+            return Err(ExprError::new(at, "expression ends too early"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok((Expr::Number(n), Kind::Number)),
+            Token::Open => {
+                let parsed = self.or()?;
+                self.expect(Token::Close)?;
+                Ok(parsed)
+            }
+            Token::Word(word) if self.peek() == Some(&Token::Open) => self.call(at, &word),
+            Token::Word(word) if ["AND", "OR", "NOT"].iter().any(|k| word.eq_ignore_ascii_case(k)) => {
+                Err(ExprError::new(at, format!("`{word}` needs a condition before it")))
+            }
+            Token::Word(word) => series(at, &word).map(|symbol| (Expr::Series(symbol), Kind::Number)),
+            other => Err(ExprError::new(at, format!("unexpected `{other}`"))),
+        }
+    }
+
+    /// `name(SERIES, n)` with the name already consumed.
+    fn call(&mut self, at: usize, name: &str) -> Result<(Expr, Kind), ExprError> {
+        let function = Function::from_name(name).ok_or_else(|| {
+            ExprError::new(at, format!("unknown function `{name}`; expected sma, min, max, lag or change"))
+        })?;
+        self.expect(Token::Open)?;
+
+        let series_at = self.offset();
+        let symbol = match self.peek().cloned() {
+            Some(Token::Word(word)) => {
+                self.pos += 1;
+                series(series_at, &word)?
+            }
+            _ => return Err(ExprError::new(series_at, format!("`{name}` takes a series name first"))),
+        };
+        self.expect(Token::Comma)?;
+
+        let n_at = self.offset();
+        let n = match self.peek() {
+            Some(Token::Number(n)) if n.fract() == 0.0 && (1.0..=MAX_RULE_WINDOW as f64).contains(n) => *n as usize,
+            _ => {
+                let message =
+                    format!("`{name}` takes a whole number of observations from 1 to {MAX_RULE_WINDOW}");
+                return Err(ExprError::new(n_at, message));
+            }
+        };
+        self.pos += 1;
+        self.expect(Token::Close)?;
+
+        Ok((Expr::Call(function, symbol, n), Kind::Number))
+    }
+}
+
+fn series(at: usize, name: &str) -> Result<MarketSymbol, ExprError> {
+    MarketSymbol::from_str(name).map_err(|_| ExprError::new(at, format!("unknown series `{name}`")))
+}
+
+fn require(found: Kind, expected: Kind, at: usize, op: &str) -> Result<(), ExprError> {
+    if found == expected {
+        return Ok(());
+    }
+    let message = match expected {
+        Kind::Number => format!("`{op}` needs numbers on both sides, found a condition"),
+        Kind::Condition => format!("`{op}` needs conditions on both sides, found a number"),
+    };
+    Err(ExprError::new(at, message))
+}
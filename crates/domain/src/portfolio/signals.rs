@@ -0,0 +1,637 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::market_price::MarketSymbol;
+use crate::portfolio::nav::{value_as_of, PriceHistory};
+
+/// Date-sorted series keyed by name.
+pub type SeriesMap = BTreeMap<String, Vec<(NaiveDate, f64)>>;
+
+/// Everything a strategy may read: daily closes keyed by ledger asset (`BTC`), market metrics
+/// keyed by [`MarketSymbol`] name and derived indicators keyed by indicator name. Series must be
+/// sorted by date.
+#[derive(Debug, Clone, Default)]
+pub struct MarketDataSet {
+    pub prices: PriceHistory,
+    pub metrics: SeriesMap,
+    pub indicators: SeriesMap,
+}
+
+impl MarketDataSet {
+    /// The data as it was known at the end of `as_of`.
+    pub fn at(&self, as_of: NaiveDate) -> MarketContext<'_> {
+        MarketContext { as_of, data: self }
+    }
+
+    /// Every date with a close of at least one asset, in order. Replays step through these.
+    pub fn price_dates(&self) -> Vec<NaiveDate> {
+        let mut dates: Vec<NaiveDate> = self.prices.values().flatten().map(|(d, _)| *d).collect();
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+}
+
+/// Read-only view of a [`MarketDataSet`] as of one day. No accessor returns an observation dated
+/// after `as_of`, so a strategy evaluated over history sees exactly what it would have seen live.
+#[derive(Debug, Clone, Copy)]
+pub struct MarketContext<'a> {
+    as_of: NaiveDate,
+    data: &'a MarketDataSet,
+}
+
+impl<'a> MarketContext<'a> {
+    pub fn as_of(&self) -> NaiveDate {
+        self.as_of
+    }
+
+    /// Last close of `asset` on or before `as_of`.
+    pub fn price(&self, asset: &str) -> Option<f64> {
+        value_as_of(self.prices(asset), self.as_of).map(|(_, v)| v)
+    }
+
+    /// Closes of `asset` up to and including `as_of`.
+    pub fn prices(&self, asset: &str) -> &'a [(NaiveDate, f64)] {
+        self.visible(self.data.prices.get(asset))
+    }
+
+    pub fn metric(&self, symbol: MarketSymbol) -> Option<f64> {
+        self.metric_series(symbol).last().map(|(_, v)| *v)
+    }
+
+    pub fn metric_series(&self, symbol: MarketSymbol) -> &'a [(NaiveDate, f64)] {
+        self.visible(self.data.metrics.get(symbol.as_str()))
+    }
+
+    /// Any stored series: closes for price series (`BTC_USD`), metric values for the rest.
+    pub fn series(&self, symbol: &MarketSymbol) -> &'a [(NaiveDate, f64)] {
+        match symbol.ledger_asset() {
+            Some(asset) => self.prices(asset),
+            None => self.visible(self.data.metrics.get(symbol.as_str())),
+        }
+    }
+
+    pub fn indicator(&self, name: &str) -> Option<f64> {
+        self.indicator_series(name).last().map(|(_, v)| *v)
+    }
+
+    pub fn indicator_series(&self, name: &str) -> &'a [(NaiveDate, f64)] {
+        self.visible(self.data.indicators.get(name))
+    }
+
+    fn visible(&self, series: Option<&'a Vec<(NaiveDate, f64)>>) -> &'a [(NaiveDate, f64)] {
+        let series = series.map(Vec::as_slice).unwrap_or_default();
+        &series[..series.partition_point(|(d, _)| *d <= self.as_of)]
+    }
+}
+
+/// What a strategy recommends for one asset.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "weight", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SignalAction {
+    Buy,
+    Sell,
+    Hold,
+    /// Target share of the portfolio between 0 and 1.
+    Weight(f64),
+}
+
+impl SignalAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalAction::Buy => "BUY",
+            SignalAction::Sell => "SELL",
+            SignalAction::Hold => "HOLD",
+            SignalAction::Weight(_) => "WEIGHT",
+        }
+    }
+}
+
+impl FromStr for SignalAction {
+    type Err = ParseSignalActionError;
+
+    /// Parses the payload-free actions; weights carry a number and are built directly.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "BUY" => Ok(SignalAction::Buy),
+            "SELL" => Ok(SignalAction::Sell),
+            "HOLD" => Ok(SignalAction::Hold),
+            _ => Err(ParseSignalActionError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSignalActionError(pub String);
+
+impl fmt::Display for ParseSignalActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid signal action: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseSignalActionError {}
+
+/// What a signal was computed from: the values the strategy read, the levels it compared them
+/// against and which version of the rule did the comparing. Stored with the signal so it can be
+/// audited without replaying anything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalExplanation {
+    /// Changes whenever the rule's logic or definition does.
+    pub rule_version: String,
+    /// Values read, keyed by series or derived quantity, e.g. `BTC_USD` or `sma(BTC_USD, 200)`.
+    pub inputs: BTreeMap<String, f64>,
+    /// Levels the inputs were compared against, e.g. `undervalued_below`.
+    pub thresholds: BTreeMap<String, f64>,
+}
+
+impl SignalExplanation {
+    pub fn new(rule_version: impl Into<String>) -> Self {
+        Self { rule_version: rule_version.into(), ..Self::default() }
+    }
+
+    pub fn input(mut self, name: impl Into<String>, value: f64) -> Self {
+        self.inputs.insert(name.into(), value);
+        self
+    }
+
+    pub fn threshold(mut self, name: impl Into<String>, value: f64) -> Self {
+        self.thresholds.insert(name.into(), value);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Signal {
+    pub strategy: String,
+    pub asset_symbol: String,
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub action: SignalAction,
+    /// How strongly the rule fired, between 0 and 1.
+    pub confidence: f64,
+    /// Why the signal fired, in words.
+    pub reason: String,
+    /// Why the signal fired, in numbers.
+    #[serde(default)]
+    pub explanation: SignalExplanation,
+}
+
+impl Signal {
+    pub fn new(
+        strategy: &str,
+        asset_symbol: &str,
+        ctx: &MarketContext,
+        action: SignalAction,
+        confidence: f64,
+        reason: String,
+    ) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            asset_symbol: asset_symbol.to_string(),
+            date: ctx.as_of(),
+            action,
+            confidence: confidence.clamp(0.0, 1.0),
+            reason,
+            explanation: SignalExplanation::default(),
+        }
+    }
+
+    pub fn explained(mut self, explanation: SignalExplanation) -> Self {
+        self.explanation = explanation;
+        self
+    }
+
+    /// `strategy_signals.signal_type`, e.g. `MAYER_MULTIPLE_SELL`. It includes the strategy so
+    /// two strategies agreeing on an asset on the same day are stored side by side.
+    pub fn signal_type(&self) -> String {
+        format!("{}_{}", self.strategy.to_uppercase(), self.action.as_str())
+    }
+
+    /// Number stored in `strategy_signals.value`: the weight for allocation signals, the
+    /// confidence for everything else.
+    pub fn value(&self) -> f64 {
+        match self.action {
+            SignalAction::Weight(weight) => weight,
+            _ => self.confidence,
+        }
+    }
+}
+
+/// A rule that turns market data into signals. Strategies must only read through the context,
+/// which is what lets the live generator and historical replays share one implementation.
+pub trait Strategy: Send + Sync {
+    /// Unique, stable name. Stored as the first part of the `signal_type` of persisted signals.
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Series the strategy reads, so callers know what to load. Includes the closes of the assets
+    /// it signals, which replays need to fill its trades.
+    fn inputs(&self) -> Vec<MarketSymbol>;
+
+    /// Signals for `ctx.as_of()`. Strategies that have nothing to say return no signals.
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrategyError {
+    DuplicateStrategy(String),
+    UnknownStrategy(String),
+}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyError::DuplicateStrategy(name) => write!(f, "Strategy {} is registered more than once", name),
+            StrategyError::UnknownStrategy(name) => write!(f, "Unknown strategy: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for StrategyError {}
+
+/// Strategies by name, enumerated in name order.
+#[derive(Default)]
+pub struct StrategyRegistry {
+    strategies: BTreeMap<&'static str, Box<dyn Strategy>>,
+}
+
+impl StrategyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The long-horizon strategies shipped with the dashboard.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for strategy in [
+            Box::new(TwoHundredWeekMa::default()) as Box<dyn Strategy>,
+            Box::new(MayerMultiple::default()),
+            Box::new(FearGreedExtremes::default()),
+            Box::new(DominanceTrend::default()),
+        ] {
+            registry.register(strategy).expect("built-in strategy names are unique");
+        }
+        registry
+    }
+
+    pub fn register(&mut self, strategy: Box<dyn Strategy>) -> Result<(), StrategyError> {
+        let name = strategy.name();
+        if self.strategies.contains_key(name) {
+            return Err(StrategyError::DuplicateStrategy(name.to_string()));
+        }
+        self.strategies.insert(name, strategy);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&dyn Strategy, StrategyError> {
+        self.strategies
+            .get(name)
+            .map(|s| s.as_ref())
+            .ok_or_else(|| StrategyError::UnknownStrategy(name.to_string()))
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.strategies.keys().copied().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Strategy> {
+        self.strategies.values().map(|s| s.as_ref())
+    }
+
+    /// Inputs of every registered strategy, each once.
+    pub fn inputs(&self) -> Vec<MarketSymbol> {
+        let mut inputs: Vec<MarketSymbol> = Vec::new();
+        for input in self.iter().flat_map(|s| s.inputs()) {
+            if !inputs.iter().any(|known| known.as_str() == input.as_str()) {
+                inputs.push(input);
+            }
+        }
+        inputs
+    }
+
+    /// Signals of every registered strategy for `ctx.as_of()`.
+    pub fn evaluate_all(&self, ctx: &MarketContext) -> Vec<Signal> {
+        self.iter().flat_map(|s| s.evaluate(ctx)).collect()
+    }
+}
+
+/// Assets the built-in strategies look at.
+pub const LONG_TERM_ASSETS: [&str; 2] = ["BTC", "ETH"];
+
+/// `rule_version` of the built-in strategies' signals. Bump it when their logic changes; their
+/// parameters are recorded as thresholds.
+pub const BUILTIN_RULE_VERSION: &str = "builtin-1";
+
+/// 0.5 right at the threshold, rising linearly to 1 once `distance` reaches `full`.
+fn scaled_confidence(distance: f64, full: f64) -> f64 {
+    0.5 + 0.5 * (distance / full).clamp(0.0, 1.0)
+}
+
+/// `1400` reads `200-week`, `200` reads `200-day`.
+fn window_label(days: usize) -> String {
+    if days.is_multiple_of(7) {
+        format!("{}-week", days / 7)
+    } else {
+        format!("{days}-day")
+    }
+}
+
+/// Mean of the last `window` values, `None` until there are that many.
+pub(crate) fn trailing_mean(series: &[(NaiveDate, f64)], window: usize) -> Option<f64> {
+    if window == 0 || series.len() < window {
+        return None;
+    }
+    let tail = &series[series.len() - window..];
+    Some(tail.iter().map(|(_, v)| v).sum::<f64>() / window as f64)
+}
+
+/// Buys while the close is below its 200-week moving average, historically the deepest part of
+/// bear markets. The window can be tuned; the default is [`TwoHundredWeekMa::WINDOW_DAYS`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwoHundredWeekMa {
+    pub window_days: usize,
+}
+
+impl TwoHundredWeekMa {
+    /// 200 weeks of daily closes.
+    pub const WINDOW_DAYS: usize = 1400;
+}
+
+impl Default for TwoHundredWeekMa {
+    fn default() -> Self {
+        Self { window_days: Self::WINDOW_DAYS }
+    }
+}
+
+impl Strategy for TwoHundredWeekMa {
+    fn name(&self) -> &'static str {
+        "200w_ma"
+    }
+
+    fn description(&self) -> &'static str {
+        "Buy below the 200-week moving average"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::BtcUsd, MarketSymbol::EthUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        LONG_TERM_ASSETS
+            .iter()
+            .filter_map(|asset| {
+                let closes = ctx.prices(asset);
+                let (_, close) = *closes.last()?;
+                let ma = trailing_mean(closes, self.window_days)?;
+                if close >= ma {
+                    return None;
+                }
+                let discount = 1.0 - close / ma;
+                Some(Signal::new(
+                    self.name(),
+                    asset,
+                    ctx,
+                    SignalAction::Buy,
+                    scaled_confidence(discount, 0.3),
+                    format!(
+                        "{asset} closed at {close:.2}, {:.1}% below its {} moving average of {ma:.2}",
+                        discount * 100.0,
+                        window_label(self.window_days)
+                    ),
+                )
+                .explained(
+                    SignalExplanation::new(BUILTIN_RULE_VERSION)
+                        .input("close", close)
+                        .input("moving_average", ma)
+                        .threshold("window_days", self.window_days as f64),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Buys when the Mayer multiple (close over its 200-day moving average) is below 0.8 and sells
+/// when it is above 2.4. The constants are the defaults of the tunable fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MayerMultiple {
+    pub window_days: usize,
+    pub undervalued_below: f64,
+    pub overheated_above: f64,
+}
+
+impl MayerMultiple {
+    pub const WINDOW_DAYS: usize = 200;
+    pub const UNDERVALUED_BELOW: f64 = 0.8;
+    pub const OVERHEATED_ABOVE: f64 = 2.4;
+}
+
+impl Default for MayerMultiple {
+    fn default() -> Self {
+        Self {
+            window_days: Self::WINDOW_DAYS,
+            undervalued_below: Self::UNDERVALUED_BELOW,
+            overheated_above: Self::OVERHEATED_ABOVE,
+        }
+    }
+}
+
+impl Strategy for MayerMultiple {
+    fn name(&self) -> &'static str {
+        "mayer_multiple"
+    }
+
+    fn description(&self) -> &'static str {
+        "Buy when the Mayer multiple is below 0.8, sell above 2.4"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::BtcUsd, MarketSymbol::EthUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        LONG_TERM_ASSETS
+            .iter()
+            .filter_map(|asset| {
+                let closes = ctx.prices(asset);
+                let (_, close) = *closes.last()?;
+                let ma = trailing_mean(closes, self.window_days)?;
+                let multiple = close / ma;
+
+                let (action, band, confidence) = if multiple < self.undervalued_below {
+                    let depth = self.undervalued_below - multiple;
+                    (SignalAction::Buy, format!("below {}", self.undervalued_below), scaled_confidence(depth, 0.3))
+                } else if multiple > self.overheated_above {
+                    let excess = multiple - self.overheated_above;
+                    (SignalAction::Sell, format!("above {}", self.overheated_above), scaled_confidence(excess, 1.0))
+                } else {
+                    return None;
+                };
+
+                Some(Signal::new(
+                    self.name(),
+                    asset,
+                    ctx,
+                    action,
+                    confidence,
+                    format!(
+                        "{asset} Mayer multiple is {multiple:.2} ({band}): close {close:.2} over a {} average of {ma:.2}",
+                        window_label(self.window_days)
+                    ),
+                )
+                .explained(
+                    SignalExplanation::new(BUILTIN_RULE_VERSION)
+                        .input("close", close)
+                        .input("moving_average", ma)
+                        .input("mayer_multiple", multiple)
+                        .threshold("window_days", self.window_days as f64)
+                        .threshold("undervalued_below", self.undervalued_below)
+                        .threshold("overheated_above", self.overheated_above),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Contrarian: buys BTC on extreme fear and sells on extreme greed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FearGreedExtremes {
+    pub fear_at_or_below: f64,
+    pub greed_at_or_above: f64,
+}
+
+impl FearGreedExtremes {
+    pub const EXTREME_FEAR_AT_OR_BELOW: f64 = 20.0;
+    pub const EXTREME_GREED_AT_OR_ABOVE: f64 = 80.0;
+}
+
+impl Default for FearGreedExtremes {
+    fn default() -> Self {
+        Self { fear_at_or_below: Self::EXTREME_FEAR_AT_OR_BELOW, greed_at_or_above: Self::EXTREME_GREED_AT_OR_ABOVE }
+    }
+}
+
+impl Strategy for FearGreedExtremes {
+    fn name(&self) -> &'static str {
+        "fear_greed_extremes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Buy BTC on extreme fear, sell on extreme greed"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::FearGreedIndex, MarketSymbol::BtcUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        let Some(&(date, value)) = ctx.metric_series(MarketSymbol::FearGreedIndex).last() else {
+            return Vec::new();
+        };
+
+        let (action, label, confidence) = if value <= self.fear_at_or_below {
+            let depth = self.fear_at_or_below - value;
+            (SignalAction::Buy, "extreme fear", scaled_confidence(depth, self.fear_at_or_below))
+        } else if value >= self.greed_at_or_above {
+            let excess = value - self.greed_at_or_above;
+            (SignalAction::Sell, "extreme greed", scaled_confidence(excess, 100.0 - self.greed_at_or_above))
+        } else {
+            return Vec::new();
+        };
+
+        vec![Signal::new(
+            self.name(),
+            "BTC",
+            ctx,
+            action,
+            confidence,
+            format!("Fear & Greed Index is {value:.0} on {date} ({label})"),
+        )
+        .explained(
+            SignalExplanation::new(BUILTIN_RULE_VERSION)
+                .input(MarketSymbol::FearGreedIndex.as_str(), value)
+                .threshold("fear_at_or_below", self.fear_at_or_below)
+                .threshold("greed_at_or_above", self.greed_at_or_above),
+        )]
+    }
+}
+
+/// Rising BTC dominance means capital rotating out of altcoins into BTC, falling dominance the
+/// opposite. Sells ETH on a rise of 2 percentage points over 30 days and buys it on a drop of as
+/// much. `BTC_DOMINANCE` is stored as a fraction of the total market cap, `trend_points` is in
+/// percentage points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DominanceTrend {
+    pub lookback_days: i64,
+    pub trend_points: f64,
+}
+
+impl DominanceTrend {
+    pub const LOOKBACK_DAYS: i64 = 30;
+    pub const TREND_POINTS: f64 = 2.0;
+}
+
+impl Default for DominanceTrend {
+    fn default() -> Self {
+        Self { lookback_days: Self::LOOKBACK_DAYS, trend_points: Self::TREND_POINTS }
+    }
+}
+
+impl Strategy for DominanceTrend {
+    fn name(&self) -> &'static str {
+        "btc_dominance_trend"
+    }
+
+    fn description(&self) -> &'static str {
+        "Rotate out of ETH while BTC dominance rises, into it while it falls"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::BtcDominance, MarketSymbol::EthUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        let series = ctx.metric_series(MarketSymbol::BtcDominance);
+        let Some(&(date, latest)) = series.last() else {
+            return Vec::new();
+        };
+        let Some((then_date, then)) = value_as_of(series, date - Duration::days(self.lookback_days)) else {
+            return Vec::new();
+        };
+        let (latest_percent, then_percent) = (latest * 100.0, then * 100.0);
+        let change = latest_percent - then_percent;
+
+        let (action, direction) = if change >= self.trend_points {
+            (SignalAction::Sell, "rose")
+        } else if change <= -self.trend_points {
+            (SignalAction::Buy, "fell")
+        } else {
+            return Vec::new();
+        };
+
+        vec![Signal::new(
+            self.name(),
+            "ETH",
+            ctx,
+            action,
+            scaled_confidence(change.abs() - self.trend_points, self.trend_points * 2.0),
+            format!(
+                "BTC dominance {direction} {:.1} points from {then_percent:.1}% on {then_date} to {latest_percent:.1}%",
+                change.abs()
+            ),
+        )
+        .explained(
+            SignalExplanation::new(BUILTIN_RULE_VERSION)
+                .input(MarketSymbol::BtcDominance.as_str(), latest)
+                .input("dominance_then", then)
+                .input("change_points", change)
+                .threshold("lookback_days", self.lookback_days as f64)
+                .threshold("trend_points", self.trend_points),
+        )]
+    }
+}
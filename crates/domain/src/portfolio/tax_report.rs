@@ -0,0 +1,243 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::currency::{ConversionError, Currency, CurrencyConverter};
+use crate::portfolio::income::{income_in_year, IncomeRow};
+use crate::portfolio::portfolio_state::{LedgerError, LedgerTransaction};
+use crate::portfolio::portfolio_utils::{CostBasisMethod, LotBook};
+use crate::portfolio::tax_rules::{classify_gains, ClassifiedGain, HoldingTerm, Jurisdiction};
+
+/// One lot matched against one disposal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxLotRow {
+    pub asset_symbol: String,
+    pub quantity: f64,
+    pub acquired_on: NaiveDate,
+    pub disposed_on: NaiveDate,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub gain: f64,
+    pub term: HoldingTerm,
+    pub taxable: bool,
+    pub acquisition_tx_id: Uuid,
+    pub disposal_tx_id: Uuid,
+}
+
+impl From<&ClassifiedGain> for TaxLotRow {
+    fn from(classified: &ClassifiedGain) -> Self {
+        let gain = &classified.gain;
+        TaxLotRow {
+            asset_symbol: gain.asset_symbol.clone(),
+            quantity: gain.quantity,
+            acquired_on: gain.acquired_at.date(),
+            disposed_on: gain.disposed_at.date(),
+            proceeds: gain.proceeds_usd,
+            cost_basis: gain.cost_basis_usd,
+            gain: gain.gain_usd,
+            term: classified.classification.term,
+            taxable: classified.classification.taxable,
+            acquisition_tx_id: gain.acquisition_tx_id,
+            disposal_tx_id: gain.disposal_tx_id,
+        }
+    }
+}
+
+/// Realized gains and income of one calendar year. Amounts are in `currency`, USD unless
+/// converted with [`TaxReport::in_currency`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxReport {
+    pub portfolio_id: Uuid,
+    pub year: i32,
+    pub cost_basis_method: CostBasisMethod,
+    pub jurisdiction: Jurisdiction,
+    #[serde(default)]
+    pub currency: Currency,
+    pub rows: Vec<TaxLotRow>,
+    pub total_proceeds: f64,
+    pub total_cost_basis: f64,
+    pub short_term_gain: f64,
+    pub long_term_gain: f64,
+    pub total_gain: f64,
+    pub taxable_gain: f64,
+    pub tax_free_gain: f64,
+    /// Staking, lending, airdrop and fork income, taxable when received.
+    #[serde(default)]
+    pub income: Vec<IncomeRow>,
+    #[serde(default)]
+    pub total_income: f64,
+}
+
+impl TaxReport {
+    pub const CSV_HEADER: &'static str = "asset,quantity,acquired_on,disposed_on,proceeds_usd,cost_basis_usd,gain_usd,term,taxable,acquisition_tx_id,disposal_tx_id";
+    pub const INCOME_CSV_HEADER: &'static str = "asset,quantity,received_on,kind,unit_value_usd,value_usd,tx_id";
+
+    /// Replays the whole ledger under `method` and keeps the disposals made in `year`,
+    /// classified under the holding-period rules of `jurisdiction`, and the income received in
+    /// `year`.
+    ///
+    /// The ledger is ordered by timestamp and then by transaction id before lots are matched,
+    /// so the same ledger and method always produce the same rows in the same order.
+    pub fn build(
+        portfolio_id: Uuid,
+        year: i32,
+        method: CostBasisMethod,
+        jurisdiction: Jurisdiction,
+        transactions: &[LedgerTransaction],
+    ) -> Result<Self, LedgerError> {
+        let mut ordered = transactions.to_vec();
+        ordered.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+
+        let book = LotBook::from_transactions(&ordered, method)?;
+        let mut rows: Vec<TaxLotRow> = classify_gains(book.realized(), jurisdiction.rules().as_ref())
+            .iter()
+            .filter(|classified| classified.gain.disposed_at.year() == year)
+            .map(TaxLotRow::from)
+            .collect();
+        rows.sort_by(|a, b| {
+            (a.disposed_on, &a.asset_symbol, a.disposal_tx_id, a.acquired_on, a.acquisition_tx_id)
+                .cmp(&(b.disposed_on, &b.asset_symbol, b.disposal_tx_id, b.acquired_on, b.acquisition_tx_id))
+        });
+
+        let income = income_in_year(&ordered, year)?;
+
+        Ok(Self::with_rows(portfolio_id, year, method, jurisdiction, Currency::Usd, rows, income))
+    }
+
+    /// The same report in another currency: proceeds at the rate of the disposal date, cost
+    /// basis at the rate of the acquisition date, so gains include the FX result.
+    pub fn in_currency(&self, converter: &CurrencyConverter) -> Result<Self, ConversionError> {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let proceeds = converter.convert(row.proceeds, row.disposed_on)?;
+                let cost_basis = converter.convert(row.cost_basis, row.acquired_on)?;
+                Ok(TaxLotRow {
+                    proceeds,
+                    cost_basis,
+                    gain: proceeds - cost_basis,
+                    ..row.clone()
+                })
+            })
+            .collect::<Result<Vec<_>, ConversionError>>()?;
+        let income = self
+            .income
+            .iter()
+            .map(|row| {
+                let rate = converter.rate_as_of(row.received_on)?;
+                Ok(IncomeRow {
+                    unit_value: row.unit_value / rate,
+                    value: row.value / rate,
+                    ..row.clone()
+                })
+            })
+            .collect::<Result<Vec<_>, ConversionError>>()?;
+
+        Ok(Self::with_rows(
+            self.portfolio_id,
+            self.year,
+            self.cost_basis_method,
+            self.jurisdiction,
+            converter.currency(),
+            rows,
+            income,
+        ))
+    }
+
+    fn with_rows(
+        portfolio_id: Uuid,
+        year: i32,
+        method: CostBasisMethod,
+        jurisdiction: Jurisdiction,
+        currency: Currency,
+        rows: Vec<TaxLotRow>,
+        income: Vec<IncomeRow>,
+    ) -> Self {
+        let sum = |f: fn(&TaxLotRow) -> f64, keep: fn(&TaxLotRow) -> bool| {
+            rows.iter().filter(|r| keep(r)).map(f).sum::<f64>()
+        };
+
+        TaxReport {
+            portfolio_id,
+            year,
+            cost_basis_method: method,
+            jurisdiction,
+            currency,
+            total_proceeds: sum(|r| r.proceeds, |_| true),
+            total_cost_basis: sum(|r| r.cost_basis, |_| true),
+            short_term_gain: sum(|r| r.gain, |r| r.term == HoldingTerm::ShortTerm),
+            long_term_gain: sum(|r| r.gain, |r| r.term == HoldingTerm::LongTerm),
+            total_gain: sum(|r| r.gain, |_| true),
+            taxable_gain: sum(|r| r.gain, |r| r.taxable),
+            tax_free_gain: sum(|r| r.gain, |r| !r.taxable),
+            total_income: income.iter().map(|r| r.value).sum(),
+            rows,
+            income,
+        }
+    }
+
+    /// One line per disposal, then, when there was income, a blank line and one line per
+    /// income receipt under its own header. Quantities with 8 decimals, amounts with 2. Amount
+    /// columns are suffixed with the report currency.
+    pub fn to_csv(&self) -> String {
+        let header = |header: &str| match self.currency {
+            Currency::Usd => header.to_string(),
+            other => header.replace("_usd", &format!("_{}", other.as_str().to_lowercase())),
+        };
+        let mut out = String::with_capacity(Self::CSV_HEADER.len() + 1 + (self.rows.len() + self.income.len()) * 128);
+        out.push_str(&header(Self::CSV_HEADER));
+        out.push('\n');
+
+        for row in &self.rows {
+            let _ = writeln!(
+                out,
+                "{},{:.8},{},{},{:.2},{:.2},{:.2},{},{},{},{}",
+                csv_field(&row.asset_symbol),
+                row.quantity,
+                row.acquired_on,
+                row.disposed_on,
+                row.proceeds,
+                row.cost_basis,
+                row.gain,
+                row.term.as_str(),
+                row.taxable,
+                row.acquisition_tx_id,
+                row.disposal_tx_id,
+            );
+        }
+
+        if !self.income.is_empty() {
+            out.push('\n');
+            out.push_str(&header(Self::INCOME_CSV_HEADER));
+            out.push('\n');
+            for row in &self.income {
+                let _ = writeln!(
+                    out,
+                    "{},{:.8},{},{},{:.2},{:.2},{}",
+                    csv_field(&row.asset_symbol),
+                    row.quantity,
+                    row.received_on,
+                    row.kind.as_str(),
+                    row.unit_value,
+                    row.value,
+                    row.tx_id,
+                );
+            }
+        }
+
+        out
+    }
+}
+
+/// `value` quoted when it holds a separator, a quote or a line break, quotes doubled.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
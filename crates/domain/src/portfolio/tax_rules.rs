@@ -0,0 +1,149 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Months, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::portfolio_utils::RealizedGain;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HoldingTerm {
+    ShortTerm,
+    LongTerm,
+}
+
+impl HoldingTerm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldingTerm::ShortTerm => "SHORT_TERM",
+            HoldingTerm::LongTerm => "LONG_TERM",
+        }
+    }
+
+    /// Long-term when the asset was held for more than one year, i.e. disposed of after the
+    /// anniversary of its acquisition.
+    pub fn classify(acquired_at: NaiveDateTime, disposed_at: NaiveDateTime) -> Self {
+        let anniversary = acquired_at.date().checked_add_months(Months::new(12));
+        match anniversary {
+            Some(anniversary) if disposed_at.date() > anniversary => HoldingTerm::LongTerm,
+            _ => HoldingTerm::ShortTerm,
+        }
+    }
+}
+
+/// How one disposal is treated under a jurisdiction's rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GainClassification {
+    pub term: HoldingTerm,
+    pub taxable: bool,
+}
+
+/// Holding-period rules of one tax jurisdiction.
+pub trait HoldingPeriodRules: Send + Sync {
+    fn jurisdiction(&self) -> Jurisdiction;
+    fn classify(&self, acquired_at: NaiveDateTime, disposed_at: NaiveDateTime) -> GainClassification;
+}
+
+/// United States: every disposal is taxable, split into short and long term at one year.
+pub struct UsRules;
+
+impl HoldingPeriodRules for UsRules {
+    fn jurisdiction(&self) -> Jurisdiction {
+        Jurisdiction::Us
+    }
+
+    fn classify(&self, acquired_at: NaiveDateTime, disposed_at: NaiveDateTime) -> GainClassification {
+        GainClassification {
+            term: HoldingTerm::classify(acquired_at, disposed_at),
+            taxable: true,
+        }
+    }
+}
+
+/// Germany (private sales, § 23 EStG): disposals after a holding period of more than one
+/// year are tax-free, earlier ones are taxable.
+pub struct GermanRules;
+
+impl HoldingPeriodRules for GermanRules {
+    fn jurisdiction(&self) -> Jurisdiction {
+        Jurisdiction::De
+    }
+
+    fn classify(&self, acquired_at: NaiveDateTime, disposed_at: NaiveDateTime) -> GainClassification {
+        let term = HoldingTerm::classify(acquired_at, disposed_at);
+        GainClassification {
+            term,
+            taxable: term == HoldingTerm::ShortTerm,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Jurisdiction {
+    #[default]
+    #[serde(rename = "US")]
+    Us,
+    #[serde(rename = "DE")]
+    De,
+}
+
+impl Jurisdiction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Jurisdiction::Us => "US",
+            Jurisdiction::De => "DE",
+        }
+    }
+
+    pub fn rules(&self) -> Box<dyn HoldingPeriodRules> {
+        match self {
+            Jurisdiction::Us => Box::new(UsRules),
+            Jurisdiction::De => Box::new(GermanRules),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseJurisdictionError(pub String);
+
+impl fmt::Display for ParseJurisdictionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid Jurisdiction: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseJurisdictionError {}
+
+impl FromStr for Jurisdiction {
+    type Err = ParseJurisdictionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "US" | "USA" => Ok(Jurisdiction::Us),
+            "DE" | "GERMANY" => Ok(Jurisdiction::De),
+            other => Err(ParseJurisdictionError(other.to_string())),
+        }
+    }
+}
+
+/// A realized gain annotated with the rules it was classified under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassifiedGain {
+    #[serde(flatten)]
+    pub gain: RealizedGain,
+    pub jurisdiction: Jurisdiction,
+    #[serde(flatten)]
+    pub classification: GainClassification,
+}
+
+pub fn classify_gains(gains: &[RealizedGain], rules: &dyn HoldingPeriodRules) -> Vec<ClassifiedGain> {
+    gains
+        .iter()
+        .map(|gain| ClassifiedGain {
+            gain: gain.clone(),
+            jurisdiction: rules.jurisdiction(),
+            classification: rules.classify(gain.acquired_at, gain.disposed_at),
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    backtest, BacktestConfig, BacktestError, MarketContext, MarketDataSet, MarketSymbol, Signal, SignalAction, Strategy,
+    StrategyRegistry, TradeSide,
+};
+
+fn day(i: i64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + Duration::days(i)
+}
+
+fn btc(closes: &[f64]) -> MarketDataSet {
+    let mut data = MarketDataSet::default();
+    data.prices.insert(
+        "BTC".to_string(),
+        closes.iter().enumerate().map(|(i, c)| (day(i as i64), *c)).collect(),
+    );
+    data
+}
+
+/// Goes all in on BTC on the first day it sees.
+struct AllIn;
+
+impl Strategy for AllIn {
+    fn name(&self) -> &'static str {
+        "all_in"
+    }
+
+    fn description(&self) -> &'static str {
+        "Buys BTC once"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::BtcUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        if ctx.prices("BTC").len() != 1 {
+            return Vec::new();
+        }
+        vec![Signal::new(self.name(), "BTC", ctx, SignalAction::Weight(1.0), 1.0, "first close".to_string())]
+    }
+}
+
+/// Buys the day before every rise. Only possible with a peek at tomorrow's close.
+struct Clairvoyant;
+
+impl Strategy for Clairvoyant {
+    fn name(&self) -> &'static str {
+        "clairvoyant"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tries to read the future"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::BtcUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        let tomorrow = ctx.as_of() + Duration::days(1);
+        match ctx.prices("BTC").iter().find(|(d, _)| *d == tomorrow) {
+            Some(_) => vec![Signal::new(self.name(), "BTC", ctx, SignalAction::Buy, 1.0, "peeked".to_string())],
+            None => Vec::new(),
+        }
+    }
+}
+
+#[test]
+fn test_signal_fills_at_next_close_with_fees_and_slippage() {
+    let data = btc(&[100.0, 200.0, 300.0]);
+    let config = BacktestConfig { fee_rate: 0.01, slippage: 0.01, ..BacktestConfig::new(day(0), day(2), 1_000.0) };
+
+    let result = backtest(&AllIn, &data, &config).unwrap();
+
+    assert_eq!(result.trades.len(), 1);
+    let trade = &result.trades[0];
+    assert_eq!(trade.date, day(1));
+    assert_eq!(trade.side, TradeSide::Buy);
+    assert_eq!(trade.price_usd, 202.0);
+
+    // 1000 buys 990.10 of notional plus 9.90 of fee, at 202 instead of 200.
+    let notional = 1_000.0 / 1.01;
+    assert!((trade.fee_usd - notional * 0.01).abs() < 1e-9);
+    assert!((trade.quantity - notional / 202.0).abs() < 1e-9);
+    assert!((result.equity_curve[1].equity - trade.quantity * 200.0).abs() < 1e-9);
+    assert_eq!(result.equity_curve[0].equity, 1_000.0);
+    assert!(result.stats.total_slippage_usd > 0.0);
+    assert_eq!(result.stats.trade_count, 1);
+}
+
+#[test]
+fn test_strategy_cannot_see_the_future() {
+    let data = btc(&[100.0, 110.0, 120.0, 130.0]);
+    let result = backtest(&Clairvoyant, &data, &BacktestConfig::new(day(0), day(3), 1_000.0)).unwrap();
+
+    assert!(result.trades.is_empty());
+    assert_eq!(result.stats.final_equity, 1_000.0);
+}
+
+#[test]
+fn test_idle_cash_earns_cash_rate() {
+    let data = btc(&vec![100.0; 366]);
+    let config = BacktestConfig { cash_rate: 0.05, ..BacktestConfig::new(day(0), day(365), 1_000.0) };
+
+    let result = backtest(&Clairvoyant, &data, &config).unwrap();
+
+    assert!((result.stats.final_equity - 1_050.0).abs() < 1e-6);
+    assert!((result.stats.cash_interest_usd - 50.0).abs() < 1e-6);
+    assert_eq!(result.stats.average_exposure, 0.0);
+}
+
+#[test]
+fn test_rejects_empty_range_and_bad_config() {
+    let data = btc(&[100.0]);
+
+    assert_eq!(
+        backtest(&AllIn, &data, &BacktestConfig::new(day(5), day(6), 1_000.0)),
+        Err(BacktestError::NoData { from: day(5), to: day(6) })
+    );
+    assert!(matches!(
+        backtest(&AllIn, &data, &BacktestConfig::new(day(0), day(0), 0.0)),
+        Err(BacktestError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_builtin_strategies_replay_from_their_own_inputs() {
+    // Only what a caller loads from `inputs()`, with cycles deep enough for every rule to fire.
+    let days = 2_000;
+    let wave = |i: i64, period: f64| (i as f64 * std::f64::consts::TAU / period).sin();
+    for strategy in StrategyRegistry::builtin().iter() {
+        let mut data = MarketDataSet::default();
+        for input in strategy.inputs() {
+            let values = (0..days).map(|i| match input {
+                MarketSymbol::FearGreedIndex => (day(i), 50.0 + 45.0 * wave(i, 90.0)),
+                MarketSymbol::BtcDominance => (day(i), 0.55 + 0.05 * wave(i, 120.0)),
+                _ => (day(i), 30_000.0 * (1.0 + 0.8 * wave(i, 700.0))),
+            });
+            match input.ledger_asset() {
+                Some(asset) => data.prices.insert(asset.to_string(), values.collect()),
+                None => data.metrics.insert(input.as_str().to_string(), values.collect()),
+            };
+        }
+
+        let result = backtest(strategy, &data, &BacktestConfig::new(day(0), day(days - 1), 1_000.0))
+            .unwrap_or_else(|e| panic!("{}: {}", strategy.name(), e));
+        assert!(!result.trades.is_empty(), "{} never traded", strategy.name());
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use super::fixtures::tx;
+use crate::{CostBasisMethod, LedgerError, LedgerTransaction, LotBook, PortfolioState, TransactionKind};
+
+/// Buys 1 BTC at 10k, 1 BTC at 30k, 1 BTC at 20k, then sells 1 BTC at 25k.
+fn ledger() -> (Uuid, Vec<LedgerTransaction>) {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2021, 1, 1), 1.0, Some(10_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2021, 6, 1), 1.0, Some(30_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2022, 1, 1), 1.0, Some(20_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2023, 1, 1), 1.0, Some(25_000.0)),
+    ];
+    (account, txs)
+}
+
+fn realized(method: CostBasisMethod) -> f64 {
+    let (_, txs) = ledger();
+    let book = LotBook::from_transactions(&txs, method).unwrap();
+    book.realized().iter().map(|g| g.gain_usd).sum()
+}
+
+#[test]
+fn test_realized_gain_per_method() {
+    assert_eq!(realized(CostBasisMethod::Fifo), 15_000.0);
+    assert_eq!(realized(CostBasisMethod::Lifo), 5_000.0);
+    assert_eq!(realized(CostBasisMethod::Hifo), -5_000.0);
+    assert_eq!(realized(CostBasisMethod::AverageCost), 5_000.0);
+}
+
+#[test]
+fn test_unrealized_pnl_uses_open_lots() {
+    let (_, txs) = ledger();
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+
+    let prices = BTreeMap::from([("BTC".to_string(), 40_000.0)]);
+    let pnl = book.pnl(&prices);
+
+    assert_eq!(pnl.len(), 1);
+    assert_eq!(pnl[0].quantity, 2.0);
+    assert_eq!(pnl[0].cost_basis, 50_000.0);
+    assert_eq!(pnl[0].unrealized_pnl, Some(30_000.0));
+}
+
+#[test]
+fn test_partial_disposal_splits_lots() {
+    let (account, mut txs) = ledger();
+    txs.push(tx(account, TransactionKind::Sell, "BTC", (2023, 2, 1), 1.5, Some(20_000.0)));
+
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+    let lots = book.open_lots("BTC");
+
+    assert_eq!(lots.len(), 1);
+    assert_eq!(lots[0].quantity, 0.5);
+    assert_eq!(lots[0].unit_cost_usd, 20_000.0);
+}
+
+#[test]
+fn test_overselling_is_rejected() {
+    let (account, mut txs) = ledger();
+    txs.push(tx(account, TransactionKind::Sell, "BTC", (2023, 2, 1), 5.0, Some(20_000.0)));
+
+    assert!(matches!(
+        LotBook::from_transactions(&txs, CostBasisMethod::Fifo),
+        Err(LedgerError::InsufficientBalance { .. })
+    ));
+    assert!(PortfolioState::from_transactions(&txs).is_err());
+}
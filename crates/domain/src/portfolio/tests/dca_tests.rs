@@ -0,0 +1,68 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    compare_dca, dca_schedule, simulate_dca, DcaFrequency, DcaMethod, DcaPlan, MarketDataSet, MarketSymbol,
+};
+
+fn day(i: i64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + Duration::days(i)
+}
+
+fn data(closes: &[f64], fear_greed: &[f64]) -> MarketDataSet {
+    let series = |values: &[f64]| values.iter().enumerate().map(|(i, v)| (day(i as i64), *v)).collect();
+    let mut data = MarketDataSet::default();
+    data.prices.insert("BTC".to_string(), series(closes));
+    data.metrics.insert(MarketSymbol::FearGreedIndex.as_str().to_string(), series(fear_greed));
+    data
+}
+
+fn plan(method: DcaMethod) -> DcaPlan {
+    DcaPlan { asset: "BTC".to_string(), amount: 100.0, frequency: DcaFrequency::Daily, start: day(0), method }
+}
+
+#[test]
+fn test_fixed_dca_buys_the_same_amount_every_period() {
+    let data = data(&[100.0, 50.0, 100.0], &[]);
+
+    let sim = simulate_dca(&plan(DcaMethod::Fixed), &data, day(2));
+
+    assert_eq!(sim.buys.len(), 3);
+    assert_eq!(sim.invested, 300.0);
+    assert_eq!(sim.quantity, 4.0);
+    assert_eq!(sim.average_cost, Some(75.0));
+    assert_eq!(sim.final_value, 400.0);
+}
+
+#[test]
+fn test_value_averaging_skips_buys_when_ahead_of_target() {
+    let data = data(&[100.0, 300.0, 100.0], &[]);
+
+    let sim = simulate_dca(&plan(DcaMethod::ValueAveraging), &data, day(2));
+
+    // Day 1: 1 BTC worth 300 is above the 200 target, nothing to buy. Day 2: worth 100, buy 200.
+    let amounts: Vec<f64> = sim.buys.iter().map(|b| b.amount).collect();
+    assert_eq!(amounts, vec![100.0, 200.0]);
+}
+
+#[test]
+fn test_fear_greed_dca_buys_more_on_fear() {
+    let data = data(&[100.0, 100.0], &[10.0, 90.0]);
+
+    let sim = simulate_dca(&plan(DcaMethod::FearGreed), &data, day(1));
+
+    assert_eq!(sim.buys[0].amount, 200.0);
+    assert_eq!(sim.buys[1].amount, 50.0);
+    assert_eq!(compare_dca(&plan(DcaMethod::Fixed), &data, day(1)).len(), DcaMethod::ALL.len());
+}
+
+#[test]
+fn test_schedule_lists_future_buys_sized_with_todays_reading() {
+    let data = data(&[100.0], &[15.0]);
+    let plan = DcaPlan { frequency: DcaFrequency::Monthly, start: NaiveDate::from_ymd_opt(2023, 10, 31).unwrap(), ..plan(DcaMethod::FearGreed) };
+
+    let schedule = dca_schedule(&plan, &data.at(day(0)), 2);
+
+    assert_eq!(schedule[0].date, NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+    assert_eq!(schedule[1].date, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+    assert_eq!(schedule[0].amount, 200.0);
+}
@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use super::fixtures::tx;
+use crate::{
+    value_income, CostBasisMethod, Jurisdiction, LotBook, PortfolioState, PriceHistory, TaxReport, TransactionKind,
+};
+
+fn day(m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+}
+
+#[test]
+fn test_income_is_valued_from_market_data_at_receipt() {
+    let account = Uuid::new_v4();
+    let mut txs = vec![
+        tx(account, TransactionKind::StakingReward, "ETH", (2024, 3, 2), 0.5, None),
+        tx(account, TransactionKind::LendingInterest, "USDC", (2024, 3, 2), 10.0, None),
+        tx(account, TransactionKind::Airdrop, "XYZ", (2024, 3, 2), 100.0, None),
+        tx(account, TransactionKind::Fork, "ABC", (2024, 3, 2), 1.0, Some(7.0)),
+        tx(account, TransactionKind::StakingReward, "ETH", (2024, 3, 3), 0.5, Some(3_400.0)),
+    ];
+    let prices = PriceHistory::from([("ETH".to_string(), vec![(day(3, 1), 3_000.0), (day(3, 3), 3_500.0)])]);
+
+    let unvalued = value_income(&mut txs, &prices);
+
+    assert_eq!(txs[0].price_usd, Some(3_000.0));
+    assert_eq!(txs[1].price_usd, Some(1.0));
+    assert_eq!(unvalued, vec![txs[2].id]);
+    assert_eq!(txs[3].price_usd, Some(7.0));
+    assert_eq!(txs[4].price_usd, Some(3_500.0));
+}
+
+#[test]
+fn test_income_value_is_lot_basis_and_taxable_income() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::StakingReward, "ETH", (2024, 1, 5), 1.0, Some(2_000.0)),
+        tx(account, TransactionKind::Fork, "ETH", (2024, 2, 5), 1.0, Some(2_500.0)),
+        tx(account, TransactionKind::Sell, "ETH", (2024, 6, 1), 1.0, Some(3_000.0)),
+    ];
+
+    let state = PortfolioState::from_transactions(&txs).unwrap();
+    assert_eq!(state.balance(account, "ETH"), 1.0);
+
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+    assert_eq!(book.open_lots("ETH")[0].unit_cost_usd, 2_500.0);
+
+    let report = TaxReport::build(Uuid::new_v4(), 2024, CostBasisMethod::Fifo, Jurisdiction::Us, &txs).unwrap();
+    assert_eq!(report.income.len(), 2);
+    assert_eq!(report.total_income, 4_500.0);
+    assert_eq!(report.total_gain, 1_000.0);
+}
+
+#[test]
+fn test_unvalued_income_is_rejected() {
+    let account = Uuid::new_v4();
+    let txs = vec![tx(account, TransactionKind::Airdrop, "XYZ", (2024, 1, 5), 1.0, None)];
+
+    assert!(LotBook::from_transactions(&txs, CostBasisMethod::Fifo).is_err());
+}
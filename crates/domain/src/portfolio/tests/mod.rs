@@ -0,0 +1,57 @@
+#[cfg(test)]
+pub mod backtest_tests;
+#[cfg(test)]
+pub mod cost_basis_tests;
+#[cfg(test)]
+pub mod dca_tests;
+#[cfg(test)]
+pub mod income_tests;
+#[cfg(test)]
+pub mod nav_tests;
+#[cfg(test)]
+pub mod performance_tests;
+#[cfg(test)]
+pub mod rebalance_tests;
+#[cfg(test)]
+pub mod signal_rules_tests;
+#[cfg(test)]
+pub mod signals_tests;
+#[cfg(test)]
+pub mod tax_report_tests;
+#[cfg(test)]
+pub mod walk_forward_tests;
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use uuid::Uuid;
+
+    use crate::{LedgerTransaction, TransactionKind};
+
+    pub fn at(date: (i32, u32, u32)) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap().and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    pub fn tx(
+        account_id: Uuid,
+        kind: TransactionKind,
+        asset: &str,
+        date: (i32, u32, u32),
+        quantity: f64,
+        price: Option<f64>,
+    ) -> LedgerTransaction {
+        LedgerTransaction {
+            id: Uuid::new_v4(),
+            account_id,
+            timestamp: at(date),
+            kind,
+            asset_symbol: asset.to_string(),
+            quantity,
+            price_usd: price,
+            fee_usd: None,
+            counterparty_account_id: None,
+            note: None,
+            external_id: None,
+        }
+    }
+}
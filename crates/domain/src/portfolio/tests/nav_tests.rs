@@ -0,0 +1,36 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use super::fixtures::tx;
+use crate::{daily_nav, PriceHistory, TransactionKind};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+#[test]
+fn test_nav_forward_fills_missing_closes() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Deposit, "USD", (2024, 1, 1), 10_000.0, None),
+        tx(account, TransactionKind::Buy, "BTC", (2024, 1, 2), 0.1, Some(40_000.0)),
+    ];
+    let prices = PriceHistory::from([(
+        "BTC".to_string(),
+        vec![(day(1), 39_000.0), (day(2), 40_000.0), (day(4), 44_000.0)],
+    )]);
+
+    let nav = daily_nav(&txs, &prices, day(1), day(4)).unwrap();
+
+    assert_eq!(nav.len(), 4);
+    assert_eq!(nav[0].nav, 10_000.0);
+    assert_eq!(nav[0].net_flow, 10_000.0);
+    assert!(!nav[0].forward_filled);
+
+    assert_eq!(nav[1].nav, 10_000.0);
+    assert_eq!(nav[2].nav, 10_000.0);
+    assert!(nav[2].forward_filled);
+
+    assert_eq!(nav[3].nav, 6_000.0 + 4_400.0);
+    assert!(!nav[3].forward_filled);
+}
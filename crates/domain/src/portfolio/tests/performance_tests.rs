@@ -0,0 +1,69 @@
+use chrono::NaiveDate;
+
+use crate::{performance, xirr, NavPoint, PerformancePeriod};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+fn point(d: u32, nav: f64, net_flow: f64) -> NavPoint {
+    NavPoint { date: day(d), nav, net_flow, forward_filled: false }
+}
+
+#[test]
+fn test_twr_ignores_external_flows() {
+    let nav = vec![
+        point(1, 100.0, 100.0),
+        point(2, 110.0, 0.0),
+        point(3, 210.0, 100.0),
+        point(4, 231.0, 0.0),
+    ];
+
+    let report = performance(&nav, &[], PerformancePeriod::Inception, day(4)).unwrap();
+
+    assert_eq!(report.from, day(1));
+    assert!((report.time_weighted_return - 0.21).abs() < 1e-9);
+    assert!(report.money_weighted_return.unwrap() > 0.0);
+}
+
+#[test]
+fn test_max_drawdown_and_duration() {
+    let nav = vec![
+        point(1, 100.0, 100.0),
+        point(2, 120.0, 0.0),
+        point(3, 90.0, 0.0),
+        point(4, 100.0, 0.0),
+        point(5, 130.0, 0.0),
+    ];
+
+    let report = performance(&nav, &[(day(1), 5.0)], PerformancePeriod::Inception, day(5)).unwrap();
+
+    assert!((report.max_drawdown + 0.25).abs() < 1e-9);
+    assert_eq!(report.max_drawdown_days, 2);
+    assert!(report.sharpe_ratio.is_some());
+    assert!(report.sortino_ratio.is_some());
+}
+
+#[test]
+fn test_xirr_of_one_year_holding() {
+    let flows = vec![
+        (NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(), -1_000.0),
+        (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 1_100.0),
+    ];
+
+    assert!((xirr(&flows).unwrap() - 0.10).abs() < 1e-6);
+    assert!(xirr(&flows[..1]).is_none());
+}
+
+#[test]
+fn test_period_start_is_clamped_to_inception() {
+    let as_of = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+    let inception = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+
+    assert_eq!(PerformancePeriod::MonthToDate.start(as_of, inception), NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+    assert_eq!(PerformancePeriod::QuarterToDate.start(as_of, inception), NaiveDate::from_ymd_opt(2024, 4, 1).unwrap());
+    assert_eq!(PerformancePeriod::OneYear.start(as_of, inception), NaiveDate::from_ymd_opt(2023, 5, 16).unwrap());
+
+    let late_inception = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    assert_eq!(PerformancePeriod::YearToDate.start(as_of, late_inception), late_inception);
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use super::fixtures::tx;
+use crate::{
+    rebalance, AllocationBucket, AllocationTarget, AssetClass, CostBasisMethod, Jurisdiction, LotBook, PortfolioState,
+    RebalanceError, TradeSide, TransactionKind,
+};
+
+fn target(bucket: &str, weight: f64, band: f64) -> AllocationTarget {
+    AllocationTarget { bucket: AllocationBucket::from_str(bucket).unwrap(), weight, band }
+}
+
+fn prices(btc: f64) -> BTreeMap<String, f64> {
+    BTreeMap::from([("BTC".to_string(), btc)])
+}
+
+fn as_of() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()
+}
+
+#[test]
+fn test_bucket_keys_round_trip() {
+    assert_eq!(AllocationBucket::from_str("class:equity").unwrap(), AllocationBucket::Class(AssetClass::Equity));
+    assert_eq!(AllocationBucket::from_str("xbt").unwrap(), AllocationBucket::Asset("BTC".to_string()));
+    assert_eq!(AllocationBucket::Class(AssetClass::Crypto).key(), "CLASS:CRYPTO");
+    assert!(AllocationBucket::from_str("CLASS:BONDS").is_err());
+    assert_eq!(AssetClass::of("GOLD"), AssetClass::Commodity);
+    assert_eq!(AssetClass::of("USDT"), AssetClass::Stablecoin);
+}
+
+#[test]
+fn test_no_trades_inside_bands() {
+    let holdings = BTreeMap::from([("USD".to_string(), 4_000.0), ("BTC".to_string(), 0.1)]);
+    let targets = vec![target("CLASS:CRYPTO", 0.6, 0.05), target("CLASS:CASH", 0.4, 0.05)];
+
+    let plan = rebalance(&holdings, &prices(60_000.0), &LotBook::default(), &targets, Jurisdiction::Us, as_of()).unwrap();
+
+    assert!(plan.trades.is_empty());
+    assert!(plan.buckets.iter().all(|b| b.in_band));
+}
+
+#[test]
+fn test_trades_back_to_band_edge() {
+    let holdings = BTreeMap::from([("USD".to_string(), 4_000.0), ("BTC".to_string(), 0.1)]);
+    let targets = vec![target("BTC", 0.6, 0.05), target("USD", 0.4, 0.05)];
+
+    let plan = rebalance(&holdings, &prices(90_000.0), &LotBook::default(), &targets, Jurisdiction::Us, as_of()).unwrap();
+
+    assert_eq!(plan.trades.len(), 1);
+    assert_eq!(plan.trades[0].side, TradeSide::Sell);
+    assert!((plan.trades[0].value - 550.0).abs() < 1e-6);
+    assert!(plan.buckets.iter().all(|b| (b.weight_after - b.target_weight).abs() <= b.band + 1e-9));
+}
+
+#[test]
+fn test_non_positive_prices_are_rejected() {
+    let holdings = BTreeMap::from([("USD".to_string(), 4_000.0), ("BTC".to_string(), 0.1)]);
+    let targets = vec![target("BTC", 0.6, 0.05), target("USD", 0.4, 0.05)];
+
+    for price in [0.0, -1.0, f64::NAN] {
+        let err = rebalance(&holdings, &prices(price), &LotBook::default(), &targets, Jurisdiction::Us, as_of()).unwrap_err();
+        assert!(matches!(err, RebalanceError::InvalidPrice(ref asset, _) if asset == "BTC"), "{err}");
+    }
+}
+
+#[test]
+fn test_idle_cash_buys_class_default_asset() {
+    let holdings = BTreeMap::from([("USD".to_string(), 10_000.0)]);
+    let targets = vec![target("CLASS:CRYPTO", 0.6, 0.05), target("CLASS:CASH", 0.4, 0.05)];
+
+    let plan = rebalance(&holdings, &prices(50_000.0), &LotBook::default(), &targets, Jurisdiction::Us, as_of()).unwrap();
+
+    assert_eq!(plan.trades.len(), 1);
+    assert_eq!(plan.trades[0].asset_symbol, "BTC");
+    assert_eq!(plan.trades[0].side, TradeSide::Buy);
+    assert!((plan.trades[0].value - 5_500.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_sells_prefer_losses_then_long_term_lots() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Deposit, "USD", (2022, 1, 1), 110_000.0, None),
+        tx(account, TransactionKind::Buy, "BTC", (2022, 1, 1), 1.0, Some(60_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2023, 1, 1), 1.0, Some(20_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2024, 5, 1), 1.0, Some(30_000.0)),
+    ];
+    let holdings = PortfolioState::from_transactions(&txs).unwrap().holdings();
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+    let targets = vec![target("BTC", 0.5, 0.05), target("USD", 0.5, 0.05)];
+
+    let plan = rebalance(&holdings, &prices(50_000.0), &book, &targets, Jurisdiction::Us, as_of()).unwrap();
+
+    let sell = &plan.trades[0];
+    assert_eq!(sell.side, TradeSide::Sell);
+    assert!((sell.quantity - 1.35).abs() < 1e-9);
+    assert_eq!(sell.lots.len(), 2);
+    assert_eq!(sell.lots[0].tx_id, txs[1].id);
+    assert_eq!(sell.lots[1].tx_id, txs[2].id);
+    assert!(sell.lots[1].long_term);
+    assert!((sell.estimated_gain - (-10_000.0 + 0.35 * 30_000.0)).abs() < 1e-6);
+}
+
+#[test]
+fn test_long_term_follows_the_jurisdiction_holding_period() {
+    let account = Uuid::new_v4();
+    // Bought 366 days before `as_of`, but sold on the anniversary: still short-term, as in the tax report.
+    let txs = vec![
+        tx(account, TransactionKind::Deposit, "USD", (2023, 1, 1), 60_000.0, None),
+        tx(account, TransactionKind::Buy, "BTC", (2023, 6, 1), 1.0, Some(20_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2023, 6, 2), 1.0, Some(25_000.0)),
+    ];
+    let holdings = PortfolioState::from_transactions(&txs).unwrap().holdings();
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+    let targets = vec![target("BTC", 0.5, 0.05), target("USD", 0.5, 0.05)];
+
+    for jurisdiction in [Jurisdiction::Us, Jurisdiction::De] {
+        let plan = rebalance(&holdings, &prices(50_000.0), &book, &targets, jurisdiction, as_of()).unwrap();
+        let sell = &plan.trades[0];
+        assert!(sell.lots.iter().all(|lot| !lot.long_term), "{jurisdiction:?}");
+        // Without a long-term lot the smaller gain per dollar goes first.
+        assert_eq!(sell.lots[0].tx_id, txs[2].id);
+    }
+}
@@ -0,0 +1,266 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{MarketDataSet, MarketSymbol, RuleSet, SignalAction, Strategy, MAX_RULE_WINDOW};
+
+fn day(i: usize) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2022, 1, 1).unwrap() + Duration::days(i as i64)
+}
+
+fn series(values: impl IntoIterator<Item = f64>) -> Vec<(NaiveDate, f64)> {
+    values.into_iter().enumerate().map(|(i, v)| (day(i), v)).collect()
+}
+
+/// BTC falling from 100 by 1 a day while the Fear & Greed Index drops from 50 by 2 a day.
+fn falling_market() -> MarketDataSet {
+    let mut data = MarketDataSet::default();
+    data.prices.insert("BTC".to_string(), series((0..30).map(|i| 100.0 - i as f64)));
+    data.metrics.insert(
+        MarketSymbol::FearGreedIndex.as_str().to_string(),
+        series((0..30).map(|i| 50.0 - 2.0 * i as f64)),
+    );
+    data
+}
+
+const DEEP_FEAR: &str = r#"
+[[rule]]
+name = "deep_fear"
+asset = "btc"
+action = "BUY"
+confidence = 0.8
+description = "Capitulation"
+when = "FEAR_GREED_INDEX < 20 AND BTC_USD < sma(BTC_USD, 5)"
+"#;
+
+#[test]
+fn test_parse_rules_file() {
+    let rules = RuleSet::parse(DEEP_FEAR).unwrap();
+
+    assert_eq!(rules.rules().len(), 1);
+    let rule = &rules.rules()[0];
+    assert_eq!(rule.name, "deep_fear");
+    assert_eq!(rule.asset, "BTC");
+    assert_eq!(rule.action, SignalAction::Buy);
+    assert_eq!(rule.confidence, 0.8);
+    assert_eq!(rule.lookback(), 5);
+
+    let inputs: Vec<&str> = rules.inputs().iter().map(|s| s.as_str()).collect();
+    assert_eq!(inputs, vec!["FEAR_GREED_INDEX", "BTC_USD"]);
+}
+
+#[test]
+fn test_rule_fires_only_when_condition_holds() {
+    let rules = RuleSet::parse(DEEP_FEAR).unwrap();
+    let data = falling_market();
+
+    // Fear reaches 18 on day 16.
+    assert!(rules.evaluate(&data.at(day(15))).is_empty());
+    let signals = rules.evaluate(&data.at(day(16)));
+    assert_eq!(signals.len(), 1);
+    assert_eq!(signals[0].signal_type(), "DEEP_FEAR_BUY");
+    assert_eq!(signals[0].asset_symbol, "BTC");
+    assert_eq!(signals[0].date, day(16));
+    assert!(signals[0].reason.starts_with("Capitulation (when FEAR_GREED_INDEX < 20"));
+}
+
+#[test]
+fn test_signals_record_inputs_thresholds_and_rule_version() {
+    let rules = RuleSet::parse(DEEP_FEAR).unwrap();
+    let signal = &rules.evaluate(&falling_market().at(day(16)))[0];
+
+    let explanation = &signal.explanation;
+    assert_eq!(explanation.rule_version, rules.rules()[0].version());
+    assert_eq!(explanation.rule_version.len(), 12);
+    assert_eq!(explanation.inputs["FEAR_GREED_INDEX"], 18.0);
+    assert_eq!(explanation.inputs["BTC_USD"], 84.0);
+    assert_eq!(explanation.inputs["sma(BTC_USD, 5)"], 86.0);
+    assert_eq!(explanation.thresholds.len(), 1);
+    assert_eq!(explanation.thresholds["FEAR_GREED_INDEX"], 20.0);
+
+    let edited = RuleSet::parse(&DEEP_FEAR.replace("< 20", "< 25")).unwrap();
+    assert_ne!(edited.rules()[0].version(), explanation.rule_version);
+}
+
+#[test]
+fn test_missing_data_does_not_fire() {
+    let rules = RuleSet::parse(DEEP_FEAR).unwrap();
+    let mut data = falling_market();
+    data.prices.clear();
+
+    assert!(rules.evaluate(&data.at(day(20))).is_empty());
+}
+
+#[test]
+fn test_or_holds_when_one_side_is_true_and_the_other_missing() {
+    let rules = RuleSet::parse(
+        r#"
+[[rule]]
+name = "either"
+asset = "BTC"
+action = "SELL"
+when = "ETH_USD > 0 OR NOT (BTC_USD >= 90)"
+"#,
+    )
+    .unwrap();
+    let data = falling_market();
+
+    assert!(rules.evaluate(&data.at(day(10))).is_empty());
+    assert_eq!(rules.evaluate(&data.at(day(11))).len(), 1);
+}
+
+#[test]
+fn test_arithmetic_and_window_functions() {
+    let rules = RuleSet::parse(
+        r#"
+[[rule]]
+name = "drawdown"
+asset = "BTC"
+action = "WEIGHT"
+weight = 0.5
+when = "change(BTC_USD, 10) < -0.1 AND BTC_USD / max(BTC_USD, 20) - 1 <= -0.15 AND lag(BTC_USD, 1) - 1 == BTC_USD"
+"#,
+    )
+    .unwrap();
+    let data = falling_market();
+
+    // Ten days back is 11 points higher from day 10 on; the 20-day high needs 20 days.
+    assert!(rules.evaluate(&data.at(day(18))).is_empty());
+    let signals = rules.evaluate(&data.at(day(19)));
+    assert_eq!(signals.len(), 1);
+    assert_eq!(signals[0].action, SignalAction::Weight(0.5));
+    assert_eq!(signals[0].signal_type(), "DRAWDOWN_WEIGHT");
+}
+
+#[test]
+fn test_dry_run_lists_every_day_a_rule_fires() {
+    let rules = RuleSet::parse(DEEP_FEAR).unwrap();
+    let data = falling_market();
+
+    let fired = rules.dry_run(&data, day(10), day(20));
+    let dates: Vec<NaiveDate> = fired.iter().map(|s| s.date).collect();
+    assert_eq!(dates, (16..=20).map(day).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_expression_errors_carry_the_line() {
+    let errors = RuleSet::parse(
+        r#"
+[[rule]]
+name = "ok"
+asset = "BTC"
+action = "BUY"
+when = "BTC_USD < 1"
+
+[[rule]]
+name = "typo"
+asset = "BTC"
+action = "BUY"
+when = "BTC_UST < 1"
+
+[[rule]]
+name = "not_a_condition"
+asset = "BTC"
+action = "BUY"
+when = "BTC_USD + 1"
+"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].line, 12);
+    assert!(errors[0].message.contains("unknown series `BTC_UST`"));
+    assert_eq!(errors[1].line, 18);
+    assert!(errors[1].message.contains("must be a condition"));
+}
+
+#[test]
+fn test_multiline_condition_reports_the_offending_line() {
+    let errors = RuleSet::parse(
+        r#"[[rule]]
+name = "multi"
+asset = "BTC"
+action = "BUY"
+when = """
+FEAR_GREED_INDEX < 20
+AND sma(BTC_USD) > 1
+"""
+"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 7);
+    assert!(errors[0].to_string().starts_with("line 7: expected `,`"));
+}
+
+#[test]
+fn test_validation_errors() {
+    let errors = RuleSet::parse(
+        r#"
+[[rule]]
+name = "dup"
+asset = "BTC"
+action = "BUY"
+when = "BTC_USD < 1"
+
+[[rule]]
+name = "dup"
+asset = "BTC"
+action = "WEIGHT"
+when = "BTC_USD < 1"
+
+[[rule]]
+name = "bad action"
+asset = "BTC"
+action = "SHORT"
+confidence = 2
+when = "BTC_USD < 1 < 2"
+"#,
+    )
+    .unwrap_err();
+
+    let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![11, 15, 17, 18, 19]);
+    assert!(errors[0].message.contains("needs a `weight`"));
+    assert!(errors[4].message.contains("cannot be chained"));
+}
+
+#[test]
+fn test_window_sizes_are_bounded() {
+    let errors = RuleSet::parse(
+        r#"
+[[rule]]
+name = "huge"
+asset = "BTC"
+action = "BUY"
+when = "lag(BTC_USD, 100000000000000000000) < 1"
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 6);
+    assert!(errors[0].message.contains(&format!("from 1 to {MAX_RULE_WINDOW}")));
+
+    let rules = RuleSet::parse(&format!(
+        r#"
+[[rule]]
+name = "decade"
+asset = "BTC"
+action = "BUY"
+when = "change(BTC_USD, {MAX_RULE_WINDOW}) > 1"
+"#
+    ))
+    .unwrap();
+    assert_eq!(rules.lookback(), MAX_RULE_WINDOW + 1);
+    assert_eq!(rules.warm_up(), Duration::days((MAX_RULE_WINDOW as i64 + 1) * 7 / 5 + 30));
+    assert!(rules.evaluate(&falling_market().at(day(29))).is_empty());
+}
+
+#[test]
+fn test_toml_errors_carry_the_line() {
+    let errors = RuleSet::parse("[[rule]]\nname = \"x\"\nasset = \"BTC\"\naction = \"BUY\"\nwhen = \"BTC_USD < 1\"\nwhne = 1\n")
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 6);
+    assert!(errors[0].message.contains("unknown field"));
+}
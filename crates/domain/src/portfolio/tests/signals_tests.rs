@@ -0,0 +1,136 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    DominanceTrend, FearGreedExtremes, MarketContext, MarketDataSet, MarketSymbol, MayerMultiple, Signal, SignalAction,
+    Strategy, StrategyError, StrategyRegistry, TwoHundredWeekMa, BUILTIN_RULE_VERSION,
+};
+
+fn day(i: usize) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2020, 1, 1).unwrap() + Duration::days(i as i64)
+}
+
+fn series(values: impl IntoIterator<Item = f64>) -> Vec<(NaiveDate, f64)> {
+    values.into_iter().enumerate().map(|(i, v)| (day(i), v)).collect()
+}
+
+fn with_prices(asset: &str, closes: Vec<(NaiveDate, f64)>) -> MarketDataSet {
+    let mut data = MarketDataSet::default();
+    data.prices.insert(asset.to_string(), closes);
+    data
+}
+
+fn with_metric(symbol: MarketSymbol, values: Vec<(NaiveDate, f64)>) -> MarketDataSet {
+    let mut data = MarketDataSet::default();
+    data.metrics.insert(symbol.as_str().to_string(), values);
+    data
+}
+
+#[test]
+fn test_context_hides_observations_after_as_of() {
+    let data = with_prices("BTC", series([1.0, 2.0, 3.0]));
+    let ctx = data.at(day(1));
+
+    assert_eq!(ctx.price("BTC"), Some(2.0));
+    assert_eq!(ctx.prices("BTC").len(), 2);
+    assert!(ctx.prices("ETH").is_empty());
+    assert_eq!(data.at(day(10)).price("BTC"), Some(3.0));
+    assert_eq!(data.at(day(0) - Duration::days(1)).price("BTC"), None);
+}
+
+#[test]
+fn test_200_week_ma_needs_a_full_window() {
+    let mut closes = series(std::iter::repeat_n(100.0, TwoHundredWeekMa::WINDOW_DAYS - 1));
+    closes.push((day(TwoHundredWeekMa::WINDOW_DAYS - 1), 50.0));
+    let data = with_prices("BTC", closes);
+
+    let signals = TwoHundredWeekMa::default().evaluate(&data.at(day(TwoHundredWeekMa::WINDOW_DAYS - 1)));
+    assert_eq!(signals.len(), 1);
+    assert_eq!(signals[0].action, SignalAction::Buy);
+    assert!(signals[0].reason.contains("below its 200-week moving average"));
+
+    // One day earlier the window is not full yet.
+    assert!(TwoHundredWeekMa::default().evaluate(&data.at(day(TwoHundredWeekMa::WINDOW_DAYS - 2))).is_empty());
+}
+
+#[test]
+fn test_mayer_multiple_bands() {
+    let mut closes = series(std::iter::repeat_n(100.0, MayerMultiple::WINDOW_DAYS));
+    closes.push((day(MayerMultiple::WINDOW_DAYS), 400.0));
+    let data = with_prices("ETH", closes);
+
+    let signals = MayerMultiple::default().evaluate(&data.at(day(MayerMultiple::WINDOW_DAYS)));
+    assert_eq!(signals[0].action, SignalAction::Sell);
+    assert_eq!(signals[0].asset_symbol, "ETH");
+    assert_eq!(signals[0].signal_type(), "MAYER_MULTIPLE_SELL");
+    assert!(signals[0].confidence > 0.5 && signals[0].confidence <= 1.0);
+
+    let explanation = &signals[0].explanation;
+    assert_eq!(explanation.rule_version, BUILTIN_RULE_VERSION);
+    assert_eq!(explanation.inputs["close"], 400.0);
+    assert_eq!(explanation.inputs["mayer_multiple"], 400.0 / explanation.inputs["moving_average"]);
+    assert_eq!(explanation.thresholds["overheated_above"], MayerMultiple::OVERHEATED_ABOVE);
+
+    assert!(MayerMultiple::default().evaluate(&data.at(day(MayerMultiple::WINDOW_DAYS - 1))).is_empty());
+}
+
+#[test]
+fn test_fear_greed_and_dominance() {
+    let fear = with_metric(MarketSymbol::FearGreedIndex, series([50.0, 12.0]));
+    assert_eq!(FearGreedExtremes::default().evaluate(&fear.at(day(1)))[0].action, SignalAction::Buy);
+    assert!(FearGreedExtremes::default().evaluate(&fear.at(day(0))).is_empty());
+
+    // Stored as a fraction: 50% to 53% over 30 days.
+    let rising = with_metric(MarketSymbol::BtcDominance, series((0..=30).map(|i| 0.50 + i as f64 * 0.001)));
+    let signals = DominanceTrend::default().evaluate(&rising.at(day(30)));
+    assert_eq!(signals[0].action, SignalAction::Sell);
+    assert_eq!(signals[0].asset_symbol, "ETH");
+    assert!(signals[0].reason.contains("rose 3.0 points from 50.0%"), "{}", signals[0].reason);
+    assert!((signals[0].explanation.inputs["change_points"] - 3.0).abs() < 1e-9);
+    assert!(DominanceTrend::default().evaluate(&rising.at(day(10))).is_empty());
+
+    // A point and a half is below the threshold.
+    let drifting = with_metric(MarketSymbol::BtcDominance, series((0..=30).map(|i| 0.50 - i as f64 * 0.0005)));
+    assert!(DominanceTrend::default().evaluate(&drifting.at(day(30))).is_empty());
+}
+
+struct AlwaysHold;
+
+impl Strategy for AlwaysHold {
+    fn name(&self) -> &'static str {
+        "always_hold"
+    }
+
+    fn description(&self) -> &'static str {
+        "Holds everything"
+    }
+
+    fn inputs(&self) -> Vec<MarketSymbol> {
+        vec![MarketSymbol::BtcUsd]
+    }
+
+    fn evaluate(&self, ctx: &MarketContext) -> Vec<Signal> {
+        vec![Signal::new(self.name(), "BTC", ctx, SignalAction::Hold, 2.0, "test".to_string())]
+    }
+}
+
+#[test]
+fn test_registry_enumerates_by_name_and_rejects_duplicates() {
+    let mut registry = StrategyRegistry::builtin();
+    registry.register(Box::new(AlwaysHold)).unwrap();
+
+    assert_eq!(
+        registry.names(),
+        vec!["200w_ma", "always_hold", "btc_dominance_trend", "fear_greed_extremes", "mayer_multiple"]
+    );
+    assert_eq!(
+        registry.register(Box::new(AlwaysHold)),
+        Err(StrategyError::DuplicateStrategy("always_hold".to_string()))
+    );
+    assert!(registry.get("nope").is_err());
+
+    let data = MarketDataSet::default();
+    let signals = registry.evaluate_all(&data.at(day(0)));
+    assert_eq!(signals.len(), 1);
+    assert_eq!(signals[0].confidence, 1.0);
+    assert_eq!(signals[0].date, day(0));
+}
@@ -0,0 +1,90 @@
+use uuid::Uuid;
+
+use super::fixtures::{at, tx};
+use crate::{CostBasisMethod, HoldingTerm, Jurisdiction, TaxReport, TransactionKind};
+
+#[test]
+fn test_holding_term_splits_after_one_year() {
+    assert_eq!(HoldingTerm::classify(at((2023, 3, 1)), at((2024, 3, 1))), HoldingTerm::ShortTerm);
+    assert_eq!(HoldingTerm::classify(at((2023, 3, 1)), at((2024, 3, 2))), HoldingTerm::LongTerm);
+}
+
+#[test]
+fn test_report_keeps_disposals_of_the_year() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2022, 1, 10), 1.0, Some(40_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2023, 11, 1), 1.0, Some(35_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2023, 6, 1), 0.5, Some(30_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2024, 2, 1), 1.0, Some(45_000.0)),
+    ];
+
+    let report = TaxReport::build(Uuid::new_v4(), 2024, CostBasisMethod::Fifo, Jurisdiction::Us, &txs).unwrap();
+
+    assert_eq!(report.rows.len(), 2);
+    assert_eq!(report.rows[0].term, HoldingTerm::LongTerm);
+    assert_eq!(report.rows[0].quantity, 0.5);
+    assert_eq!(report.rows[1].term, HoldingTerm::ShortTerm);
+    assert_eq!(report.long_term_gain, 0.5 * 5_000.0);
+    assert_eq!(report.short_term_gain, 0.5 * 10_000.0);
+    assert_eq!(report.total_gain, 7_500.0);
+    assert_eq!(report.taxable_gain, 7_500.0);
+}
+
+#[test]
+fn test_german_report_exempts_long_term_gains() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2022, 1, 10), 1.0, Some(40_000.0)),
+        tx(account, TransactionKind::Buy, "BTC", (2023, 11, 1), 1.0, Some(35_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2024, 2, 1), 2.0, Some(45_000.0)),
+    ];
+
+    let report = TaxReport::build(Uuid::new_v4(), 2024, CostBasisMethod::Fifo, Jurisdiction::De, &txs).unwrap();
+
+    assert!(!report.rows[0].taxable);
+    assert!(report.rows[1].taxable);
+    assert_eq!(report.tax_free_gain, 5_000.0);
+    assert_eq!(report.taxable_gain, 10_000.0);
+    assert!(report.to_csv().lines().nth(1).unwrap().contains(",LONG_TERM,false,"));
+}
+
+#[test]
+fn test_report_is_reproducible() {
+    let account = Uuid::new_v4();
+    let mut txs = vec![
+        tx(account, TransactionKind::Buy, "ETH", (2024, 1, 1), 2.0, Some(2_000.0)),
+        tx(account, TransactionKind::Buy, "ETH", (2024, 1, 1), 2.0, Some(2_500.0)),
+        tx(account, TransactionKind::Sell, "ETH", (2024, 4, 1), 3.0, Some(3_000.0)),
+    ];
+    let portfolio_id = Uuid::new_v4();
+
+    let first = TaxReport::build(portfolio_id, 2024, CostBasisMethod::Fifo, Jurisdiction::Us, &txs).unwrap();
+    txs.reverse();
+    let second = TaxReport::build(portfolio_id, 2024, CostBasisMethod::Fifo, Jurisdiction::Us, &txs).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(first.to_csv(), second.to_csv());
+    assert!(first.to_csv().starts_with(TaxReport::CSV_HEADER));
+    assert_eq!(first.to_csv().lines().count(), 3);
+}
+
+#[test]
+fn test_csv_lists_income_and_quotes_symbols() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2024, 1, 2), 1.0, Some(40_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2024, 3, 1), 1.0, Some(50_000.0)),
+        tx(account, TransactionKind::StakingReward, "ETH", (2024, 4, 1), 0.5, Some(3_000.0)),
+        tx(account, TransactionKind::Airdrop, "A,\"B\"", (2024, 5, 1), 10.0, Some(1.5)),
+    ];
+
+    let csv = TaxReport::build(Uuid::new_v4(), 2024, CostBasisMethod::Fifo, Jurisdiction::Us, &txs).unwrap().to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[2], "");
+    assert_eq!(lines[3], TaxReport::INCOME_CSV_HEADER);
+    assert!(lines[4].starts_with("ETH,0.50000000,2024-04-01,STAKING_REWARD,3000.00,1500.00,"));
+    assert!(lines[5].starts_with("\"A,\"\"B\"\"\",10.00000000,2024-05-01,AIRDROP,1.50,15.00,"));
+}
@@ -0,0 +1,147 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    builtin_tunables, candidates, walk_forward, BacktestConfig, DominanceTrend, FearGreedExtremes, MarketDataSet,
+    MarketSymbol, Objective, ParameterRange, Parameters, SearchMethod, Tunable, WalkForwardConfig, WalkForwardError,
+};
+
+fn day(i: i64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2022, 1, 1).unwrap() + Duration::days(i)
+}
+
+/// BTC swinging between 50 and 150 every 60 days, with the Fear & Greed Index following it
+/// between 5 and 95.
+fn cycles(days: i64) -> MarketDataSet {
+    let phase = |i: i64| (i as f64 * std::f64::consts::TAU / 60.0).sin();
+    let mut data = MarketDataSet::default();
+    data.prices.insert("BTC".to_string(), (0..days).map(|i| (day(i), 100.0 + 50.0 * phase(i))).collect());
+    data.metrics.insert(
+        MarketSymbol::FearGreedIndex.as_str().to_string(),
+        (0..days).map(|i| (day(i), 50.0 + 45.0 * phase(i))).collect(),
+    );
+    data
+}
+
+fn config(days: i64, in_sample_days: i64, out_of_sample_days: i64) -> WalkForwardConfig {
+    WalkForwardConfig {
+        in_sample_days,
+        out_of_sample_days,
+        ..WalkForwardConfig::new(BacktestConfig::new(day(0), day(days - 1), 10_000.0))
+    }
+}
+
+#[test]
+fn test_grid_and_random_candidates() {
+    let space = FearGreedExtremes::default().search_space();
+    let grid = candidates(&space, SearchMethod::Grid).unwrap();
+    assert_eq!(grid.len(), 25);
+    assert_eq!(grid[0]["fear_at_or_below"], 10.0);
+    assert_eq!(grid[0]["greed_at_or_above"], 70.0);
+    assert_eq!(grid[1]["greed_at_or_above"], 75.0);
+
+    let random = candidates(&space, SearchMethod::Random { samples: 8, seed: 7 }).unwrap();
+    assert_eq!(random.len(), 8);
+    assert!(random.iter().all(|p| space.iter().all(|r| r.values.contains(&p[&r.name]))));
+    assert_eq!(random, candidates(&space, SearchMethod::Random { samples: 8, seed: 7 }).unwrap());
+
+    let empty = [ParameterRange::new("fear_at_or_below", &[])];
+    assert!(matches!(candidates(&empty, SearchMethod::Grid), Err(WalkForwardError::InvalidParameters(_))));
+}
+
+#[test]
+fn test_with_parameters_rejects_unknown_and_crossed_values() {
+    for strategy in builtin_tunables() {
+        let defaults = strategy.parameters();
+        assert_eq!(strategy.with_parameters(&defaults).unwrap().name(), strategy.name());
+
+        let unknown = Parameters::from([("window".to_string(), 1.0)]);
+        assert!(strategy.with_parameters(&unknown).is_err());
+    }
+
+    let strategy = FearGreedExtremes::default();
+    let crossed = Parameters::from([("fear_at_or_below".to_string(), 80.0), ("greed_at_or_above".to_string(), 70.0)]);
+    assert!(matches!(strategy.with_parameters(&crossed), Err(WalkForwardError::InvalidParameters(_))));
+}
+
+#[test]
+fn test_windows_roll_by_the_out_of_sample_length() {
+    let windows = config(400, 180, 60).windows();
+
+    assert_eq!(windows.len(), 3);
+    assert_eq!(windows[0], (day(0), day(179), day(180), day(239)));
+    assert_eq!(windows[1].0, day(60));
+    assert_eq!(windows[2].3, day(359));
+}
+
+#[test]
+fn test_walk_forward_scores_each_window_out_of_sample() {
+    let space = vec![
+        ParameterRange::new("fear_at_or_below", &[10.0, 20.0, 30.0]),
+        ParameterRange::new("greed_at_or_above", &[70.0, 80.0]),
+    ];
+    let report = walk_forward(&FearGreedExtremes::default(), &space, &cycles(400), &config(400, 180, 60)).unwrap();
+
+    assert_eq!(report.strategy, "fear_greed_extremes");
+    assert_eq!(report.windows.len(), 3);
+    assert_eq!(report.skipped_windows, 0);
+    for window in &report.windows {
+        assert_eq!(window.candidates, 6);
+        assert_eq!(window.parameters.len(), 2);
+        assert!(window.in_sample_score.is_finite() && window.out_of_sample_score.is_finite());
+    }
+
+    let compounded = report.windows.iter().map(|w| 1.0 + w.out_of_sample_return).product::<f64>() - 1.0;
+    assert!((report.out_of_sample_return - compounded).abs() < 1e-12);
+    assert_eq!(report.stability.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["fear_at_or_below", "greed_at_or_above"]);
+}
+
+#[test]
+fn test_a_single_valid_candidate_is_perfectly_stable() {
+    // 90 is above the greed threshold, so only the 20/80 combination is tradable.
+    let space = vec![
+        ParameterRange::new("fear_at_or_below", &[20.0, 90.0]),
+        ParameterRange::new("greed_at_or_above", &[80.0]),
+    ];
+    let mut config = config(400, 180, 60);
+    config.objective = Objective::TotalReturn;
+    let report = walk_forward(&FearGreedExtremes::default(), &space, &cycles(400), &config).unwrap();
+
+    assert!(report.windows.iter().all(|w| w.candidates == 1));
+    let fear = &report.stability[0];
+    assert_eq!((fear.distinct_values, fear.most_common, fear.changes), (1, 20.0, 0));
+    assert_eq!(fear.most_common_share, 1.0);
+    assert_eq!(fear.coefficient_of_variation, Some(0.0));
+}
+
+#[test]
+fn test_windows_without_closes_are_skipped() {
+    let space = FearGreedExtremes::default().search_space();
+    // Closes only cover the first 300 of 400 days: the last window has nothing to trade.
+    let report = walk_forward(&FearGreedExtremes::default(), &space, &cycles(300), &config(400, 180, 60)).unwrap();
+    assert_eq!(report.windows.len(), 2);
+    assert_eq!(report.skipped_windows, 1);
+
+    let none = walk_forward(&FearGreedExtremes::default(), &space, &MarketDataSet::default(), &config(400, 180, 60));
+    assert_eq!(none.unwrap_err(), WalkForwardError::NoWindows);
+    assert_eq!("total-return".parse::<Objective>().unwrap(), Objective::TotalReturn);
+}
+
+#[test]
+fn test_dominance_search_space_is_in_percentage_points() {
+    // Dominance is stored as a fraction: 50% for 100 days, then 54%.
+    let dominance = |step: f64| {
+        let mut data = MarketDataSet::default();
+        data.metrics.insert(
+            MarketSymbol::BtcDominance.as_str().to_string(),
+            (0..106).map(|i| (day(i), if i < 100 { 0.50 } else { 0.50 + step })).collect(),
+        );
+        data
+    };
+    let (jump, drift) = (dominance(0.04), dominance(0.005));
+
+    for parameters in candidates(&DominanceTrend::default().search_space(), SearchMethod::Grid).unwrap() {
+        let strategy = DominanceTrend::default().with_parameters(&parameters).unwrap();
+        assert_eq!(strategy.evaluate(&jump.at(day(105))).len(), 1, "{parameters:?} missed a 4 point rise");
+        assert!(strategy.evaluate(&drift.at(day(105))).is_empty(), "{parameters:?} fired on half a point");
+    }
+}
@@ -0,0 +1,506 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::backtest::{backtest, BacktestConfig, BacktestError, BacktestResult};
+use crate::portfolio::signals::{DominanceTrend, FearGreedExtremes, MarketDataSet, MayerMultiple, Strategy, TwoHundredWeekMa};
+
+/// Named numeric parameters of a strategy, e.g. `window_days = 200`.
+pub type Parameters = BTreeMap<String, f64>;
+
+/// Values worth trying for one parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterRange {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+impl ParameterRange {
+    pub fn new(name: &str, values: &[f64]) -> Self {
+        Self { name: name.to_string(), values: values.to_vec() }
+    }
+}
+
+/// A strategy whose parameters the walk-forward optimizer may search.
+pub trait Tunable: Strategy {
+    /// Parameters currently in effect.
+    fn parameters(&self) -> Parameters;
+
+    /// The grid searched by default.
+    fn search_space(&self) -> Vec<ParameterRange>;
+
+    /// The same strategy with `parameters` applied over the current ones. Unknown names and
+    /// combinations that make no sense (a buy band above the sell band) are rejected.
+    fn with_parameters(&self, parameters: &Parameters) -> Result<Box<dyn Strategy>, WalkForwardError>;
+}
+
+/// The built-in strategies that have parameters, by strategy name.
+pub fn builtin_tunables() -> Vec<Box<dyn Tunable>> {
+    vec![
+        Box::new(TwoHundredWeekMa::default()),
+        Box::new(MayerMultiple::default()),
+        Box::new(FearGreedExtremes::default()),
+        Box::new(DominanceTrend::default()),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "method")]
+pub enum SearchMethod {
+    /// Every combination of the search space.
+    Grid,
+    /// `samples` combinations drawn from the search space, reproducible through `seed`.
+    Random { samples: usize, seed: u64 },
+}
+
+/// What a window's parameters are chosen for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Objective {
+    /// Sharpe ratio of the equity curve; zero when it cannot be computed, e.g. for a flat curve.
+    #[default]
+    Sharpe,
+    TotalReturn,
+}
+
+impl Objective {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Objective::Sharpe => "SHARPE",
+            Objective::TotalReturn => "TOTAL_RETURN",
+        }
+    }
+
+    pub fn score(&self, result: &BacktestResult) -> f64 {
+        match self {
+            Objective::Sharpe => result.stats.performance.as_ref().and_then(|p| p.sharpe_ratio).unwrap_or(0.0),
+            Objective::TotalReturn => result.stats.total_return,
+        }
+    }
+}
+
+impl FromStr for Objective {
+    type Err = ParseObjectiveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().replace('-', "_").as_str() {
+            "SHARPE" => Ok(Objective::Sharpe),
+            "TOTAL_RETURN" | "RETURN" => Ok(Objective::TotalReturn),
+            other => Err(ParseObjectiveError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseObjectiveError(pub String);
+
+impl fmt::Display for ParseObjectiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid objective: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseObjectiveError {}
+
+/// Rolling windows over `backtest.from..=backtest.to`: parameters are searched on
+/// `in_sample_days`, then traded unchanged on the `out_of_sample_days` that follow. Windows
+/// advance by the out-of-sample length, so out-of-sample periods never overlap. Fees, slippage
+/// and sizing come from `backtest`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardConfig {
+    pub backtest: BacktestConfig,
+    pub in_sample_days: i64,
+    pub out_of_sample_days: i64,
+    pub search: SearchMethod,
+    pub objective: Objective,
+}
+
+impl WalkForwardConfig {
+    /// Two years in sample, half a year out of sample, full grid, Sharpe.
+    pub fn new(backtest: BacktestConfig) -> Self {
+        Self { backtest, in_sample_days: 730, out_of_sample_days: 182, search: SearchMethod::Grid, objective: Objective::Sharpe }
+    }
+
+    /// `(in-sample from, in-sample to, out-of-sample from, out-of-sample to)` of every window
+    /// that fits completely.
+    pub fn windows(&self) -> Vec<(NaiveDate, NaiveDate, NaiveDate, NaiveDate)> {
+        let mut windows = Vec::new();
+        let mut start = self.backtest.from;
+        loop {
+            let in_sample_to = start + Duration::days(self.in_sample_days - 1);
+            let out_of_sample_from = in_sample_to + Duration::days(1);
+            let out_of_sample_to = out_of_sample_from + Duration::days(self.out_of_sample_days - 1);
+            if out_of_sample_to > self.backtest.to {
+                return windows;
+            }
+            windows.push((start, in_sample_to, out_of_sample_from, out_of_sample_to));
+            start += Duration::days(self.out_of_sample_days);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalkForwardError {
+    InvalidConfig(String),
+    InvalidParameters(String),
+    /// No window had both in-sample and out-of-sample closes.
+    NoWindows,
+    Backtest(BacktestError),
+}
+
+impl fmt::Display for WalkForwardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalkForwardError::InvalidConfig(reason) => write!(f, "Invalid walk-forward configuration: {}", reason),
+            WalkForwardError::InvalidParameters(reason) => write!(f, "Invalid parameters: {}", reason),
+            WalkForwardError::NoWindows => write!(f, "No walk-forward window has data in and out of sample"),
+            WalkForwardError::Backtest(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for WalkForwardError {}
+
+impl From<BacktestError> for WalkForwardError {
+    fn from(e: BacktestError) -> Self {
+        WalkForwardError::Backtest(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardWindow {
+    pub in_sample_from: NaiveDate,
+    pub in_sample_to: NaiveDate,
+    pub out_of_sample_from: NaiveDate,
+    pub out_of_sample_to: NaiveDate,
+    /// The best parameters in sample, the only ones traded out of sample.
+    pub parameters: Parameters,
+    pub candidates: usize,
+    pub in_sample_score: f64,
+    pub out_of_sample_score: f64,
+    pub out_of_sample_return: f64,
+}
+
+/// How much the chosen value of one parameter moved from window to window. Values that keep
+/// jumping around mean the in-sample optimum is mostly noise.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterStability {
+    pub name: String,
+    pub distinct_values: usize,
+    pub most_common: f64,
+    /// Share of windows that chose `most_common`.
+    pub most_common_share: f64,
+    /// Times the choice differed from the previous window's.
+    pub changes: usize,
+    /// Standard deviation over the mean of the chosen values.
+    pub coefficient_of_variation: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardReport {
+    pub strategy: String,
+    pub objective: Objective,
+    pub windows: Vec<WalkForwardWindow>,
+    /// Windows skipped for lack of closes.
+    pub skipped_windows: usize,
+    pub stability: Vec<ParameterStability>,
+    /// Out-of-sample returns compounded window after window.
+    pub out_of_sample_return: f64,
+    pub mean_in_sample_score: f64,
+    pub mean_out_of_sample_score: f64,
+    /// Mean out-of-sample over mean in-sample score. Far below 1 is the signature of overfitting.
+    pub efficiency: Option<f64>,
+}
+
+/// Optimizes `strategy` on each in-sample window and scores the winner on the window after it.
+/// Each out-of-sample replay starts from cash, so no position carries over from the search.
+pub fn walk_forward(
+    strategy: &dyn Tunable,
+    space: &[ParameterRange],
+    data: &MarketDataSet,
+    config: &WalkForwardConfig,
+) -> Result<WalkForwardReport, WalkForwardError> {
+    if config.in_sample_days < 1 || config.out_of_sample_days < 1 {
+        return Err(WalkForwardError::InvalidConfig("window lengths must be at least one day".to_string()));
+    }
+    let candidates = candidates(space, config.search)?;
+    let strategies: Vec<(Parameters, Box<dyn Strategy>)> = candidates
+        .into_iter()
+        .filter_map(|parameters| strategy.with_parameters(&parameters).ok().map(|s| (parameters, s)))
+        .collect();
+    if strategies.is_empty() {
+        return Err(WalkForwardError::InvalidParameters("no combination of the search space is valid".to_string()));
+    }
+
+    let mut windows = Vec::new();
+    let mut skipped_windows = 0;
+    for (in_sample_from, in_sample_to, out_of_sample_from, out_of_sample_to) in config.windows() {
+        let Some((best, in_sample_score)) = best_in_sample(&strategies, data, config, in_sample_from, in_sample_to)? else {
+            skipped_windows += 1;
+            continue;
+        };
+        let (parameters, winner) = &strategies[best];
+
+        let out_of_sample_config = BacktestConfig { from: out_of_sample_from, to: out_of_sample_to, ..config.backtest.clone() };
+        let result = match backtest(winner.as_ref(), data, &out_of_sample_config) {
+            Ok(result) => result,
+            Err(BacktestError::NoData { .. }) => {
+                skipped_windows += 1;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        windows.push(WalkForwardWindow {
+            in_sample_from,
+            in_sample_to,
+            out_of_sample_from,
+            out_of_sample_to,
+            parameters: parameters.clone(),
+            candidates: strategies.len(),
+            in_sample_score,
+            out_of_sample_score: config.objective.score(&result),
+            out_of_sample_return: result.stats.total_return,
+        });
+    }
+    if windows.is_empty() {
+        return Err(WalkForwardError::NoWindows);
+    }
+
+    let mean = |scores: Vec<f64>| scores.iter().sum::<f64>() / scores.len() as f64;
+    let mean_in_sample_score = mean(windows.iter().map(|w| w.in_sample_score).collect());
+    let mean_out_of_sample_score = mean(windows.iter().map(|w| w.out_of_sample_score).collect());
+
+    Ok(WalkForwardReport {
+        strategy: strategy.name().to_string(),
+        objective: config.objective,
+        stability: space.iter().map(|range| stability(&range.name, &windows)).collect(),
+        out_of_sample_return: windows.iter().map(|w| 1.0 + w.out_of_sample_return).product::<f64>() - 1.0,
+        efficiency: (mean_in_sample_score.abs() > f64::EPSILON).then(|| mean_out_of_sample_score / mean_in_sample_score),
+        mean_in_sample_score,
+        mean_out_of_sample_score,
+        skipped_windows,
+        windows,
+    })
+}
+
+/// Index and score of the best candidate in sample; the first one wins ties. `None` when the
+/// window has no closes.
+fn best_in_sample(
+    strategies: &[(Parameters, Box<dyn Strategy>)],
+    data: &MarketDataSet,
+    config: &WalkForwardConfig,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Option<(usize, f64)>, WalkForwardError> {
+    let in_sample_config = BacktestConfig { from, to, ..config.backtest.clone() };
+    let mut best: Option<(usize, f64)> = None;
+    for (i, (_, strategy)) in strategies.iter().enumerate() {
+        let result = match backtest(strategy.as_ref(), data, &in_sample_config) {
+            Ok(result) => result,
+            Err(BacktestError::NoData { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let score = config.objective.score(&result);
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((i, score));
+        }
+    }
+    Ok(best)
+}
+
+/// Parameter sets to try, in a stable order.
+pub fn candidates(space: &[ParameterRange], search: SearchMethod) -> Result<Vec<Parameters>, WalkForwardError> {
+    if let Some(range) = space.iter().find(|r| r.values.is_empty()) {
+        return Err(WalkForwardError::InvalidParameters(format!("no values to try for {}", range.name)));
+    }
+
+    match search {
+        SearchMethod::Grid => Ok(space.iter().fold(vec![Parameters::new()], |combinations, range| {
+            combinations
+                .iter()
+                .flat_map(|combination| {
+                    range.values.iter().map(move |value| {
+                        let mut next = combination.clone();
+                        next.insert(range.name.clone(), *value);
+                        next
+                    })
+                })
+                .collect()
+        })),
+        SearchMethod::Random { samples, seed } => {
+            let mut state = seed;
+            Ok((0..samples)
+                .map(|_| {
+                    space
+                        .iter()
+                        .map(|range| {
+                            let pick = (split_mix(&mut state) % range.values.len() as u64) as usize;
+                            (range.name.clone(), range.values[pick])
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+}
+
+/// SplitMix64: small, seedable and good enough to spread samples over a grid.
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn stability(name: &str, windows: &[WalkForwardWindow]) -> ParameterStability {
+    let chosen: Vec<f64> = windows.iter().filter_map(|w| w.parameters.get(name).copied()).collect();
+
+    let mut counts: Vec<(f64, usize)> = Vec::new();
+    for value in &chosen {
+        match counts.iter_mut().find(|(v, _)| v == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*value, 1)),
+        }
+    }
+    let (most_common, most_common_count) = counts
+        .iter()
+        .copied()
+        .reduce(|best, next| if next.1 > best.1 { next } else { best })
+        .unwrap_or((f64::NAN, 0));
+
+    let n = chosen.len() as f64;
+    let mean = chosen.iter().sum::<f64>() / n;
+    let std_dev = (chosen.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    ParameterStability {
+        name: name.to_string(),
+        distinct_values: counts.len(),
+        most_common,
+        most_common_share: most_common_count as f64 / n,
+        changes: chosen.windows(2).filter(|pair| pair[0] != pair[1]).count(),
+        coefficient_of_variation: (mean.abs() > f64::EPSILON).then(|| std_dev / mean.abs()),
+    }
+}
+
+/// Applies `overrides` to `current`, refusing names `current` does not have.
+fn merge(current: Parameters, overrides: &Parameters) -> Result<Parameters, WalkForwardError> {
+    let mut merged = current;
+    for (name, value) in overrides {
+        match merged.get_mut(name) {
+            Some(slot) => *slot = *value,
+            None => return Err(WalkForwardError::InvalidParameters(format!("unknown parameter {name}"))),
+        }
+    }
+    Ok(merged)
+}
+
+fn window_days(parameters: &Parameters, name: &str) -> Result<usize, WalkForwardError> {
+    let days = parameters[name];
+    if days.fract() != 0.0 || days < 1.0 {
+        return Err(WalkForwardError::InvalidParameters(format!("{name} must be a whole number of days, got {days}")));
+    }
+    Ok(days as usize)
+}
+
+impl Tunable for TwoHundredWeekMa {
+    fn parameters(&self) -> Parameters {
+        Parameters::from([("window_days".to_string(), self.window_days as f64)])
+    }
+
+    fn search_space(&self) -> Vec<ParameterRange> {
+        // 100, 150 and 200 weeks.
+        vec![ParameterRange::new("window_days", &[700.0, 1050.0, 1400.0])]
+    }
+
+    fn with_parameters(&self, parameters: &Parameters) -> Result<Box<dyn Strategy>, WalkForwardError> {
+        let p = merge(self.parameters(), parameters)?;
+        Ok(Box::new(TwoHundredWeekMa { window_days: window_days(&p, "window_days")? }))
+    }
+}
+
+impl Tunable for MayerMultiple {
+    fn parameters(&self) -> Parameters {
+        Parameters::from([
+            ("window_days".to_string(), self.window_days as f64),
+            ("undervalued_below".to_string(), self.undervalued_below),
+            ("overheated_above".to_string(), self.overheated_above),
+        ])
+    }
+
+    fn search_space(&self) -> Vec<ParameterRange> {
+        vec![
+            ParameterRange::new("window_days", &[100.0, 150.0, 200.0, 250.0]),
+            ParameterRange::new("undervalued_below", &[0.6, 0.7, 0.8, 0.9]),
+            ParameterRange::new("overheated_above", &[1.8, 2.1, 2.4, 2.7]),
+        ]
+    }
+
+    fn with_parameters(&self, parameters: &Parameters) -> Result<Box<dyn Strategy>, WalkForwardError> {
+        let p = merge(self.parameters(), parameters)?;
+        let (undervalued_below, overheated_above) = (p["undervalued_below"], p["overheated_above"]);
+        if !(0.0 < undervalued_below && undervalued_below < overheated_above) {
+            return Err(WalkForwardError::InvalidParameters(format!(
+                "undervalued_below {undervalued_below} must be positive and below overheated_above {overheated_above}"
+            )));
+        }
+        Ok(Box::new(MayerMultiple { window_days: window_days(&p, "window_days")?, undervalued_below, overheated_above }))
+    }
+}
+
+impl Tunable for FearGreedExtremes {
+    fn parameters(&self) -> Parameters {
+        Parameters::from([
+            ("fear_at_or_below".to_string(), self.fear_at_or_below),
+            ("greed_at_or_above".to_string(), self.greed_at_or_above),
+        ])
+    }
+
+    fn search_space(&self) -> Vec<ParameterRange> {
+        vec![
+            ParameterRange::new("fear_at_or_below", &[10.0, 15.0, 20.0, 25.0, 30.0]),
+            ParameterRange::new("greed_at_or_above", &[70.0, 75.0, 80.0, 85.0, 90.0]),
+        ]
+    }
+
+    fn with_parameters(&self, parameters: &Parameters) -> Result<Box<dyn Strategy>, WalkForwardError> {
+        let p = merge(self.parameters(), parameters)?;
+        let (fear_at_or_below, greed_at_or_above) = (p["fear_at_or_below"], p["greed_at_or_above"]);
+        if !(0.0 < fear_at_or_below && fear_at_or_below < greed_at_or_above && greed_at_or_above < 100.0) {
+            return Err(WalkForwardError::InvalidParameters(format!(
+                "need 0 < fear_at_or_below {fear_at_or_below} < greed_at_or_above {greed_at_or_above} < 100"
+            )));
+        }
+        Ok(Box::new(FearGreedExtremes { fear_at_or_below, greed_at_or_above }))
+    }
+}
+
+impl Tunable for DominanceTrend {
+    fn parameters(&self) -> Parameters {
+        Parameters::from([
+            ("lookback_days".to_string(), self.lookback_days as f64),
+            ("trend_points".to_string(), self.trend_points),
+        ])
+    }
+
+    fn search_space(&self) -> Vec<ParameterRange> {
+        vec![
+            ParameterRange::new("lookback_days", &[14.0, 30.0, 60.0, 90.0]),
+            // Percentage points of dominance, not the stored fraction.
+            ParameterRange::new("trend_points", &[1.0, 2.0, 3.0]),
+        ]
+    }
+
+    fn with_parameters(&self, parameters: &Parameters) -> Result<Box<dyn Strategy>, WalkForwardError> {
+        let p = merge(self.parameters(), parameters)?;
+        let trend_points = p["trend_points"];
+        if trend_points <= 0.0 {
+            return Err(WalkForwardError::InvalidParameters(format!("trend_points must be positive, got {trend_points}")));
+        }
+        Ok(Box::new(DominanceTrend { lookback_days: window_days(&p, "lookback_days")? as i64, trend_points }))
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::{AlertChannel, AlertCondition, AlertRule, DeliveryStatus, SignalEvent};
+
+fn day(i: i64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, 1).unwrap() + Duration::days(i)
+}
+
+fn noon(i: i64) -> NaiveDateTime {
+    day(i).and_hms_opt(12, 0, 0).unwrap()
+}
+
+fn rule(condition: &str) -> AlertRule {
+    AlertRule {
+        id: Uuid::new_v4(),
+        name: "test".to_string(),
+        condition: condition.parse().unwrap(),
+        channel: AlertChannel::Webhook,
+        target: "http://localhost/hook".to_string(),
+        cooldown_minutes: 60,
+        enabled: true,
+        created_at: noon(0),
+    }
+}
+
+fn signal(i: i64, asset: &str, signal_type: &str, source: &str) -> SignalEvent {
+    SignalEvent {
+        id: Uuid::new_v4(),
+        asset_symbol: asset.to_string(),
+        timestamp: day(i).and_hms_opt(0, 0, 0).unwrap(),
+        signal_type: signal_type.to_string(),
+        value: Some(0.7),
+        description: Some("because".to_string()),
+        source: Some(source.to_string()),
+    }
+}
+
+#[test]
+fn test_parse_and_print_conditions() {
+    for text in [
+        "BTC_DOMINANCE crosses 0.6",
+        "BTC_USD crosses above 100000",
+        "FEAR_GREED_INDEX below 15",
+        "new BUY signal from longterm",
+        "new signal for BTC",
+    ] {
+        assert_eq!(text.parse::<AlertCondition>().unwrap().to_string(), text);
+    }
+    assert_eq!("fear_greed_index BELOW 15".parse::<AlertCondition>().unwrap().to_string(), "FEAR_GREED_INDEX below 15");
+
+    for invalid in ["", "NOT_A_SERIES below 1", "BTC_USD near 5", "BTC_USD below x", "new BUY", "new signal to BTC"] {
+        assert!(invalid.parse::<AlertCondition>().is_err(), "{invalid}");
+    }
+    assert_eq!("smtp".parse::<AlertChannel>().unwrap(), AlertChannel::Email);
+}
+
+#[test]
+fn test_crosses_compares_the_last_two_observations() {
+    let crosses = rule("BTC_DOMINANCE crosses 0.6");
+    assert!(crosses.evaluate(&[(day(0), 0.58), (day(1), 0.59)], &[]).is_empty());
+
+    let matched = crosses.evaluate(&[(day(0), 0.58), (day(1), 0.61)], &[]);
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0].dedup_key, day(1).to_string());
+    assert_eq!(matched[0].rule_id, crosses.id);
+    assert!(matched[0].subject.contains("crossed above 0.6"));
+    assert_eq!(crosses.evaluate(&[(day(0), 0.61), (day(1), 0.55)], &[]).len(), 1);
+
+    let above_only = rule("BTC_DOMINANCE crosses above 0.6");
+    assert!(above_only.evaluate(&[(day(0), 0.61), (day(1), 0.55)], &[]).is_empty());
+    assert!(above_only.evaluate(&[(day(1), 0.65)], &[]).is_empty());
+}
+
+#[test]
+fn test_beyond_holds_for_the_latest_observation() {
+    let fear = rule("FEAR_GREED_INDEX below 15");
+    assert!(fear.evaluate(&[(day(0), 10.0), (day(1), 15.0)], &[]).is_empty());
+    assert_eq!(fear.evaluate(&[(day(1), 14.0)], &[])[0].dedup_key, day(1).to_string());
+    assert!(fear.evaluate(&[], &[]).is_empty());
+}
+
+#[test]
+fn test_new_signal_filters_and_ignores_signals_before_the_rule() {
+    let signals = [
+        signal(-1, "BTC", "MAYER_MULTIPLE_BUY", "longterm"),
+        signal(0, "BTC", "MAYER_MULTIPLE_BUY", "longterm"),
+        signal(0, "ETH", "BTC_DOMINANCE_TREND_SELL", "longterm"),
+        signal(1, "USDC", "YIELD_AAVE_LENDING_ABOVE", "yields"),
+    ];
+
+    let buys = rule("new BUY signal from longterm").evaluate(&[], &signals);
+    assert_eq!(buys.len(), 1);
+    assert_eq!(buys[0].dedup_key, signals[1].id.to_string());
+
+    assert_eq!(rule("new signal").evaluate(&[], &signals).len(), 3);
+    assert_eq!(rule("new signal for eth").evaluate(&[], &signals).len(), 1);
+    assert!(rule("new SELL signal from yields").evaluate(&[], &signals).is_empty());
+}
+
+#[test]
+fn test_cooldown_and_final_statuses() {
+    let rule = rule("FEAR_GREED_INDEX below 15");
+    assert!(!rule.cooling_down(None, noon(1)));
+    assert!(rule.cooling_down(Some(noon(1) - Duration::minutes(59)), noon(1)));
+    assert!(!rule.cooling_down(Some(noon(1) - Duration::minutes(60)), noon(1)));
+
+    assert!(DeliveryStatus::Sent.is_final() && DeliveryStatus::Suppressed.is_final());
+    assert!(!DeliveryStatus::Failed.is_final());
+    assert_eq!("suppressed".parse::<DeliveryStatus>().unwrap(), DeliveryStatus::Suppressed);
+}
@@ -0,0 +1,21 @@
+use crate::{hash_api_key, GeneratedApiKey, API_KEY_PREFIX};
+
+#[test]
+fn test_generated_key_matches_its_hash() {
+    let generated = GeneratedApiKey::generate();
+
+    assert!(generated.key.starts_with(API_KEY_PREFIX));
+    assert_eq!(generated.key.len(), API_KEY_PREFIX.len() + 64);
+    assert!(generated.key.starts_with(&generated.prefix));
+    assert_eq!(hash_api_key(&generated.key), generated.hash);
+    assert_ne!(generated.hash, generated.key);
+}
+
+#[test]
+fn test_generated_keys_are_unique() {
+    let first = GeneratedApiKey::generate();
+    let second = GeneratedApiKey::generate();
+
+    assert_ne!(first.key, second.key);
+    assert_ne!(first.hash, second.hash);
+}
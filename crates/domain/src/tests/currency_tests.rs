@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::portfolio::tests::fixtures::tx;
+use crate::{
+    rebalance, simulate_dca, AllocationBucket, AllocationTarget, ConversionError, CostBasisMethod, Currency,
+    CurrencyConverter, DcaFrequency, DcaMethod, DcaPlan, Jurisdiction, LotBook, MarketDataSet, NavPoint, PortfolioState,
+    TaxReport, TransactionKind,
+};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+}
+
+#[test]
+fn test_usd_converter_is_identity() {
+    let converter = CurrencyConverter::usd();
+
+    assert_eq!(converter.currency(), Currency::Usd);
+    assert_eq!(converter.convert(123.45, day(1)).unwrap(), 123.45);
+}
+
+#[test]
+fn test_eur_uses_rate_of_the_day_and_forward_fills_weekends() {
+    // DEXUSEU quotes USD per EUR.
+    let converter = CurrencyConverter::new(Currency::Eur, vec![(day(5), 1.25), (day(8), 1.5)]);
+
+    assert_eq!(converter.convert(125.0, day(5)).unwrap(), 100.0);
+    assert_eq!(converter.convert(125.0, day(7)).unwrap(), 100.0);
+    assert_eq!(converter.convert(150.0, day(8)).unwrap(), 100.0);
+    assert_eq!(
+        converter.convert(1.0, day(4)),
+        Err(ConversionError::MissingRate { currency: Currency::Eur, date: day(4) })
+    );
+}
+
+#[test]
+fn test_convert_nav_converts_value_and_flows_per_day() {
+    let converter = CurrencyConverter::new(Currency::Eur, vec![(day(1), 1.0), (day(2), 2.0)]);
+    let nav = vec![
+        NavPoint { date: day(1), nav: 100.0, net_flow: 100.0, forward_filled: false },
+        NavPoint { date: day(2), nav: 100.0, net_flow: 0.0, forward_filled: true },
+    ];
+
+    let converted = converter.convert_nav(&nav).unwrap();
+
+    assert_eq!(converted[0].nav, 100.0);
+    assert_eq!(converted[0].net_flow, 100.0);
+    assert_eq!(converted[1].nav, 50.0);
+    assert!(converted[1].forward_filled);
+}
+
+#[test]
+fn test_parse_currency() {
+    assert_eq!("eur".parse::<Currency>().unwrap(), Currency::Eur);
+    assert_eq!(" USD ".parse::<Currency>().unwrap(), Currency::Usd);
+    assert!("GBP".parse::<Currency>().is_err());
+}
+
+#[test]
+fn test_tax_report_converts_proceeds_and_cost_at_their_own_dates() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2024, 1, 2), 1.0, Some(40_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2024, 1, 9), 1.0, Some(40_000.0)),
+    ];
+    let converter = CurrencyConverter::new(Currency::Eur, vec![(day(1), 1.0), (day(8), 1.25)]);
+
+    let report = TaxReport::build(Uuid::new_v4(), 2024, CostBasisMethod::Fifo, Jurisdiction::De, &txs)
+        .unwrap()
+        .in_currency(&converter)
+        .unwrap();
+
+    assert_eq!(report.currency, Currency::Eur);
+    assert_eq!(report.total_cost_basis, 40_000.0);
+    assert_eq!(report.total_proceeds, 32_000.0);
+    assert_eq!(report.total_gain, -8_000.0);
+    assert!(report.to_csv().starts_with("asset,quantity,acquired_on,disposed_on,proceeds_eur,"));
+}
+
+#[test]
+fn test_pnl_converts_lots_at_their_acquisition_date() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2024, 1, 2), 1.0, Some(40_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2024, 1, 9), 0.5, Some(50_000.0)),
+    ];
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+    let prices = BTreeMap::from([("BTC".to_string(), 60_000.0)]);
+    let converter = CurrencyConverter::new(Currency::Eur, vec![(day(1), 1.0), (day(8), 1.25)]);
+
+    let pnl = book.pnl_in_currency(&prices, &converter, day(9)).unwrap();
+
+    assert_eq!(pnl[0].cost_basis, 20_000.0);
+    // EUR 20,000 of proceeds against EUR 20,000 of cost: the USD gain was eaten by the FX move.
+    assert_eq!(pnl[0].realized_pnl, 0.0);
+    assert_eq!(pnl[0].price, Some(48_000.0));
+    assert_eq!(pnl[0].market_value, Some(24_000.0));
+    assert_eq!(pnl[0].unrealized_pnl, Some(4_000.0));
+}
+
+#[test]
+fn test_rebalance_plan_converts_at_its_date() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Deposit, "USD", (2024, 1, 1), 10_000.0, None),
+        tx(account, TransactionKind::Buy, "BTC", (2024, 1, 2), 0.2, Some(40_000.0)),
+    ];
+    let holdings = PortfolioState::from_transactions(&txs).unwrap().holdings();
+    let book = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap();
+    let targets: Vec<AllocationTarget> = ["BTC", "USD"]
+        .iter()
+        .map(|bucket| AllocationTarget { bucket: AllocationBucket::from_str(bucket).unwrap(), weight: 0.5, band: 0.05 })
+        .collect();
+    let prices = BTreeMap::from([("BTC".to_string(), 60_000.0)]);
+    let converter = CurrencyConverter::new(Currency::Eur, vec![(day(1), 1.0), (day(8), 1.25)]);
+
+    let usd = rebalance(&holdings, &prices, &book, &targets, Jurisdiction::Us, day(9)).unwrap();
+    let eur = usd.in_currency(&converter).unwrap();
+
+    assert_eq!(eur.currency, Currency::Eur);
+    assert_eq!(eur.total_value, usd.total_value / 1.25);
+    assert_eq!(eur.trades[0].price, 48_000.0);
+    assert_eq!(eur.trades[0].lots[0].unit_cost, 32_000.0);
+    assert_eq!(eur.estimated_gain, usd.estimated_gain / 1.25);
+}
+
+#[test]
+fn test_converted_amounts_serialize_without_a_usd_suffix() {
+    let account = Uuid::new_v4();
+    let txs = vec![
+        tx(account, TransactionKind::Buy, "BTC", (2024, 1, 2), 1.0, Some(40_000.0)),
+        tx(account, TransactionKind::Sell, "BTC", (2024, 1, 9), 1.0, Some(40_000.0)),
+    ];
+    let report = TaxReport::build(Uuid::new_v4(), 2024, CostBasisMethod::Fifo, Jurisdiction::Us, &txs).unwrap();
+    let nav = NavPoint { date: day(1), nav: 1.0, net_flow: 0.0, forward_filled: false };
+    let pnl = LotBook::from_transactions(&txs, CostBasisMethod::Fifo).unwrap().pnl(&BTreeMap::new());
+
+    for json in [
+        serde_json::to_string(&report).unwrap(),
+        serde_json::to_string(&nav).unwrap(),
+        serde_json::to_string(&pnl).unwrap(),
+    ] {
+        assert!(!json.contains("_usd"), "{json}");
+    }
+    assert!(serde_json::to_string(&report).unwrap().contains("\"total_gain\":0.0"));
+}
+
+#[test]
+fn test_dca_replays_against_converted_closes() {
+    let converter = CurrencyConverter::new(Currency::Eur, vec![(day(1), 1.25), (day(3), 1.0)]);
+    let closes = vec![(day(1), 50_000.0), (day(2), 50_000.0), (day(3), 40_000.0)];
+    let mut data = MarketDataSet::default();
+    data.prices.insert("BTC".to_string(), converter.convert_series(&closes).unwrap());
+    let plan = DcaPlan {
+        asset: "BTC".to_string(),
+        amount: 100.0,
+        frequency: DcaFrequency::Daily,
+        start: day(1),
+        method: DcaMethod::Fixed,
+    };
+
+    let simulation = simulate_dca(&plan, &data, day(3));
+
+    // EUR 100 a day at EUR 40,000, 40,000 and 40,000.
+    assert_eq!(simulation.invested, 300.0);
+    assert!((simulation.quantity - 300.0 / 40_000.0).abs() < 1e-12);
+    assert!((simulation.final_value - 300.0).abs() < 1e-9);
+    let json = serde_json::to_string(&simulation).unwrap();
+    assert!(!json.contains("_usd"), "{json}");
+    assert!(json.contains("\"invested\":300.0"));
+}
@@ -0,0 +1,8 @@
+#[cfg(test)]
+pub mod alerts_tests;
+#[cfg(test)]
+pub mod api_key_tests;
+#[cfg(test)]
+pub mod currency_tests;
+#[cfg(test)]
+pub mod yields_tests;
@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+
+use crate::{threshold_crossing, CrossingDirection, YieldBenchmark, YieldKind, YieldObservation, YieldQuote};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 6, d).unwrap()
+}
+
+fn quote(apy: f64, date: NaiveDate) -> YieldQuote {
+    YieldQuote {
+        source: "fixture".to_string(),
+        protocol: "aave-v3".to_string(),
+        asset_symbol: "USDC".to_string(),
+        kind: YieldKind::Lending,
+        date,
+        apy,
+        tvl_usd: None,
+    }
+}
+
+#[test]
+fn test_spread_uses_risk_adjusted_apy_and_forward_filled_rates() {
+    let dff = vec![(day(7), 5.0)];
+    let dgs2 = vec![(day(10), 4.0)];
+
+    // Saturday quote against Friday's DFF; no DGS2 yet.
+    let observation = YieldObservation::new(quote(10.0, day(8)), 0.2, &dff, &dgs2);
+
+    assert_eq!(observation.risk_adjusted_apy, 8.0);
+    assert_eq!(observation.spread_over(YieldBenchmark::Dff), Some(3.0));
+    assert_eq!(observation.spread_over(YieldBenchmark::Dgs2), None);
+}
+
+#[test]
+fn test_threshold_crossing_reports_the_furthest_level() {
+    let levels = [0.0, 2.0, 5.0];
+
+    assert_eq!(threshold_crossing(-1.0, 2.5, &levels), Some((CrossingDirection::Above, 2.0)));
+    assert_eq!(threshold_crossing(1.0, 2.0, &levels), Some((CrossingDirection::Above, 2.0)));
+    assert_eq!(threshold_crossing(6.0, -0.5, &levels), Some((CrossingDirection::Below, 0.0)));
+    assert_eq!(threshold_crossing(2.5, 3.0, &levels), None);
+    assert_eq!(threshold_crossing(2.0, 2.0, &levels), None);
+}
+
+#[test]
+fn test_parse_yield_kind() {
+    assert_eq!("supply".parse::<YieldKind>().unwrap(), YieldKind::Lending);
+    assert_eq!(" STAKING ".parse::<YieldKind>().unwrap(), YieldKind::Staking);
+    assert!("farming".parse::<YieldKind>().is_err());
+}
@@ -0,0 +1,56 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Every issued key starts with this, so leaked keys are easy to grep for.
+pub const API_KEY_PREFIX: &str = "cpd_";
+
+/// Characters of a key that are stored in clear to tell keys apart in listings.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Someone who owns portfolios and calls the API with their own keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// A stored API key. The key itself is never stored, only its hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub prefix: String,
+    pub label: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+/// A freshly generated key. `key` is shown to the user once; only `hash` and `prefix` are kept.
+#[derive(Debug, Clone)]
+pub struct GeneratedApiKey {
+    pub key: String,
+    pub prefix: String,
+    pub hash: String,
+}
+
+impl GeneratedApiKey {
+    /// 64 hex characters from two random v4 UUIDs (244 random bits) behind [`API_KEY_PREFIX`].
+    pub fn generate() -> Self {
+        let key = format!("{}{}{}", API_KEY_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        Self {
+            prefix: key[..DISPLAY_PREFIX_LEN].to_string(),
+            hash: hash_api_key(&key),
+            key,
+        }
+    }
+}
+
+/// Hex SHA-256 of a key. Keys are long random strings, so a fast unsalted hash is enough to
+/// make a leaked `api_keys` table useless while keeping the lookup a single indexed equality.
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.trim().as_bytes()))
+}
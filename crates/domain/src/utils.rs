@@ -6,17 +6,50 @@ pub fn current_timestamp_ms() -> u128 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()
 }
 
+/// Known spellings of the same asset across data providers and exchanges, matched case-insensitively.
+const SYMBOL_ALIASES: &[(&str, &str)] = &[
+    // CoinGecko coin ids
+    ("bitcoin", "BTC"),
+    ("ethereum", "ETH"),
+    ("sui", "SUI"),
+    ("solana", "SOL"),
+    // Kraken asset codes
+    ("XBT", "BTC"),
+    ("XXBT", "BTC"),
+    ("XETH", "ETH"),
+    ("XXRP", "XRP"),
+    ("XLTC", "LTC"),
+    ("XXDG", "DOGE"),
+    ("XDG", "DOGE"),
+    ("ZUSD", "USD"),
+    ("ZEUR", "EUR"),
+    ("ZGBP", "GBP"),
+    // Staked / wrapped variants held at the same price
+    ("ETH2", "ETH"),
+    ("ETH2.S", "ETH"),
+    ("WETH", "ETH"),
+    ("WBTC", "BTC"),
+];
+
+/// USD-pegged stablecoins whose quotes are treated as USD prices.
+const USD_STABLECOINS: &[&str] = &["USDT", "USDC", "BUSD", "DAI", "TUSD", "FDUSD"];
+
 pub fn normalize_symbol(coin_id: &str) -> String {
-    let s: &str = match coin_id {
-        "bitcoin" => "BTC",
-        "ethereum" => "ETH",
-        "sui" => "SUI",
-        "solana" => "SOL",
-        _ => coin_id,
-    };
+    let trimmed = coin_id.trim();
+    let s: &str = SYMBOL_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(trimmed))
+        .map(|(_, symbol)| *symbol)
+        .unwrap_or(trimmed);
     s.to_uppercase()
 }
 
+/// True for `USD` itself and for the stablecoins pegged to it (after normalization).
+pub fn is_usd_quote(symbol: &str) -> bool {
+    let normalized = normalize_symbol(symbol);
+    normalized == "USD" || USD_STABLECOINS.contains(&normalized.as_str())
+}
+
 pub fn chrono_to_offset(dt: DateTime<Utc>) -> OffsetDateTime {
     OffsetDateTime::from_unix_timestamp(dt.timestamp())
         .expect("Invalid timestamp conversion")
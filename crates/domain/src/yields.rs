@@ -0,0 +1,147 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::portfolio::nav::value_as_of;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum YieldKind {
+    Lending,
+    Staking,
+}
+
+impl YieldKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            YieldKind::Lending => "LENDING",
+            YieldKind::Staking => "STAKING",
+        }
+    }
+}
+
+impl FromStr for YieldKind {
+    type Err = ParseYieldKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "LENDING" | "LEND" | "SUPPLY" => Ok(YieldKind::Lending),
+            "STAKING" | "STAKE" => Ok(YieldKind::Staking),
+            other => Err(ParseYieldKindError(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseYieldKindError(pub String);
+
+impl fmt::Display for ParseYieldKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid yield kind: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseYieldKindError {}
+
+/// One APY as reported by a source. `apy` is in percent, like the FRED rates it is compared to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YieldQuote {
+    pub source: String,
+    pub protocol: String,
+    pub asset_symbol: String,
+    pub kind: YieldKind,
+    pub date: NaiveDate,
+    pub apy: f64,
+    pub tvl_usd: Option<f64>,
+}
+
+/// A quote after normalization: the APY left after the source's risk discount and its spread,
+/// in percentage points, over the risk-free rates of the same day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YieldObservation {
+    pub quote: YieldQuote,
+    pub risk_adjusted_apy: f64,
+    pub spread_over_dff: Option<f64>,
+    pub spread_over_dgs2: Option<f64>,
+}
+
+impl YieldObservation {
+    /// `risk_discount` is the share of the APY given up for smart-contract, custody and
+    /// depeg risk (`0.2` keeps 80% of it). Rates are forward-filled over weekends and holidays.
+    pub fn new(quote: YieldQuote, risk_discount: f64, dff: &[(NaiveDate, f64)], dgs2: &[(NaiveDate, f64)]) -> Self {
+        let risk_adjusted_apy = quote.apy * (1.0 - risk_discount.clamp(0.0, 1.0));
+        let spread = |rates: &[(NaiveDate, f64)]| value_as_of(rates, quote.date).map(|(_, rate)| risk_adjusted_apy - rate);
+
+        Self {
+            spread_over_dff: spread(dff),
+            spread_over_dgs2: spread(dgs2),
+            risk_adjusted_apy,
+            quote,
+        }
+    }
+
+    pub fn spread_over(&self, benchmark: YieldBenchmark) -> Option<f64> {
+        match benchmark {
+            YieldBenchmark::Dff => self.spread_over_dff,
+            YieldBenchmark::Dgs2 => self.spread_over_dgs2,
+        }
+    }
+}
+
+/// Risk-free rate a yield has to beat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum YieldBenchmark {
+    /// Federal funds rate, what idle dollars earn overnight.
+    #[default]
+    Dff,
+    /// 2-year Treasury, what locking dollars up for a while earns.
+    Dgs2,
+}
+
+impl YieldBenchmark {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            YieldBenchmark::Dff => "DFF",
+            YieldBenchmark::Dgs2 => "DGS2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CrossingDirection {
+    Above,
+    Below,
+}
+
+impl CrossingDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrossingDirection::Above => "ABOVE",
+            CrossingDirection::Below => "BELOW",
+        }
+    }
+}
+
+/// The level a spread crossed between two observations. When a move crosses several levels the
+/// furthest one is reported. Landing exactly on a level counts as reaching it.
+pub fn threshold_crossing(previous: f64, current: f64, levels: &[f64]) -> Option<(CrossingDirection, f64)> {
+    if current > previous {
+        levels
+            .iter()
+            .copied()
+            .filter(|level| previous < *level && current >= *level)
+            .max_by(f64::total_cmp)
+            .map(|level| (CrossingDirection::Above, level))
+    } else {
+        levels
+            .iter()
+            .copied()
+            .filter(|level| previous >= *level && current < *level)
+            .min_by(f64::total_cmp)
+            .map(|level| (CrossingDirection::Below, level))
+    }
+}
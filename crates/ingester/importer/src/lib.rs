@@ -0,0 +1,7 @@
+pub mod parsers;
+pub mod report;
+
+mod tests;
+
+pub use parsers::{ImportFormat, TradeHistoryParser, import};
+pub use report::{ImportReport, RowError};
@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use domain::{TransactionKind, is_usd_quote, normalize_symbol};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::parsers::{FieldError, RowIds, TradeHistoryParser, for_each_row, new_tx, parse_amount, parse_timestamp, push_quote_leg, push_validated, split_amount_asset};
+use crate::report::ImportReport;
+
+/// Binance "Trade History" export:
+/// `Date(UTC),Pair,Side,Price,Executed,Amount,Fee`
+/// where `Executed`, `Amount` and `Fee` carry their asset as a suffix (`0.5BTC`).
+pub struct BinanceParser;
+
+#[derive(Debug, Deserialize)]
+struct BinanceTradeRow {
+    #[serde(rename = "Date(UTC)")]
+    date: String,
+    #[serde(rename = "Pair")]
+    pair: String,
+    #[serde(rename = "Side")]
+    side: String,
+    #[serde(rename = "Price")]
+    price: String,
+    #[serde(rename = "Executed")]
+    executed: String,
+    #[serde(rename = "Amount")]
+    amount: String,
+    #[serde(rename = "Fee")]
+    fee: String,
+}
+
+impl TradeHistoryParser for BinanceParser {
+    fn source(&self) -> &'static str {
+        "binance"
+    }
+
+    fn parse(&self, account_id: Uuid, data: &str) -> ImportReport {
+        let mut report = ImportReport::new(self.source());
+        let mut ids = RowIds::new(self.source());
+
+        for_each_row(data, &[], &mut report, |line, record, row: BinanceTradeRow, report| {
+            let trade = match parse_trade(&row) {
+                Ok(trade) => trade,
+                Err((field, message)) => {
+                    report.error(line, Some(field), message);
+                    return;
+                }
+            };
+
+            let external_id = ids.hashed(record);
+            let fee_asset = normalize_symbol(&trade.fee_asset);
+            let fee_in_quote = fee_asset == trade.quote;
+
+            let accepted = push_validated(report, line, new_tx(
+                account_id,
+                trade.timestamp,
+                trade.kind,
+                trade.asset.clone(),
+                trade.quantity,
+                Some(trade.price),
+                fee_in_quote.then_some(trade.fee),
+                external_id.clone(),
+            ));
+            if !accepted {
+                return;
+            }
+
+            let quote_fee = if fee_in_quote { trade.fee } else { 0.0 };
+            let quote_amount = match trade.kind {
+                TransactionKind::Buy => -(trade.notional + quote_fee),
+                _ => trade.notional - quote_fee,
+            };
+            push_quote_leg(report, line, account_id, trade.timestamp, &trade.quote, quote_amount, &external_id);
+
+            // Fees charged in the traded coin, in BNB or in another stablecoin reduce that
+            // coin's balance.
+            if !fee_in_quote && trade.fee > 0.0 {
+                let fee_price = if fee_asset == trade.asset {
+                    Some(trade.price)
+                } else {
+                    is_usd_quote(&fee_asset).then_some(1.0)
+                };
+                push_validated(report, line, new_tx(
+                    account_id,
+                    trade.timestamp,
+                    TransactionKind::Fee,
+                    fee_asset,
+                    trade.fee,
+                    fee_price,
+                    None,
+                    format!("{external_id}:fee"),
+                ));
+            }
+        });
+
+        report
+    }
+}
+
+struct BinanceTrade {
+    timestamp: NaiveDateTime,
+    kind: TransactionKind,
+    asset: String,
+    quantity: f64,
+    price: f64,
+    /// Stablecoin or USD the trade is quoted in, and the amount of it traded before fees.
+    quote: String,
+    notional: f64,
+    fee: f64,
+    fee_asset: String,
+}
+
+fn parse_trade(row: &BinanceTradeRow) -> Result<BinanceTrade, FieldError> {
+    let timestamp = parse_timestamp(&row.date).map_err(|e| ("Date(UTC)", e))?;
+    let price = parse_amount(&row.price).map_err(|e| ("Price", e))?;
+    let (quantity, base) = split_amount_asset(&row.executed).map_err(|e| ("Executed", e))?;
+    let (notional, quote) = split_amount_asset(&row.amount).map_err(|e| ("Amount", e))?;
+    let (fee, fee_asset) = split_amount_asset(&row.fee).map_err(|e| ("Fee", e))?;
+
+    let kind = match row.side.trim().to_uppercase().as_str() {
+        "BUY" => TransactionKind::Buy,
+        "SELL" => TransactionKind::Sell,
+        other => return Err(("Side", format!("Unknown side: {other}"))),
+    };
+    if !is_usd_quote(&quote) {
+        return Err(("Pair", format!("Pair {} is not quoted in USD or a USD stablecoin", row.pair)));
+    }
+
+    Ok(BinanceTrade {
+        timestamp,
+        kind,
+        asset: normalize_symbol(&base),
+        quantity,
+        price,
+        quote: normalize_symbol(&quote),
+        notional,
+        fee,
+        fee_asset,
+    })
+}
@@ -0,0 +1,120 @@
+use chrono::NaiveDateTime;
+use domain::{TransactionKind, is_usd_quote, normalize_symbol};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::parsers::{FieldError, RowIds, TradeHistoryParser, for_each_row, new_tx, parse_amount, parse_timestamp, push_validated};
+use crate::report::ImportReport;
+
+/// Coinbase "Transaction history" export. The file starts with a few lines of text before
+/// the header; both the current layout (with an `ID` column and `Price at Transaction`) and
+/// the older `Spot Price at Transaction` layout are accepted.
+pub struct CoinbaseParser;
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseRow {
+    #[serde(rename = "ID", default)]
+    id: Option<String>,
+    #[serde(rename = "Timestamp")]
+    timestamp: String,
+    #[serde(rename = "Transaction Type")]
+    kind: String,
+    #[serde(rename = "Asset")]
+    asset: String,
+    #[serde(rename = "Quantity Transacted")]
+    quantity: String,
+    #[serde(rename = "Price Currency", alias = "Spot Price Currency")]
+    price_currency: String,
+    #[serde(rename = "Price at Transaction", alias = "Spot Price at Transaction")]
+    price: String,
+    #[serde(rename = "Fees and/or Spread", default)]
+    fees: Option<String>,
+}
+
+impl TradeHistoryParser for CoinbaseParser {
+    fn source(&self) -> &'static str {
+        "coinbase"
+    }
+
+    fn parse(&self, account_id: Uuid, data: &str) -> ImportReport {
+        let mut report = ImportReport::new(self.source());
+        let mut ids = RowIds::new(self.source());
+
+        for_each_row(data, &["ID,", "Timestamp,"], &mut report, |line, record, row: CoinbaseRow, report| {
+            let parsed = match parse_row(&row) {
+                Ok(parsed) => parsed,
+                Err((field, message)) => {
+                    report.error(line, Some(field), message);
+                    return;
+                }
+            };
+
+            let external_id = match row.id.as_deref().filter(|id| !id.is_empty()) {
+                Some(id) => ids.explicit(id),
+                None => ids.hashed(record),
+            };
+
+            push_validated(report, line, new_tx(
+                account_id,
+                parsed.timestamp,
+                parsed.kind,
+                parsed.asset,
+                parsed.quantity,
+                parsed.price,
+                parsed.fee,
+                external_id,
+            ));
+        });
+
+        report
+    }
+}
+
+struct CoinbaseTransaction {
+    timestamp: NaiveDateTime,
+    kind: TransactionKind,
+    asset: String,
+    quantity: f64,
+    price: Option<f64>,
+    fee: Option<f64>,
+}
+
+fn parse_row(row: &CoinbaseRow) -> Result<CoinbaseTransaction, FieldError> {
+    let kind = match row.kind.trim().to_lowercase().as_str() {
+        "buy" | "advanced trade buy" | "advance trade buy" => TransactionKind::Buy,
+        "sell" | "advanced trade sell" | "advance trade sell" => TransactionKind::Sell,
+        "receive" | "deposit" => TransactionKind::Deposit,
+        "send" | "withdrawal" => TransactionKind::Withdrawal,
+        "staking income" | "inflation reward" => TransactionKind::StakingReward,
+        "interest" | "lending interest" => TransactionKind::LendingInterest,
+        "learning reward" | "coinbase earn" | "airdrop" => TransactionKind::Airdrop,
+        "convert" => return Err(("Transaction Type", "Conversions are not supported, enter them as a sell and a buy".to_string())),
+        other => return Err(("Transaction Type", format!("Unsupported transaction type: {other}"))),
+    };
+
+    let timestamp = parse_timestamp(&row.timestamp).map_err(|e| ("Timestamp", e))?;
+    let quantity = parse_amount(&row.quantity).map_err(|e| ("Quantity Transacted", e))?.abs();
+    let asset = normalize_symbol(&row.asset);
+
+    let price = if row.price.trim().is_empty() {
+        None
+    } else if is_usd_quote(&row.price_currency) {
+        Some(parse_amount(&row.price).map_err(|e| ("Price at Transaction", e))?)
+    } else {
+        return Err(("Price Currency", format!("Prices in {} are not supported", row.price_currency)));
+    };
+
+    let fee = match row.fees.as_deref().map(str::trim) {
+        Some(fee) if !fee.is_empty() => Some(parse_amount(fee).map_err(|e| ("Fees and/or Spread", e))?),
+        _ => None,
+    };
+
+    Ok(CoinbaseTransaction {
+        timestamp,
+        kind,
+        asset,
+        quantity,
+        price,
+        fee,
+    })
+}
@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use domain::{LedgerTransaction, TransactionKind, normalize_symbol};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::parsers::{RowIds, TradeHistoryParser, for_each_row, parse_timestamp, push_validated};
+use crate::report::ImportReport;
+
+/// Template for any source without a dedicated parser:
+///
+/// ```text
+/// timestamp,kind,asset,quantity,price_usd,fee_usd,counterparty_account_id,external_id,note
+/// 2024-03-01 10:00:00,BUY,BTC,0.25,62000,4.5,,,first buy
+/// ```
+///
+/// `kind` is one of the ledger transaction kinds. Only `timestamp`, `kind`, `asset` and
+/// `quantity` are required; rows without `external_id` are identified by their content.
+pub struct GenericParser;
+
+#[derive(Debug, Deserialize)]
+struct GenericRow {
+    timestamp: String,
+    kind: String,
+    asset: String,
+    quantity: f64,
+    #[serde(default)]
+    price_usd: Option<f64>,
+    #[serde(default)]
+    fee_usd: Option<f64>,
+    #[serde(default)]
+    counterparty_account_id: Option<Uuid>,
+    #[serde(default)]
+    external_id: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+impl TradeHistoryParser for GenericParser {
+    fn source(&self) -> &'static str {
+        "generic"
+    }
+
+    fn parse(&self, account_id: Uuid, data: &str) -> ImportReport {
+        let mut report = ImportReport::new(self.source());
+        let mut ids = RowIds::new(self.source());
+
+        for_each_row(data, &[], &mut report, |line, record, row: GenericRow, report| {
+            let timestamp = match parse_timestamp(&row.timestamp) {
+                Ok(timestamp) => timestamp,
+                Err(e) => {
+                    report.error(line, Some("timestamp"), e);
+                    return;
+                }
+            };
+            let kind = match TransactionKind::from_str(&row.kind) {
+                Ok(kind) => kind,
+                Err(e) => {
+                    report.error(line, Some("kind"), e.to_string());
+                    return;
+                }
+            };
+            let external_id = match row.external_id.as_deref().filter(|id| !id.is_empty()) {
+                Some(id) => ids.explicit(id),
+                None => ids.hashed(record),
+            };
+
+            push_validated(report, line, LedgerTransaction {
+                id: Uuid::new_v4(),
+                account_id,
+                timestamp,
+                kind,
+                asset_symbol: normalize_symbol(&row.asset),
+                quantity: row.quantity,
+                price_usd: row.price_usd,
+                fee_usd: row.fee_usd,
+                counterparty_account_id: row.counterparty_account_id,
+                note: row.note.filter(|n| !n.is_empty()),
+                external_id: Some(external_id),
+            });
+        });
+
+        report
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use domain::{CASH_ASSET, TransactionKind, is_usd_quote, normalize_symbol};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::parsers::{RowIds, TradeHistoryParser, for_each_row, new_tx, parse_timestamp, push_quote_leg, push_validated};
+use crate::report::ImportReport;
+
+/// Kraken `ledgers.csv` export:
+/// `txid,refid,time,type,subtype,aclass,asset,amount,fee,balance`.
+///
+/// A trade is exported as one row per asset sharing a `refid`; the legs are paired back
+/// into a single buy or sell priced by the USD or stablecoin leg.
+pub struct KrakenParser;
+
+#[derive(Debug, Clone, Deserialize)]
+struct KrakenLedgerRow {
+    txid: String,
+    refid: String,
+    time: String,
+    #[serde(rename = "type")]
+    kind: String,
+    asset: String,
+    amount: f64,
+    fee: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Leg {
+    line: u64,
+    timestamp: NaiveDateTime,
+    asset: String,
+    amount: f64,
+    fee: f64,
+}
+
+impl TradeHistoryParser for KrakenParser {
+    fn source(&self) -> &'static str {
+        "kraken"
+    }
+
+    fn parse(&self, account_id: Uuid, data: &str) -> ImportReport {
+        let mut report = ImportReport::new(self.source());
+        let ids = RowIds::new(self.source());
+        let mut trades: BTreeMap<String, Vec<Leg>> = BTreeMap::new();
+
+        for_each_row(data, &[], &mut report, |line, _, row: KrakenLedgerRow, report| {
+            // Kraken repeats pending movements without a txid; the settled row follows.
+            if row.txid.is_empty() {
+                report.rows_skipped += 1;
+                return;
+            }
+
+            let timestamp = match parse_timestamp(&row.time) {
+                Ok(timestamp) => timestamp,
+                Err(e) => {
+                    report.error(line, Some("time"), e);
+                    return;
+                }
+            };
+            let leg = Leg {
+                line,
+                timestamp,
+                asset: normalize_symbol(&row.asset),
+                amount: row.amount,
+                fee: row.fee,
+            };
+
+            match row.kind.trim().to_lowercase().as_str() {
+                "trade" | "spend" | "receive" => {
+                    trades.entry(row.refid.clone()).or_default().push(leg);
+                }
+                "deposit" | "withdrawal" => {
+                    let kind = if leg.amount >= 0.0 { TransactionKind::Deposit } else { TransactionKind::Withdrawal };
+                    push_movement(report, account_id, &leg, kind, ids.explicit(&row.txid));
+                }
+                "staking" if leg.amount > 0.0 => {
+                    push_movement(report, account_id, &leg, TransactionKind::StakingReward, ids.explicit(&row.txid));
+                }
+                "airdrop" if leg.amount > 0.0 => {
+                    push_movement(report, account_id, &leg, TransactionKind::Airdrop, ids.explicit(&row.txid));
+                }
+                // Moves between Kraken's spot and staking wallets do not change holdings.
+                "transfer" => report.rows_skipped += 1,
+                other => report.error(line, Some("type"), format!("Unsupported ledger type: {other}")),
+            }
+        });
+
+        for (refid, legs) in trades {
+            push_trade(&mut report, account_id, &refid, &legs, &ids);
+        }
+        report.transactions.sort_by_key(|tx| tx.timestamp);
+
+        report
+    }
+}
+
+fn push_movement(report: &mut ImportReport, account_id: Uuid, leg: &Leg, kind: TransactionKind, external_id: String) {
+    // Only USD fees come out of cash; stablecoin fees reduce the stablecoin.
+    let cash_fee = leg.asset == CASH_ASSET;
+    push_validated(report, leg.line, new_tx(
+        account_id,
+        leg.timestamp,
+        kind,
+        leg.asset.clone(),
+        leg.amount.abs(),
+        None,
+        (cash_fee && leg.fee > 0.0).then_some(leg.fee),
+        external_id.clone(),
+    ));
+
+    if !cash_fee && leg.fee > 0.0 {
+        push_validated(report, leg.line, new_tx(
+            account_id,
+            leg.timestamp,
+            TransactionKind::Fee,
+            leg.asset.clone(),
+            leg.fee,
+            is_usd_quote(&leg.asset).then_some(1.0),
+            None,
+            format!("{external_id}:fee"),
+        ));
+    }
+}
+
+fn push_trade(report: &mut ImportReport, account_id: Uuid, refid: &str, legs: &[Leg], ids: &RowIds) {
+    let line = legs.first().map(|l| l.line).unwrap_or(0);
+
+    let (quote, coin) = match legs {
+        [a, b] if is_usd_quote(&a.asset) && !is_usd_quote(&b.asset) => (a, b),
+        [a, b] if is_usd_quote(&b.asset) && !is_usd_quote(&a.asset) => (b, a),
+        [_, _] => {
+            report.error(line, Some("asset"), format!("Trade {refid} has no USD leg; only USD-quoted trades are supported"));
+            return;
+        }
+        _ => {
+            report.error(line, Some("refid"), format!("Trade {refid} has {} legs, expected 2", legs.len()));
+            return;
+        }
+    };
+
+    let quantity = coin.amount.abs();
+    if quantity == 0.0 {
+        report.error(coin.line, Some("amount"), format!("Trade {refid} has a zero amount"));
+        return;
+    }
+    let kind = if coin.amount > 0.0 { TransactionKind::Buy } else { TransactionKind::Sell };
+    let price = quote.amount.abs() / quantity;
+    let external_id = ids.explicit(refid);
+
+    let accepted = push_validated(report, line, new_tx(
+        account_id,
+        coin.timestamp,
+        kind,
+        coin.asset.clone(),
+        quantity,
+        Some(price),
+        (quote.fee > 0.0).then_some(quote.fee),
+        external_id.clone(),
+    ));
+    if !accepted {
+        return;
+    }
+    push_quote_leg(report, quote.line, account_id, coin.timestamp, &quote.asset, quote.amount - quote.fee, &external_id);
+
+    if coin.fee > 0.0 {
+        push_validated(report, coin.line, new_tx(
+            account_id,
+            coin.timestamp,
+            TransactionKind::Fee,
+            coin.asset.clone(),
+            coin.fee,
+            Some(price),
+            None,
+            format!("{external_id}:fee"),
+        ));
+    }
+}
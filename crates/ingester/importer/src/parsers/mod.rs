@@ -0,0 +1,320 @@
+mod binance;
+mod coinbase;
+mod generic;
+mod kraken;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use csv::{ReaderBuilder, StringRecord, Trim};
+use domain::{CASH_ASSET, LedgerTransaction, MAX_ASSET_SYMBOL_LEN, MAX_EXTERNAL_ID_LEN, TransactionKind};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::report::ImportReport;
+
+pub use binance::BinanceParser;
+pub use coinbase::CoinbaseParser;
+pub use generic::GenericParser;
+pub use kraken::KrakenParser;
+
+/// Turns one exchange export into ledger transactions booked on `account_id`.
+pub trait TradeHistoryParser: Send + Sync {
+    fn source(&self) -> &'static str;
+    fn parse(&self, account_id: Uuid, data: &str) -> ImportReport;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Binance,
+    Coinbase,
+    Kraken,
+    Generic,
+}
+
+impl ImportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::Binance => "binance",
+            ImportFormat::Coinbase => "coinbase",
+            ImportFormat::Kraken => "kraken",
+            ImportFormat::Generic => "generic",
+        }
+    }
+
+    pub fn parser(&self) -> Box<dyn TradeHistoryParser> {
+        match self {
+            ImportFormat::Binance => Box::new(BinanceParser),
+            ImportFormat::Coinbase => Box::new(CoinbaseParser),
+            ImportFormat::Kraken => Box::new(KrakenParser),
+            ImportFormat::Generic => Box::new(GenericParser),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseImportFormatError(pub String);
+
+impl fmt::Display for ParseImportFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid ImportFormat: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseImportFormatError {}
+
+impl FromStr for ImportFormat {
+    type Err = ParseImportFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "binance" => Ok(ImportFormat::Binance),
+            "coinbase" => Ok(ImportFormat::Coinbase),
+            "kraken" => Ok(ImportFormat::Kraken),
+            "generic" => Ok(ImportFormat::Generic),
+            other => Err(ParseImportFormatError(other.to_string())),
+        }
+    }
+}
+
+pub fn import(format: ImportFormat, account_id: Uuid, data: &str) -> ImportReport {
+    format.parser().parse(account_id, data)
+}
+
+/// Deserializes every row of `data` into `R` and hands it to `on_row` with its file line.
+///
+/// Exports that start with a free-text preamble (Coinbase) pass the possible beginnings of
+/// the header line in `header_starts`; everything above the header is skipped.
+pub(crate) fn for_each_row<R, F>(data: &str, header_starts: &[&str], report: &mut ImportReport, mut on_row: F)
+where
+    R: DeserializeOwned,
+    F: FnMut(u64, &StringRecord, R, &mut ImportReport),
+{
+    let (body, offset) = skip_preamble(data, header_starts);
+
+    let mut reader = ReaderBuilder::new()
+        .trim(Trim::All)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(e) => {
+            report.error(offset + 1, None, format!("Cannot read header: {e}"));
+            return;
+        }
+    };
+
+    for result in reader.records() {
+        report.rows_read += 1;
+
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0) + offset;
+                report.error(line, None, e.to_string());
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(0) + offset;
+
+        match record.deserialize::<R>(Some(&headers)) {
+            Ok(row) => on_row(line, &record, row, report),
+            Err(e) => {
+                let field = match e.kind() {
+                    csv::ErrorKind::Deserialize { err, .. } => err.field().and_then(|i| headers.get(i as usize)),
+                    _ => None,
+                };
+                report.error(line, field, e.to_string());
+            }
+        }
+    }
+}
+
+fn skip_preamble<'a>(data: &'a str, header_starts: &[&str]) -> (&'a str, u64) {
+    if header_starts.is_empty() {
+        return (data, 0);
+    }
+
+    let mut byte_offset = 0;
+    for (index, line) in data.split_inclusive('\n').enumerate() {
+        let cleaned = line.trim_start_matches('\u{feff}').trim_start_matches('"');
+        if header_starts.iter().any(|start| cleaned.starts_with(start)) {
+            return (&data[byte_offset..], index as u64);
+        }
+        byte_offset += line.len();
+    }
+
+    (data, 0)
+}
+
+/// Column name and message of a value that failed to parse.
+pub(crate) type FieldError = (&'static str, String);
+
+/// Stable identifiers for imported rows. Exchange ids are used when the export has them,
+/// otherwise the row content is hashed. Identical rows within one file are told apart by
+/// their occurrence, so the ids stay the same when the same file is imported again.
+pub(crate) struct RowIds {
+    source: &'static str,
+    seen: HashMap<String, usize>,
+}
+
+impl RowIds {
+    pub(crate) fn new(source: &'static str) -> Self {
+        Self { source, seen: HashMap::new() }
+    }
+
+    pub(crate) fn explicit(&self, id: &str) -> String {
+        format!("{}:{}", self.source, id)
+    }
+
+    pub(crate) fn hashed(&mut self, record: &StringRecord) -> String {
+        let mut hasher = Sha256::new();
+        for field in record.iter() {
+            hasher.update(field.as_bytes());
+            hasher.update([0x1f]);
+        }
+        let digest = hex::encode(&hasher.finalize()[..16]);
+
+        let occurrence = self.seen.entry(digest.clone()).or_insert(0);
+        *occurrence += 1;
+        if *occurrence == 1 {
+            format!("{}:{}", self.source, digest)
+        } else {
+            format!("{}:{}#{}", self.source, digest, occurrence)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn new_tx(
+    account_id: Uuid,
+    timestamp: NaiveDateTime,
+    kind: TransactionKind,
+    asset_symbol: String,
+    quantity: f64,
+    price_usd: Option<f64>,
+    fee_usd: Option<f64>,
+    external_id: String,
+) -> LedgerTransaction {
+    LedgerTransaction {
+        id: Uuid::new_v4(),
+        account_id,
+        timestamp,
+        kind,
+        asset_symbol,
+        quantity,
+        price_usd,
+        fee_usd,
+        counterparty_account_id: None,
+        note: None,
+        external_id: Some(external_id),
+    }
+}
+
+/// Checks the invariants the ledger relies on before a row is accepted. Returns whether it was.
+pub(crate) fn push_validated(report: &mut ImportReport, line: u64, tx: LedgerTransaction) -> bool {
+    if !tx.quantity.is_finite() || tx.quantity <= 0.0 {
+        report.error(line, Some("quantity"), format!("Quantity must be positive, got {}", tx.quantity));
+        return false;
+    }
+    if matches!(tx.kind, TransactionKind::Buy | TransactionKind::Sell) && tx.price_usd.is_none_or(|p| p < 0.0) {
+        report.error(line, Some("price"), "Trades need a non-negative USD price");
+        return false;
+    }
+    if tx.kind == TransactionKind::Transfer && tx.counterparty_account_id.is_none() {
+        report.error(line, Some("counterparty_account_id"), "Transfers need a counterparty account");
+        return false;
+    }
+    if tx.asset_symbol.is_empty() {
+        report.error(line, Some("asset"), "Asset symbol is empty");
+        return false;
+    }
+    if tx.asset_symbol.chars().count() > MAX_ASSET_SYMBOL_LEN {
+        report.error(line, Some("asset"), format!("Asset symbol is longer than {MAX_ASSET_SYMBOL_LEN} characters"));
+        return false;
+    }
+    if tx.external_id.as_ref().is_some_and(|id| id.chars().count() > MAX_EXTERNAL_ID_LEN) {
+        report.error(line, Some("external_id"), format!("External id is longer than {MAX_EXTERNAL_ID_LEN} characters"));
+        return false;
+    }
+    report.lines.insert(tx.id, line);
+    report.transactions.push(tx);
+    true
+}
+
+/// Books the stablecoin side of a trade quoted in one, so the stablecoin balance moves rather
+/// than the USD cash the trade itself settles in. `amount` is the change of the quote balance,
+/// fees included, negative when the trade spent it. Stablecoins change hands at 1 USD; trades
+/// quoted in USD itself need no second leg.
+pub(crate) fn push_quote_leg(
+    report: &mut ImportReport,
+    line: u64,
+    account_id: Uuid,
+    timestamp: NaiveDateTime,
+    quote: &str,
+    amount: f64,
+    external_id: &str,
+) {
+    if quote == CASH_ASSET || amount == 0.0 {
+        return;
+    }
+    let kind = if amount < 0.0 { TransactionKind::Sell } else { TransactionKind::Buy };
+    push_validated(report, line, new_tx(
+        account_id,
+        timestamp,
+        kind,
+        quote.to_string(),
+        amount.abs(),
+        Some(1.0),
+        None,
+        format!("{external_id}:quote"),
+    ));
+}
+
+/// Parses the timestamp layouts used by the supported exports. Values without a zone are UTC.
+pub(crate) fn parse_timestamp(value: &str) -> Result<NaiveDateTime, String> {
+    let value = value.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.naive_utc());
+    }
+
+    let without_zone = value.trim_end_matches(" UTC").trim_end_matches('Z');
+    for layout in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%m/%d/%Y %H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(without_zone, layout) {
+            return Ok(dt);
+        }
+    }
+
+    NaiveDate::parse_from_str(without_zone, "%Y-%m-%d")
+        .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+        .map_err(|_| format!("Unrecognized timestamp: {value}"))
+}
+
+/// Parses amounts such as `1,234.50`, `$1,234.50` or `-0.5`.
+pub(crate) fn parse_amount(value: &str) -> Result<f64, String> {
+    let cleaned: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '$' | ',' | ' '))
+        .collect();
+
+    cleaned
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid amount: {value}"))
+}
+
+/// Splits Binance-style amounts like `0.50000000BTC` into the number and the asset.
+pub(crate) fn split_amount_asset(value: &str) -> Result<(f64, String), String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(|| format!("Missing asset in amount: {value}"))?;
+
+    let (amount, asset) = value.split_at(split);
+    Ok((parse_amount(amount)?, asset.to_string()))
+}
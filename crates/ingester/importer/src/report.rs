@@ -0,0 +1,134 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use chrono::NaiveDateTime;
+use domain::{CostBasisMethod, LedgerError, LedgerTransaction, LotBook, PortfolioState};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// A row that could not be turned into a ledger transaction. `line` is 1-based and
+/// points at the line of the original file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RowError {
+    pub line: u64,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub rows_read: usize,
+    pub rows_skipped: usize,
+    pub transactions: Vec<LedgerTransaction>,
+    pub errors: Vec<RowError>,
+    /// File line of each accepted transaction, for errors found after parsing.
+    #[serde(skip)]
+    pub(crate) lines: HashMap<Uuid, u64>,
+}
+
+impl ImportReport {
+    pub fn new(source: &str) -> Self {
+        Self { source: source.to_string(), ..Self::default() }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Line of the file `tx_id` was read from, 0 if it is not one of the imported transactions.
+    pub fn line_of(&self, tx_id: Uuid) -> u64 {
+        self.lines.get(&tx_id).copied().unwrap_or(0)
+    }
+
+    /// Replays the portfolio's stored `ledger` together with the imported transactions and moves
+    /// the imported rows that would overdraw a balance to `errors`, so the stored ledger keeps
+    /// replaying. A trade is accepted or rejected together with its fee and quote legs. Rows the
+    /// ledger already has are left in place; they are skipped when stored.
+    pub fn reject_overdrafts(&mut self, ledger: &[LedgerTransaction], method: CostBasisMethod) {
+        enum Entry {
+            Stored(usize),
+            Imported(Range<usize>),
+        }
+
+        let stored: HashSet<(Uuid, &str)> = ledger
+            .iter()
+            .filter_map(|tx| Some((tx.account_id, tx.external_id.as_deref()?)))
+            .collect();
+
+        let mut entries: Vec<(NaiveDateTime, Entry)> = ledger
+            .iter()
+            .enumerate()
+            .map(|(i, tx)| (tx.timestamp, Entry::Stored(i)))
+            .collect();
+        let mut start = 0;
+        while start < self.transactions.len() {
+            let head = &self.transactions[start];
+            let group = trade_id(head);
+            let len = self.transactions[start..].iter().take_while(|tx| trade_id(tx) == group).count();
+            let known = head.external_id.as_deref().is_some_and(|id| stored.contains(&(head.account_id, id)));
+            if !known {
+                entries.push((head.timestamp, Entry::Imported(start..start + len)));
+            }
+            start += len;
+        }
+        // Stable, so stored rows go before imported ones with the same timestamp.
+        entries.sort_by_key(|(timestamp, _)| *timestamp);
+
+        let mut state = PortfolioState::new();
+        let mut book = LotBook::new(method);
+        let mut rejected = HashSet::new();
+        let mut errors = Vec::new();
+        for (_, entry) in entries {
+            match entry {
+                Entry::Stored(i) => {
+                    // Stored rows that no longer replay are not this import's to report.
+                    let _ = state.apply(&ledger[i]).and_then(|_| book.apply(&ledger[i]));
+                }
+                Entry::Imported(range) => {
+                    let group = &self.transactions[range];
+                    let (mut next_state, mut next_book) = (state.clone(), book.clone());
+                    let applied = group.iter().try_for_each(|tx| {
+                        next_state.apply(tx)?;
+                        next_book.apply(tx)
+                    });
+                    match applied {
+                        Ok(()) => (state, book) = (next_state, next_book),
+                        Err(e) => {
+                            errors.push(overdraft_error(self.line_of(group[0].id), e));
+                            rejected.extend(group.iter().map(|tx| tx.id));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.transactions.retain(|tx| !rejected.contains(&tx.id));
+        self.errors.extend(errors);
+    }
+
+    pub(crate) fn error(&mut self, line: u64, field: Option<&str>, message: impl Into<String>) {
+        self.errors.push(RowError {
+            line,
+            field: field.map(str::to_string),
+            message: message.into(),
+        });
+    }
+}
+
+/// External id of the trade a transaction belongs to, without the suffix of its fee or quote leg.
+fn trade_id(tx: &LedgerTransaction) -> Option<&str> {
+    let id = tx.external_id.as_deref()?;
+    Some(id.strip_suffix(":fee").or_else(|| id.strip_suffix(":quote")).unwrap_or(id))
+}
+
+fn overdraft_error(line: u64, error: LedgerError) -> RowError {
+    match error {
+        LedgerError::InsufficientBalance { asset, available, requested, .. } => RowError {
+            line,
+            field: Some("quantity".to_string()),
+            message: format!("Needs {requested} {asset} but the account only holds {available} at that time"),
+        },
+        other => RowError { line, field: None, message: other.to_string() },
+    }
+}
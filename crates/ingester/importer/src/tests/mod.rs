@@ -0,0 +1,4 @@
+#[cfg(test)]
+pub mod parser_tests;
+#[cfg(test)]
+pub mod report_tests;
@@ -0,0 +1,168 @@
+use domain::TransactionKind;
+use uuid::Uuid;
+
+use crate::{ImportFormat, import};
+
+const BINANCE: &str = "\
+Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-02 10:00:00,BTCUSDT,BUY,42000,0.5BTC,21000USDT,0.0005BTC
+2024-02-02 10:00:00,ETHUSDT,SELL,2300,2ETH,4600USDT,4.6USDT
+2024-02-03 10:00:00,ETHBTC,BUY,0.05,1ETH,0.05BTC,0.001BNB
+";
+
+const COINBASE: &str = "\
+You can use this transaction report to inform your likely tax obligations.
+
+Transactions
+User,jane@example.com,abc
+ID,Timestamp,Transaction Type,Asset,Quantity Transacted,Price Currency,Price at Transaction,Subtotal,Total (inclusive of fees and/or spread),Fees and/or Spread,Notes
+65a1,2024-01-05 14:03:01 UTC,Buy,BTC,0.01,USD,$44000.00,$440.00,$446.50,$6.50,Bought 0.01 BTC
+65a2,2024-01-06 09:00:00 UTC,Send,BTC,-0.005,USD,$45000.00,,,,Sent to wallet
+65a3,2024-01-07 09:00:00 UTC,Convert,ETH,1,USD,$2300.00,,,,Converted 1 ETH to 0.05 BTC
+";
+
+const KRAKEN: &str = "\
+\"txid\",\"refid\",\"time\",\"type\",\"subtype\",\"aclass\",\"asset\",\"amount\",\"fee\",\"balance\"
+\"L1\",\"D1\",\"2024-01-01 08:00:00\",\"deposit\",\"\",\"currency\",\"ZUSD\",1000.0,0.0,1000.0
+\"L2\",\"T1\",\"2024-01-02 08:00:00\",\"trade\",\"\",\"currency\",\"ZUSD\",-500.0,1.0,499.0
+\"L3\",\"T1\",\"2024-01-02 08:00:00\",\"trade\",\"\",\"currency\",\"XXBT\",0.0125,0.0,0.0125
+\"L4\",\"S1\",\"2024-01-03 08:00:00\",\"transfer\",\"spottostaking\",\"currency\",\"XETH\",-1.0,0.0,0.0
+\"L5\",\"R1\",\"2024-01-10 08:00:00\",\"staking\",\"\",\"currency\",\"XETH\",0.002,0.0,0.002
+";
+
+#[test]
+fn test_binance_trades_and_fees() {
+    let report = import(ImportFormat::Binance, Uuid::new_v4(), BINANCE);
+
+    assert_eq!(report.rows_read, 3);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, 4);
+    assert_eq!(report.errors[0].field.as_deref(), Some("Pair"));
+
+    let kinds: Vec<(TransactionKind, &str)> = report.transactions
+        .iter()
+        .map(|tx| (tx.kind, tx.asset_symbol.as_str()))
+        .collect();
+    assert_eq!(kinds, vec![
+        (TransactionKind::Buy, "BTC"),
+        (TransactionKind::Sell, "USDT"),
+        (TransactionKind::Fee, "BTC"),
+        (TransactionKind::Sell, "ETH"),
+        (TransactionKind::Buy, "USDT"),
+    ]);
+    assert_eq!(report.transactions[1].quantity, 21_000.0);
+    assert_eq!(report.transactions[1].external_id, report.transactions[0].external_id.as_ref().map(|id| format!("{id}:quote")));
+    assert_eq!(report.transactions[3].fee_usd, Some(4.6));
+    assert_eq!(report.transactions[4].quantity, 4_595.4);
+}
+
+#[test]
+fn test_coinbase_skips_preamble_and_reports_conversions() {
+    let report = import(ImportFormat::Coinbase, Uuid::new_v4(), COINBASE);
+
+    assert_eq!(report.transactions.len(), 2);
+    assert_eq!(report.transactions[0].kind, TransactionKind::Buy);
+    assert_eq!(report.transactions[0].price_usd, Some(44_000.0));
+    assert_eq!(report.transactions[0].fee_usd, Some(6.5));
+    assert_eq!(report.transactions[0].external_id.as_deref(), Some("coinbase:65a1"));
+    assert_eq!(report.transactions[1].kind, TransactionKind::Withdrawal);
+    assert_eq!(report.transactions[1].quantity, 0.005);
+
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, 8);
+}
+
+#[test]
+fn test_kraken_pairs_trade_legs() {
+    let report = import(ImportFormat::Kraken, Uuid::new_v4(), KRAKEN);
+
+    assert!(report.is_clean(), "{:?}", report.errors);
+    assert_eq!(report.rows_skipped, 1);
+    assert_eq!(report.transactions.len(), 3);
+
+    let buy = &report.transactions[1];
+    assert_eq!(buy.kind, TransactionKind::Buy);
+    assert_eq!(buy.asset_symbol, "BTC");
+    assert_eq!(buy.price_usd, Some(40_000.0));
+    assert_eq!(buy.fee_usd, Some(1.0));
+    assert_eq!(buy.external_id.as_deref(), Some("kraken:T1"));
+
+    let reward = &report.transactions[2];
+    assert_eq!(reward.kind, TransactionKind::StakingReward);
+    assert_eq!(reward.asset_symbol, "ETH");
+    assert_eq!(reward.price_usd, None);
+}
+
+#[test]
+fn test_kraken_stablecoin_trades_move_the_stablecoin() {
+    let data = "\
+\"txid\",\"refid\",\"time\",\"type\",\"subtype\",\"aclass\",\"asset\",\"amount\",\"fee\",\"balance\"
+\"L1\",\"T2\",\"2024-01-02 08:00:00\",\"trade\",\"\",\"currency\",\"USDC\",-500.0,1.0,0.0
+\"L2\",\"T2\",\"2024-01-02 08:00:00\",\"trade\",\"\",\"currency\",\"XXBT\",0.0125,0.0,0.0125
+";
+    let report = import(ImportFormat::Kraken, Uuid::new_v4(), data);
+
+    assert!(report.is_clean(), "{:?}", report.errors);
+    assert_eq!(report.transactions.len(), 2);
+    assert_eq!(report.transactions[0].asset_symbol, "BTC");
+    assert_eq!(report.transactions[0].fee_usd, Some(1.0));
+
+    let quote = &report.transactions[1];
+    assert_eq!(quote.kind, TransactionKind::Sell);
+    assert_eq!(quote.asset_symbol, "USDC");
+    assert_eq!(quote.quantity, 501.0);
+    assert_eq!(quote.price_usd, Some(1.0));
+    assert_eq!(quote.external_id.as_deref(), Some("kraken:T2:quote"));
+}
+
+#[test]
+fn test_reimport_produces_same_external_ids() {
+    let account = Uuid::new_v4();
+    let data = "\
+timestamp,kind,asset,quantity,price_usd,fee_usd,counterparty_account_id,external_id,note
+2024-03-01 10:00:00,BUY,bitcoin,0.25,62000,4.5,,,
+2024-03-01 10:00:00,BUY,bitcoin,0.25,62000,4.5,,,
+2024-03-02,DEPOSIT,USD,1000,,,,bank-1,salary
+";
+
+    let first = import(ImportFormat::Generic, account, data);
+    let second = import(ImportFormat::Generic, account, data);
+
+    let ids = |r: &crate::ImportReport| r.transactions.iter().map(|t| t.external_id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&first), ids(&second));
+    assert_ne!(first.transactions[0].external_id, first.transactions[1].external_id);
+    assert_eq!(first.transactions[0].asset_symbol, "BTC");
+    assert_eq!(first.transactions[2].external_id.as_deref(), Some("generic:bank-1"));
+}
+
+#[test]
+fn test_values_wider_than_their_columns_are_rejected() {
+    let data = format!("\
+timestamp,kind,asset,quantity,price_usd,fee_usd,counterparty_account_id,external_id,note
+2024-03-01,BUY,{},0.25,1,,,,
+2024-03-01,BUY,BTC,0.25,1,,,{},
+2024-03-01,BUY,BTC,0.25,1,,,ok,
+", "A".repeat(17), "x".repeat(128));
+
+    let report = import(ImportFormat::Generic, Uuid::new_v4(), &data);
+
+    assert_eq!(report.transactions.len(), 1);
+    let fields: Vec<_> = report.errors.iter().map(|e| (e.line, e.field.as_deref())).collect();
+    assert_eq!(fields, vec![(2, Some("asset")), (3, Some("external_id"))]);
+}
+
+#[test]
+fn test_generic_row_errors_are_reported() {
+    let data = "\
+timestamp,kind,asset,quantity,price_usd,fee_usd,counterparty_account_id,external_id,note
+2024-03-01,BUY,BTC,0.25,,,,,
+2024-03-01,SWAP,BTC,0.25,1,,,,
+2024-03-01,SELL,BTC,abc,1,,,,
+";
+
+    let report = import(ImportFormat::Generic, Uuid::new_v4(), data);
+
+    assert!(report.transactions.is_empty());
+    let fields: Vec<(u64, Option<&str>)> = report.errors.iter().map(|e| (e.line, e.field.as_deref())).collect();
+    assert_eq!(fields, vec![(2, Some("price")), (3, Some("kind")), (4, Some("quantity"))]);
+}
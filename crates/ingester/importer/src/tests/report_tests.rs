@@ -0,0 +1,68 @@
+use chrono::NaiveDate;
+use domain::{CostBasisMethod, LedgerTransaction, TransactionKind};
+use uuid::Uuid;
+
+use crate::{ImportFormat, import};
+
+fn usdt_deposit(account_id: Uuid, quantity: f64) -> LedgerTransaction {
+    LedgerTransaction {
+        id: Uuid::new_v4(),
+        account_id,
+        timestamp: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+        kind: TransactionKind::Deposit,
+        asset_symbol: "USDT".to_string(),
+        quantity,
+        price_usd: Some(1.0),
+        fee_usd: None,
+        counterparty_account_id: None,
+        note: None,
+        external_id: None,
+    }
+}
+
+#[test]
+fn test_overdrawing_trades_are_rejected_with_their_legs() {
+    let account = Uuid::new_v4();
+    let data = "\
+Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-02 10:00:00,BTCUSDT,BUY,40000,0.1BTC,4000USDT,0.01BNB
+2024-01-03 10:00:00,BTCUSDT,BUY,40000,0.1BTC,4000USDT,0.0001BTC
+2024-01-04 10:00:00,BTCUSDT,SELL,40000,0.5BTC,20000USDT,20USDT
+";
+    let mut report = import(ImportFormat::Binance, account, data);
+    assert!(report.is_clean(), "{:?}", report.errors);
+
+    report.reject_overdrafts(&[usdt_deposit(account, 10_000.0)], CostBasisMethod::Fifo);
+
+    let lines: Vec<_> = report.errors.iter().map(|e| (e.line, e.field.as_deref())).collect();
+    assert_eq!(lines, vec![(2, Some("quantity")), (4, Some("quantity"))]);
+    assert!(report.errors[0].message.contains("BNB"), "{}", report.errors[0].message);
+
+    let kinds: Vec<(TransactionKind, &str)> = report.transactions
+        .iter()
+        .map(|tx| (tx.kind, tx.asset_symbol.as_str()))
+        .collect();
+    assert_eq!(kinds, vec![
+        (TransactionKind::Buy, "BTC"),
+        (TransactionKind::Sell, "USDT"),
+        (TransactionKind::Fee, "BTC"),
+    ]);
+}
+
+#[test]
+fn test_rows_already_in_the_ledger_are_not_replayed_twice() {
+    let account = Uuid::new_v4();
+    let data = "\
+Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-02 10:00:00,BTCUSDT,BUY,40000,0.1BTC,4000USDT,0USDT
+2024-01-03 10:00:00,BTCUSDT,SELL,40000,0.1BTC,4000USDT,0USDT
+";
+    let mut ledger = vec![usdt_deposit(account, 4_000.0)];
+    ledger.extend(import(ImportFormat::Binance, account, data).transactions);
+
+    let mut report = import(ImportFormat::Binance, account, data);
+    report.reject_overdrafts(&ledger, CostBasisMethod::Fifo);
+
+    assert!(report.is_clean(), "{:?}", report.errors);
+    assert_eq!(report.transactions.len(), 4);
+}
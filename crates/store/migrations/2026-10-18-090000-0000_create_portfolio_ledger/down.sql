@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS ledger_transactions;
+DROP TABLE IF EXISTS accounts;
+DROP TABLE IF EXISTS portfolios;
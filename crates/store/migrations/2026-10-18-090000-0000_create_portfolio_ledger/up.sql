@@ -0,0 +1,33 @@
+-- Your SQL goes here
+-- Portfolios group the accounts whose ledgers are reported together
+CREATE TABLE IF NOT EXISTS portfolios (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(128) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Accounts: one row per exchange, wallet or bank account
+CREATE TABLE IF NOT EXISTS accounts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    portfolio_id UUID NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+    name VARCHAR(128) NOT NULL,
+    venue VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Ledger transactions: positions are derived by replaying these in timestamp order
+CREATE TABLE IF NOT EXISTS ledger_transactions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    timestamp TIMESTAMPTZ NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    asset_symbol VARCHAR(16) NOT NULL,
+    quantity DOUBLE PRECISION NOT NULL CHECK (quantity > 0),
+    price_usd DOUBLE PRECISION,
+    fee_usd DOUBLE PRECISION,
+    counterparty_account_id UUID REFERENCES accounts(id) ON DELETE SET NULL,
+    note TEXT
+);
+
+CREATE INDEX IF NOT EXISTS ledger_transactions_account_timestamp_idx
+    ON ledger_transactions (account_id, timestamp);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE portfolios
+DROP COLUMN IF EXISTS cost_basis_method;
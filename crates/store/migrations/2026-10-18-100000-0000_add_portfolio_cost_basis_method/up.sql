@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE portfolios
+ADD COLUMN cost_basis_method VARCHAR(16) NOT NULL DEFAULT 'FIFO';
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE ledger_transactions
+DROP CONSTRAINT IF EXISTS ledger_transactions_account_external_id_key;
+
+ALTER TABLE ledger_transactions
+DROP COLUMN IF EXISTS external_id;
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- Imported rows keep the exchange id (or a content hash) so re-imports can be skipped
+ALTER TABLE ledger_transactions
+ADD COLUMN external_id VARCHAR(128);
+
+ALTER TABLE ledger_transactions
+ADD CONSTRAINT ledger_transactions_account_external_id_key UNIQUE (account_id, external_id);
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+-- Portfolio NAV: one row per portfolio per day
+CREATE TABLE IF NOT EXISTS portfolio_nav (
+    portfolio_id UUID NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+    timestamp DATE NOT NULL,
+    nav_usd DOUBLE PRECISION NOT NULL,
+    net_flow_usd DOUBLE PRECISION NOT NULL DEFAULT 0,
+    forward_filled BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (portfolio_id, timestamp)
+);
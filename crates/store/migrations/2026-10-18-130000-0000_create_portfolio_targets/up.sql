@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Target allocation: one row per bucket (asset symbol or CLASS:<asset class>)
+CREATE TABLE IF NOT EXISTS portfolio_targets (
+    portfolio_id UUID NOT NULL REFERENCES portfolios(id) ON DELETE CASCADE,
+    bucket VARCHAR(32) NOT NULL,
+    weight DOUBLE PRECISION NOT NULL CHECK (weight >= 0 AND weight <= 1),
+    band DOUBLE PRECISION NOT NULL DEFAULT 0.05 CHECK (band >= 0 AND band <= 1),
+    PRIMARY KEY (portfolio_id, bucket)
+);
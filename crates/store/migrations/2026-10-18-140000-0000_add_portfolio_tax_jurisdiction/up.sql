@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE portfolios
+ADD COLUMN tax_jurisdiction VARCHAR(8) NOT NULL DEFAULT 'US';
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS portfolios_owner_id_idx;
+
+ALTER TABLE portfolios
+DROP COLUMN IF EXISTS owner_id;
+
+DROP TABLE IF EXISTS api_keys;
+DROP TABLE IF EXISTS users;
@@ -0,0 +1,18 @@
+-- Your SQL goes here
+-- Lending and staking APYs in percent, one row per source, protocol, asset, kind and day.
+-- Spreads are percentage points of the risk-adjusted APY over DFF and DGS2 of the same day.
+CREATE TABLE IF NOT EXISTS yields (
+    source VARCHAR(64) NOT NULL,
+    protocol VARCHAR(64) NOT NULL,
+    asset_symbol VARCHAR(16) NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    timestamp DATE NOT NULL,
+    apy DOUBLE PRECISION NOT NULL,
+    risk_adjusted_apy DOUBLE PRECISION NOT NULL,
+    tvl_usd DOUBLE PRECISION,
+    spread_over_dff DOUBLE PRECISION,
+    spread_over_dgs2 DOUBLE PRECISION,
+    PRIMARY KEY (source, protocol, asset_symbol, kind, timestamp)
+);
+
+CREATE INDEX IF NOT EXISTS idx_yields_asset_timestamp ON yields (asset_symbol, timestamp);
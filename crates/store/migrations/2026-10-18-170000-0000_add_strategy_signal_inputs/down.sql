@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE strategy_signals
+DROP COLUMN IF EXISTS inputs;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE strategy_signals
+ADD COLUMN inputs JSONB;
@@ -0,0 +1,27 @@
+-- Your SQL goes here
+-- Alert rules: `condition` is stored as written, e.g. 'FEAR_GREED_INDEX below 15'
+CREATE TABLE IF NOT EXISTS alert_rules (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(64) NOT NULL UNIQUE,
+    condition TEXT NOT NULL,
+    channel VARCHAR(16) NOT NULL,
+    target TEXT NOT NULL,
+    cooldown_minutes BIGINT NOT NULL DEFAULT 60,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Every match of a rule and what became of it. A match is identified by its dedup key and is
+-- not delivered again once it was sent or suppressed by the cooldown.
+CREATE TABLE IF NOT EXISTS alert_deliveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    rule_id UUID NOT NULL REFERENCES alert_rules(id) ON DELETE CASCADE,
+    dedup_key VARCHAR(128) NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    subject TEXT NOT NULL,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS alert_deliveries_rule_id_dedup_key_idx ON alert_deliveries (rule_id, dedup_key);
+CREATE INDEX IF NOT EXISTS alert_deliveries_created_at_idx ON alert_deliveries (created_at);
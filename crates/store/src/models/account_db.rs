@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::Account;
+use uuid::Uuid;
+
+use crate::schema::accounts;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = accounts)]
+#[diesel(primary_key(id))]
+pub struct AccountDB {
+    pub id: Uuid,
+    pub portfolio_id: Uuid,
+    pub name: String,
+    pub venue: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<AccountDB> for Account {
+    fn from(row: AccountDB) -> Self {
+        Account {
+            id: row.id,
+            portfolio_id: row.portfolio_id,
+            name: row.name,
+            venue: row.venue,
+            created_at: row.created_at,
+        }
+    }
+}
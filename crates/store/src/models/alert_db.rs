@@ -0,0 +1,68 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::{AlertChannel, AlertCondition, AlertRule};
+use uuid::Uuid;
+
+use crate::schema::{alert_deliveries, alert_rules};
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = alert_rules)]
+#[diesel(primary_key(id))]
+pub struct AlertRuleDB {
+    pub id: Uuid,
+    pub name: String,
+    pub condition: String,
+    pub channel: String,
+    pub target: String,
+    pub cooldown_minutes: i64,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<&AlertRule> for AlertRuleDB {
+    fn from(rule: &AlertRule) -> Self {
+        Self {
+            id: rule.id,
+            name: rule.name.clone(),
+            condition: rule.condition.to_string(),
+            channel: rule.channel.as_str().to_string(),
+            target: rule.target.clone(),
+            cooldown_minutes: rule.cooldown_minutes,
+            enabled: rule.enabled,
+            created_at: rule.created_at,
+        }
+    }
+}
+
+/// Fails on a condition or channel that no longer parses, e.g. after a series was renamed.
+impl TryFrom<AlertRuleDB> for AlertRule {
+    type Error = String;
+
+    fn try_from(row: AlertRuleDB) -> Result<Self, Self::Error> {
+        Ok(AlertRule {
+            condition: AlertCondition::from_str(&row.condition).map_err(|e| format!("Alert rule {}: {}", row.name, e))?,
+            channel: AlertChannel::from_str(&row.channel).map_err(|e| format!("Alert rule {}: {}", row.name, e))?,
+            id: row.id,
+            name: row.name,
+            target: row.target,
+            cooldown_minutes: row.cooldown_minutes,
+            enabled: row.enabled,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = alert_deliveries)]
+#[diesel(primary_key(id))]
+pub struct AlertDeliveryDB {
+    pub id: Uuid,
+    pub rule_id: Uuid,
+    pub dedup_key: String,
+    pub status: String,
+    pub subject: String,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
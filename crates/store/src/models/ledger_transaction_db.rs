@@ -0,0 +1,63 @@
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::{LedgerTransaction, ParseTransactionKindError, TransactionKind};
+use uuid::Uuid;
+
+use crate::schema::ledger_transactions;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = ledger_transactions)]
+#[diesel(primary_key(id))]
+pub struct LedgerTransactionDB {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub timestamp: NaiveDateTime,
+    pub kind: String,
+    pub asset_symbol: String,
+    pub quantity: f64,
+    pub price_usd: Option<f64>,
+    pub fee_usd: Option<f64>,
+    pub counterparty_account_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub external_id: Option<String>,
+}
+
+impl From<&LedgerTransaction> for LedgerTransactionDB {
+    fn from(tx: &LedgerTransaction) -> Self {
+        Self {
+            id: tx.id,
+            account_id: tx.account_id,
+            timestamp: tx.timestamp,
+            kind: tx.kind.as_str().to_string(),
+            asset_symbol: tx.asset_symbol.clone(),
+            quantity: tx.quantity,
+            price_usd: tx.price_usd,
+            fee_usd: tx.fee_usd,
+            counterparty_account_id: tx.counterparty_account_id,
+            note: tx.note.clone(),
+            external_id: tx.external_id.clone(),
+        }
+    }
+}
+
+impl TryFrom<LedgerTransactionDB> for LedgerTransaction {
+    type Error = ParseTransactionKindError;
+
+    fn try_from(row: LedgerTransactionDB) -> Result<Self, Self::Error> {
+        Ok(LedgerTransaction {
+            id: row.id,
+            account_id: row.account_id,
+            timestamp: row.timestamp,
+            kind: TransactionKind::from_str(&row.kind)?,
+            asset_symbol: row.asset_symbol,
+            quantity: row.quantity,
+            price_usd: row.price_usd,
+            fee_usd: row.fee_usd,
+            counterparty_account_id: row.counterparty_account_id,
+            note: row.note,
+            external_id: row.external_id,
+        })
+    }
+}
@@ -1,4 +1,12 @@
+pub mod alert_db;
 pub mod indicator_db;
 pub mod market_data_db;
 pub mod market_metrics_db;
-pub mod signal_db;
\ No newline at end of file
+pub mod signal_db;
+pub mod portfolio_db;
+pub mod account_db;
+pub mod ledger_transaction_db;
+pub mod portfolio_nav_db;
+pub mod portfolio_target_db;
+pub mod user_db;
+pub mod yield_db;
@@ -0,0 +1,54 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::{CostBasisMethod, Jurisdiction, ParseCostBasisMethodError, ParseJurisdictionError, Portfolio};
+use uuid::Uuid;
+
+use crate::schema::portfolios;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = portfolios)]
+#[diesel(primary_key(id))]
+pub struct PortfolioDB {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub cost_basis_method: String,
+    pub tax_jurisdiction: String,
+    pub owner_id: Option<Uuid>,
+}
+
+/// A stored portfolio whose settings no longer parse.
+#[derive(Debug, Clone)]
+pub enum PortfolioRowError {
+    CostBasisMethod(ParseCostBasisMethodError),
+    Jurisdiction(ParseJurisdictionError),
+}
+
+impl fmt::Display for PortfolioRowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortfolioRowError::CostBasisMethod(e) => e.fmt(f),
+            PortfolioRowError::Jurisdiction(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for PortfolioRowError {}
+
+impl TryFrom<PortfolioDB> for Portfolio {
+    type Error = PortfolioRowError;
+
+    fn try_from(row: PortfolioDB) -> Result<Self, Self::Error> {
+        Ok(Portfolio {
+            id: row.id,
+            name: row.name,
+            cost_basis_method: CostBasisMethod::from_str(&row.cost_basis_method).map_err(PortfolioRowError::CostBasisMethod)?,
+            tax_jurisdiction: Jurisdiction::from_str(&row.tax_jurisdiction).map_err(PortfolioRowError::Jurisdiction)?,
+            owner_id: row.owner_id,
+            created_at: row.created_at,
+        })
+    }
+}
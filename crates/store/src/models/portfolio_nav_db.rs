@@ -0,0 +1,40 @@
+use chrono::NaiveDate;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::NavPoint;
+use uuid::Uuid;
+
+use crate::schema::portfolio_nav;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = portfolio_nav)]
+#[diesel(primary_key(portfolio_id, timestamp))]
+pub struct PortfolioNavDB {
+    pub portfolio_id: Uuid,
+    pub timestamp: NaiveDate,
+    pub nav_usd: f64,
+    pub net_flow_usd: f64,
+    pub forward_filled: bool,
+}
+
+impl PortfolioNavDB {
+    pub fn from_point(portfolio_id: Uuid, point: &NavPoint) -> Self {
+        Self {
+            portfolio_id,
+            timestamp: point.date,
+            nav_usd: point.nav,
+            net_flow_usd: point.net_flow,
+            forward_filled: point.forward_filled,
+        }
+    }
+}
+
+impl From<PortfolioNavDB> for NavPoint {
+    fn from(row: PortfolioNavDB) -> Self {
+        NavPoint {
+            date: row.timestamp,
+            nav: row.nav_usd,
+            net_flow: row.net_flow_usd,
+            forward_filled: row.forward_filled,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::str::FromStr;
+
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::{AllocationBucket, AllocationTarget, ParseAssetClassError};
+use uuid::Uuid;
+
+use crate::schema::portfolio_targets;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = portfolio_targets)]
+#[diesel(primary_key(portfolio_id, bucket))]
+pub struct PortfolioTargetDB {
+    pub portfolio_id: Uuid,
+    pub bucket: String,
+    pub weight: f64,
+    pub band: f64,
+}
+
+impl PortfolioTargetDB {
+    pub fn from_target(portfolio_id: Uuid, target: &AllocationTarget) -> Self {
+        Self {
+            portfolio_id,
+            bucket: target.bucket.key(),
+            weight: target.weight,
+            band: target.band,
+        }
+    }
+}
+
+impl TryFrom<PortfolioTargetDB> for AllocationTarget {
+    type Error = ParseAssetClassError;
+
+    fn try_from(row: PortfolioTargetDB) -> Result<Self, Self::Error> {
+        Ok(AllocationTarget {
+            bucket: AllocationBucket::from_str(&row.bucket)?,
+            weight: row.weight,
+            band: row.band,
+        })
+    }
+}
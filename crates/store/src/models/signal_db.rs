@@ -1,4 +1,5 @@
 use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::SignalEvent;
 use uuid::Uuid;
 
 use crate::schema::strategy_signals;
@@ -17,14 +18,35 @@ pub struct StrategySignalDB {
     pub value: Option<f64>,
     pub description: Option<String>,
     pub source: Option<String>,
+    /// Serialized [`domain::SignalExplanation`]: what the signal was computed from.
+    pub inputs: Option<serde_json::Value>,
 }
 
+impl From<StrategySignalDB> for SignalEvent {
+    fn from(row: StrategySignalDB) -> Self {
+        SignalEvent {
+            id: row.id,
+            asset_symbol: row.asset_symbol,
+            timestamp: row.timestamp,
+            signal_type: row.signal_type,
+            value: row.value,
+            description: row.description,
+            source: row.source,
+        }
+    }
+}
+
+/// `timestamp` defaults to the time of the insert. Generators that evaluate one day at a time
+/// set it to that day so re-running them updates the signal instead of adding another.
 #[derive(Debug, Insertable)]
 #[diesel(table_name = strategy_signals)]
 pub struct NewStrategySignalDB {
     pub asset_symbol: String,
+    pub timestamp: Option<chrono::NaiveDateTime>,
     pub signal_type: String,
     pub value: Option<f64>,
     pub description: Option<String>,
     pub source: Option<String>,
+    /// Serialized [`domain::SignalExplanation`]: what the signal was computed from.
+    pub inputs: Option<serde_json::Value>,
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::{ApiKey, User};
+use uuid::Uuid;
+
+use crate::schema::{api_keys, users};
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = users)]
+#[diesel(primary_key(id))]
+pub struct UserDB {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<UserDB> for User {
+    fn from(row: UserDB) -> Self {
+        User {
+            id: row.id,
+            name: row.name,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = api_keys)]
+#[diesel(primary_key(id))]
+pub struct ApiKeyDB {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub key_hash: String,
+    pub prefix: String,
+    pub label: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl From<ApiKeyDB> for ApiKey {
+    fn from(row: ApiKeyDB) -> Self {
+        ApiKey {
+            id: row.id,
+            user_id: row.user_id,
+            prefix: row.prefix,
+            label: row.label,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use diesel::prelude::{Identifiable, Insertable, Queryable};
+use domain::{ParseYieldKindError, YieldKind, YieldObservation, YieldQuote};
+
+use crate::schema::yields;
+
+#[derive(Debug, Clone, Queryable, Identifiable, Insertable)]
+#[diesel(table_name = yields)]
+#[diesel(primary_key(source, protocol, asset_symbol, kind, timestamp))]
+pub struct YieldDB {
+    pub source: String,
+    pub protocol: String,
+    pub asset_symbol: String,
+    pub kind: String,
+    pub timestamp: NaiveDate,
+    pub apy: f64,
+    pub risk_adjusted_apy: f64,
+    pub tvl_usd: Option<f64>,
+    pub spread_over_dff: Option<f64>,
+    pub spread_over_dgs2: Option<f64>,
+}
+
+impl From<&YieldObservation> for YieldDB {
+    fn from(observation: &YieldObservation) -> Self {
+        let quote = &observation.quote;
+        Self {
+            source: quote.source.clone(),
+            protocol: quote.protocol.clone(),
+            asset_symbol: quote.asset_symbol.clone(),
+            kind: quote.kind.as_str().to_string(),
+            timestamp: quote.date,
+            apy: quote.apy,
+            risk_adjusted_apy: observation.risk_adjusted_apy,
+            tvl_usd: quote.tvl_usd,
+            spread_over_dff: observation.spread_over_dff,
+            spread_over_dgs2: observation.spread_over_dgs2,
+        }
+    }
+}
+
+impl TryFrom<YieldDB> for YieldObservation {
+    type Error = ParseYieldKindError;
+
+    fn try_from(row: YieldDB) -> Result<Self, Self::Error> {
+        Ok(YieldObservation {
+            quote: YieldQuote {
+                source: row.source,
+                protocol: row.protocol,
+                asset_symbol: row.asset_symbol,
+                kind: YieldKind::from_str(&row.kind)?,
+                date: row.timestamp,
+                apy: row.apy,
+                tvl_usd: row.tvl_usd,
+            },
+            risk_adjusted_apy: row.risk_adjusted_apy,
+            spread_over_dff: row.spread_over_dff,
+            spread_over_dgs2: row.spread_over_dgs2,
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::account_db::AccountDB;
+use crate::schema::accounts;
+
+/// Account repository
+pub struct AccountRepo;
+
+impl AccountRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &AccountDB) -> Result<usize, DieselError> {
+        insert_into(accounts::table)
+            .values(rec)
+            .on_conflict(accounts::id)
+            .do_update()
+            .set((
+                accounts::name.eq(excluded(accounts::name)),
+                accounts::venue.eq(excluded(accounts::venue)),
+            ))
+            .execute(conn)
+    }
+
+    pub async fn get(conn: &mut PgPooledConnection, id: Uuid) -> Result<Option<AccountDB>, DieselError> {
+        accounts::table
+            .find(id)
+            .first::<AccountDB>(conn)
+            .optional()
+    }
+
+    pub async fn for_portfolio(conn: &mut PgPooledConnection, portfolio_id: Uuid) -> Result<Vec<AccountDB>, DieselError> {
+        accounts::table
+            .filter(accounts::portfolio_id.eq(portfolio_id))
+            .order(accounts::created_at.asc())
+            .load::<AccountDB>(conn)
+    }
+}
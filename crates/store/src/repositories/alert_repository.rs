@@ -0,0 +1,93 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use domain::DeliveryStatus;
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::alert_db::{AlertDeliveryDB, AlertRuleDB};
+use crate::schema::{alert_deliveries, alert_rules};
+
+/// Alert rule repository
+pub struct AlertRuleRepo;
+
+impl AlertRuleRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &AlertRuleDB) -> Result<usize, DieselError> {
+        insert_into(alert_rules::table)
+            .values(rec)
+            .execute(conn)
+    }
+
+    pub async fn all(conn: &mut PgPooledConnection) -> Result<Vec<AlertRuleDB>, DieselError> {
+        alert_rules::table
+            .order(alert_rules::name.asc())
+            .load::<AlertRuleDB>(conn)
+    }
+
+    pub async fn enabled(conn: &mut PgPooledConnection) -> Result<Vec<AlertRuleDB>, DieselError> {
+        alert_rules::table
+            .filter(alert_rules::enabled.eq(true))
+            .order(alert_rules::name.asc())
+            .load::<AlertRuleDB>(conn)
+    }
+
+    pub async fn set_enabled(conn: &mut PgPooledConnection, id: Uuid, enabled: bool) -> Result<usize, DieselError> {
+        diesel::update(alert_rules::table.find(id))
+            .set(alert_rules::enabled.eq(enabled))
+            .execute(conn)
+    }
+
+    /// Removes the rule together with its delivery log.
+    pub async fn delete(conn: &mut PgPooledConnection, id: Uuid) -> Result<usize, DieselError> {
+        diesel::delete(alert_rules::table.find(id)).execute(conn)
+    }
+}
+
+/// Alert delivery log repository
+pub struct AlertDeliveryRepo;
+
+impl AlertDeliveryRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &AlertDeliveryDB) -> Result<usize, DieselError> {
+        insert_into(alert_deliveries::table)
+            .values(rec)
+            .execute(conn)
+    }
+
+    /// Whether the match was already sent or suppressed. Failed deliveries do not count, so they
+    /// are retried.
+    pub async fn is_settled(conn: &mut PgPooledConnection, rule_id: Uuid, dedup_key: &str) -> Result<bool, DieselError> {
+        let settled = [DeliveryStatus::Sent.as_str(), DeliveryStatus::Suppressed.as_str()];
+        diesel::select(diesel::dsl::exists(
+            alert_deliveries::table
+                .filter(alert_deliveries::rule_id.eq(rule_id))
+                .filter(alert_deliveries::dedup_key.eq(dedup_key))
+                .filter(alert_deliveries::status.eq_any(settled)),
+        ))
+        .get_result(conn)
+    }
+
+    /// When the rule last reached someone, for its cooldown.
+    pub async fn last_sent(conn: &mut PgPooledConnection, rule_id: Uuid) -> Result<Option<NaiveDateTime>, DieselError> {
+        alert_deliveries::table
+            .filter(alert_deliveries::rule_id.eq(rule_id))
+            .filter(alert_deliveries::status.eq(DeliveryStatus::Sent.as_str()))
+            .select(diesel::dsl::max(alert_deliveries::created_at))
+            .first::<Option<NaiveDateTime>>(conn)
+    }
+
+    /// Newest entries first, of every rule unless `rule_id` is given.
+    pub async fn latest_n(
+        conn: &mut PgPooledConnection,
+        rule_id: Option<Uuid>,
+        n: i64,
+    ) -> Result<Vec<AlertDeliveryDB>, DieselError> {
+        let mut query = alert_deliveries::table.into_boxed();
+        if let Some(rule_id) = rule_id {
+            query = query.filter(alert_deliveries::rule_id.eq(rule_id));
+        }
+        query
+            .order(alert_deliveries::created_at.desc())
+            .limit(n)
+            .load::<AlertDeliveryDB>(conn)
+    }
+}
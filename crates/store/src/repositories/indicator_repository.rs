@@ -1,11 +1,18 @@
+use std::collections::HashMap;
+
 use chrono::NaiveDate;
-use diesel::query_dsl::methods::{FilterDsl, OrderDsl};
+use diesel::query_dsl::methods::{FilterDsl, GroupByDsl, OrderDsl, SelectDsl};
 use diesel::upsert::excluded;
-use diesel::{ExpressionMethods, OptionalExtension, RunQueryDsl, insert_into};
+use diesel::{ExpressionMethods, OptionalExtension, RunQueryDsl, TextExpressionMethods, insert_into};
 use diesel::result::Error as DieselError;
+use domain::{MarketSymbol, SeriesMap};
 
 use crate::{db::PgPooledConnection, models::indicator_db::IndicatorDB, schema::indicators};
 
+/// Rows per statement of [`IndicatorRepo::insert_many`], well below Postgres' limit of 65535
+/// bind parameters.
+const INSERT_CHUNK: usize = 1000;
+
 /// Indicator repository
 pub struct IndicatorRepo;
 
@@ -19,6 +26,33 @@ impl IndicatorRepo {
             .execute(conn)
     }
 
+    /// Upserts like [`Self::insert`], a chunk of rows per statement.
+    pub fn insert_many(conn: &mut PgPooledConnection, recs: &[IndicatorDB]) -> Result<usize, DieselError> {
+        let mut inserted = 0;
+        for chunk in recs.chunks(INSERT_CHUNK) {
+            inserted += insert_into(indicators::table)
+                .values(chunk)
+                .on_conflict((indicators::name, indicators::timestamp))
+                .do_update()
+                .set(indicators::value.eq(excluded(indicators::value)))
+                .execute(conn)?;
+        }
+        Ok(inserted)
+    }
+
+    /// Date of the newest value of each of `names`. Names without values are left out.
+    pub fn latest_dates(
+        conn: &mut PgPooledConnection,
+        names: &[String],
+    ) -> Result<HashMap<String, NaiveDate>, DieselError> {
+        let rows = indicators::table
+            .filter(indicators::name.eq_any(names))
+            .group_by(indicators::name)
+            .select((indicators::name, diesel::dsl::max(indicators::timestamp)))
+            .load::<(String, Option<NaiveDate>)>(conn)?;
+        Ok(rows.into_iter().filter_map(|(name, date)| Some((name, date?))).collect())
+    }
+
     pub fn latest(conn: &mut PgPooledConnection, name: &str) -> Result<Option<IndicatorDB>, DieselError> {
         indicators::table
             .filter(indicators::name.eq(name))
@@ -40,4 +74,31 @@ impl IndicatorRepo {
             .order(indicators::timestamp.asc())
             .load::<IndicatorDB>(conn)
     }
+
+    /// Values of every indicator computed over each of `series`, keyed by indicator name, rows
+    /// without a value left out.
+    pub fn history(
+        conn: &mut PgPooledConnection,
+        series: &[MarketSymbol],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<SeriesMap, DieselError> {
+        let mut history = SeriesMap::new();
+        for symbol in series {
+            // Indicator names start with the series they are computed over, `_` is a wildcard.
+            let prefix = format!("{}\\_%", symbol.as_str().replace('_', "\\_"));
+            let rows = indicators::table
+                .filter(indicators::name.like(prefix))
+                .filter(indicators::timestamp.ge(from))
+                .filter(indicators::timestamp.le(to))
+                .order((indicators::name.asc(), indicators::timestamp.asc()))
+                .load::<IndicatorDB>(conn)?;
+            for row in rows {
+                if let Some(value) = row.value {
+                    history.entry(row.name).or_default().push((row.timestamp, value));
+                }
+            }
+        }
+        Ok(history)
+    }
 }
\ No newline at end of file
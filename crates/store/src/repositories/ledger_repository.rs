@@ -0,0 +1,85 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::ledger_transaction_db::LedgerTransactionDB;
+use crate::schema::{accounts, ledger_transactions};
+
+const INSERT_CHUNK: usize = 1000;
+
+/// Ledger transaction repository
+pub struct LedgerRepo;
+
+impl LedgerRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &LedgerTransactionDB) -> Result<usize, DieselError> {
+        insert_into(ledger_transactions::table)
+            .values(rec)
+            .on_conflict(ledger_transactions::id)
+            .do_update()
+            .set((
+                ledger_transactions::timestamp.eq(excluded(ledger_transactions::timestamp)),
+                ledger_transactions::kind.eq(excluded(ledger_transactions::kind)),
+                ledger_transactions::asset_symbol.eq(excluded(ledger_transactions::asset_symbol)),
+                ledger_transactions::quantity.eq(excluded(ledger_transactions::quantity)),
+                ledger_transactions::price_usd.eq(excluded(ledger_transactions::price_usd)),
+                ledger_transactions::fee_usd.eq(excluded(ledger_transactions::fee_usd)),
+                ledger_transactions::counterparty_account_id.eq(excluded(ledger_transactions::counterparty_account_id)),
+                ledger_transactions::note.eq(excluded(ledger_transactions::note)),
+            ))
+            .execute(conn)
+    }
+
+    /// Inserts the rows of one import in a single transaction, skipping those whose account
+    /// already has their `external_id`. Returns how many rows were new.
+    pub async fn insert_imported(conn: &mut PgPooledConnection, recs: &[LedgerTransactionDB]) -> Result<usize, DieselError> {
+        conn.transaction(|conn| {
+            let mut inserted = 0;
+            for chunk in recs.chunks(INSERT_CHUNK) {
+                inserted += insert_into(ledger_transactions::table)
+                    .values(chunk)
+                    .on_conflict((ledger_transactions::account_id, ledger_transactions::external_id))
+                    .do_nothing()
+                    .execute(conn)?;
+            }
+            Ok(inserted)
+        })
+    }
+
+    pub async fn delete(conn: &mut PgPooledConnection, id: Uuid) -> Result<usize, DieselError> {
+        diesel::delete(ledger_transactions::table.find(id)).execute(conn)
+    }
+
+    pub async fn for_account(conn: &mut PgPooledConnection, account_id: Uuid) -> Result<Vec<LedgerTransactionDB>, DieselError> {
+        ledger_transactions::table
+            .filter(ledger_transactions::account_id.eq(account_id))
+            .order(ledger_transactions::timestamp.asc())
+            .load::<LedgerTransactionDB>(conn)
+    }
+
+    /// All transactions booked on any account of the portfolio, oldest first.
+    pub async fn for_portfolio(conn: &mut PgPooledConnection, portfolio_id: Uuid) -> Result<Vec<LedgerTransactionDB>, DieselError> {
+        ledger_transactions::table
+            .inner_join(accounts::table.on(accounts::id.eq(ledger_transactions::account_id)))
+            .filter(accounts::portfolio_id.eq(portfolio_id))
+            .select(ledger_transactions::all_columns)
+            .order(ledger_transactions::timestamp.asc())
+            .load::<LedgerTransactionDB>(conn)
+    }
+
+    pub async fn for_portfolio_until(
+        conn: &mut PgPooledConnection,
+        portfolio_id: Uuid,
+        until: NaiveDateTime,
+    ) -> Result<Vec<LedgerTransactionDB>, DieselError> {
+        ledger_transactions::table
+            .inner_join(accounts::table.on(accounts::id.eq(ledger_transactions::account_id)))
+            .filter(accounts::portfolio_id.eq(portfolio_id))
+            .filter(ledger_transactions::timestamp.le(until))
+            .select(ledger_transactions::all_columns)
+            .order(ledger_transactions::timestamp.asc())
+            .load::<LedgerTransactionDB>(conn)
+    }
+}
@@ -3,7 +3,7 @@ use diesel::prelude::*;
 use diesel::dsl::insert_into;
 use diesel::result::Error as DieselError;
 use diesel::upsert::excluded;
-use domain::MarketSymbol;
+use domain::{MarketSymbol, PriceHistory, price_symbol};
 use crate::db::PgPooledConnection;
 use crate::models::market_data_db::MarketDataDB;
 use crate::schema::market_data;
@@ -49,4 +49,23 @@ impl MarketDataRepo {
             .order(market_data::timestamp.asc())
             .load::<MarketDataDB>(conn)
     }
-}
\ No newline at end of file
+
+    /// Closes for the given ledger assets, keyed by asset. Assets without a USD series are left out.
+    pub async fn price_history(
+        conn: &mut PgPooledConnection,
+        assets: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<PriceHistory, DieselError> {
+        let mut history = PriceHistory::new();
+        for asset in assets {
+            if history.contains_key(asset) {
+                continue;
+            }
+            let Some(symbol) = price_symbol(asset) else { continue };
+            let rows = Self::range_for_asset(conn, symbol.as_str(), from, to).await?;
+            history.insert(asset.clone(), rows.into_iter().map(|r| (r.timestamp, r.price_usd)).collect());
+        }
+        Ok(history)
+    }
+}
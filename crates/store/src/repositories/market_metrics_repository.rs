@@ -3,8 +3,10 @@ use diesel::prelude::*;
 use diesel::dsl::insert_into;
 use diesel::result::Error as DieselError;
 use diesel::upsert::excluded;
-use domain::MarketSymbol;
+use domain::{MarketDataSet, MarketSymbol, SeriesMap};
 use crate::db::PgPooledConnection;
+use crate::repositories::indicator_repository::IndicatorRepo;
+use crate::repositories::market_data_repository::MarketDataRepo;
 use crate::models::market_metrics_db::MarketMetricDataDB;
 use crate::schema::market_metrics;
 
@@ -65,4 +67,41 @@ impl MarketMetricRepo {
             .order(market_metrics::timestamp.asc())
             .load::<MarketMetricDataDB>(conn)
     }
+
+    /// Values of the given metrics keyed by metric name, rows without a value left out.
+    pub async fn history(
+        conn: &mut PgPooledConnection,
+        symbols: &[MarketSymbol],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<SeriesMap, DieselError> {
+        let mut history = SeriesMap::new();
+        for symbol in symbols {
+            let rows = Self::range(conn, symbol.as_str(), from, to).await?;
+            history.insert(
+                symbol.as_str().to_string(),
+                rows.into_iter().filter_map(|r| r.value.map(|v| (r.timestamp, v))).collect(),
+            );
+        }
+        Ok(history)
+    }
+
+    /// Everything `inputs` needs for a replay: price series as closes keyed by ledger asset along
+    /// with the indicators computed over them, the rest as metric values.
+    pub async fn market_data_set(
+        conn: &mut PgPooledConnection,
+        inputs: &[MarketSymbol],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<MarketDataSet, DieselError> {
+        let assets: Vec<String> = inputs.iter().filter_map(|s| s.ledger_asset()).map(str::to_string).collect();
+        let metrics: Vec<MarketSymbol> = inputs.iter().filter(|s| !s.is_price_series()).cloned().collect();
+        let series: Vec<MarketSymbol> = inputs.iter().filter(|s| s.is_price_series()).cloned().collect();
+
+        Ok(MarketDataSet {
+            prices: MarketDataRepo::price_history(conn, &assets, from, to).await?,
+            metrics: Self::history(conn, &metrics, from, to).await?,
+            indicators: IndicatorRepo::history(conn, &series, from, to)?,
+        })
+    }
 }
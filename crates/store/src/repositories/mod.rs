@@ -1,6 +1,14 @@
+pub mod alert_repository;
 pub mod market_data_repository;
 pub mod indicator_repository;
 pub mod market_metrics_repository;
 pub mod signal_repository;
+pub mod portfolio_repository;
+pub mod account_repository;
+pub mod ledger_repository;
+pub mod portfolio_nav_repository;
+pub mod portfolio_target_repository;
+pub mod user_repository;
+pub mod yield_repository;
 
-pub mod tests;
\ No newline at end of file
+pub mod tests;
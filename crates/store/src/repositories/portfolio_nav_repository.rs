@@ -0,0 +1,102 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::portfolio_nav_db::PortfolioNavDB;
+use crate::schema::portfolio_nav;
+
+const INSERT_CHUNK: usize = 1000;
+
+/// Portfolio NAV repository
+pub struct PortfolioNavRepo;
+
+impl PortfolioNavRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &PortfolioNavDB) -> Result<usize, DieselError> {
+        insert_into(portfolio_nav::table)
+            .values(rec)
+            .on_conflict((portfolio_nav::portfolio_id, portfolio_nav::timestamp))
+            .do_update()
+            .set((
+                portfolio_nav::nav_usd.eq(excluded(portfolio_nav::nav_usd)),
+                portfolio_nav::net_flow_usd.eq(excluded(portfolio_nav::net_flow_usd)),
+                portfolio_nav::forward_filled.eq(excluded(portfolio_nav::forward_filled)),
+            ))
+            .execute(conn)
+    }
+
+    /// Upserts like [`Self::insert`], a chunk of rows per statement.
+    pub async fn insert_many(conn: &mut PgPooledConnection, recs: &[PortfolioNavDB]) -> Result<usize, DieselError> {
+        let mut inserted = 0;
+        for chunk in recs.chunks(INSERT_CHUNK) {
+            inserted += insert_into(portfolio_nav::table)
+                .values(chunk)
+                .on_conflict((portfolio_nav::portfolio_id, portfolio_nav::timestamp))
+                .do_update()
+                .set((
+                    portfolio_nav::nav_usd.eq(excluded(portfolio_nav::nav_usd)),
+                    portfolio_nav::net_flow_usd.eq(excluded(portfolio_nav::net_flow_usd)),
+                    portfolio_nav::forward_filled.eq(excluded(portfolio_nav::forward_filled)),
+                ))
+                .execute(conn)?;
+        }
+        Ok(inserted)
+    }
+
+    /// Date of the newest stored NAV of the portfolio.
+    pub async fn latest_date(conn: &mut PgPooledConnection, portfolio_id: Uuid) -> Result<Option<NaiveDate>, DieselError> {
+        portfolio_nav::table
+            .filter(portfolio_nav::portfolio_id.eq(portfolio_id))
+            .select(diesel::dsl::max(portfolio_nav::timestamp))
+            .first(conn)
+    }
+
+    /// Drops the NAV from `from` onwards, so the next run recomputes it after a back-dated
+    /// ledger change.
+    pub async fn delete_from(conn: &mut PgPooledConnection, portfolio_id: Uuid, from: NaiveDate) -> Result<usize, DieselError> {
+        diesel::delete(
+            portfolio_nav::table
+                .filter(portfolio_nav::portfolio_id.eq(portfolio_id))
+                .filter(portfolio_nav::timestamp.ge(from)),
+        )
+        .execute(conn)
+    }
+
+    pub async fn latest_n(
+        conn: &mut PgPooledConnection,
+        portfolio_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<PortfolioNavDB>, DieselError> {
+        portfolio_nav::table
+            .filter(portfolio_nav::portfolio_id.eq(portfolio_id))
+            .order(portfolio_nav::timestamp.desc())
+            .limit(limit)
+            .load::<PortfolioNavDB>(conn)
+    }
+
+    pub async fn for_portfolio(
+        conn: &mut PgPooledConnection,
+        portfolio_id: Uuid,
+    ) -> Result<Vec<PortfolioNavDB>, DieselError> {
+        portfolio_nav::table
+            .filter(portfolio_nav::portfolio_id.eq(portfolio_id))
+            .order(portfolio_nav::timestamp.asc())
+            .load::<PortfolioNavDB>(conn)
+    }
+
+    pub async fn range(
+        conn: &mut PgPooledConnection,
+        portfolio_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PortfolioNavDB>, DieselError> {
+        portfolio_nav::table
+            .filter(portfolio_nav::portfolio_id.eq(portfolio_id))
+            .filter(portfolio_nav::timestamp.ge(from))
+            .filter(portfolio_nav::timestamp.le(to))
+            .order(portfolio_nav::timestamp.asc())
+            .load::<PortfolioNavDB>(conn)
+    }
+}
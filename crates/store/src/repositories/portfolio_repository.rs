@@ -0,0 +1,86 @@
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+use domain::{CostBasisMethod, Jurisdiction};
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::portfolio_db::PortfolioDB;
+use crate::schema::portfolios;
+
+/// Portfolio repository
+pub struct PortfolioRepo;
+
+impl PortfolioRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &PortfolioDB) -> Result<usize, DieselError> {
+        insert_into(portfolios::table)
+            .values(rec)
+            .on_conflict(portfolios::id)
+            .do_update()
+            .set((
+                portfolios::name.eq(excluded(portfolios::name)),
+                portfolios::cost_basis_method.eq(excluded(portfolios::cost_basis_method)),
+                portfolios::tax_jurisdiction.eq(excluded(portfolios::tax_jurisdiction)),
+            ))
+            .execute(conn)
+    }
+
+    pub async fn get(conn: &mut PgPooledConnection, id: Uuid) -> Result<Option<PortfolioDB>, DieselError> {
+        portfolios::table
+            .find(id)
+            .first::<PortfolioDB>(conn)
+            .optional()
+    }
+
+    pub async fn set_cost_basis_method(
+        conn: &mut PgPooledConnection,
+        id: Uuid,
+        method: CostBasisMethod,
+    ) -> Result<usize, DieselError> {
+        diesel::update(portfolios::table.find(id))
+            .set(portfolios::cost_basis_method.eq(method.as_str()))
+            .execute(conn)
+    }
+
+    pub async fn set_tax_jurisdiction(
+        conn: &mut PgPooledConnection,
+        id: Uuid,
+        jurisdiction: Jurisdiction,
+    ) -> Result<usize, DieselError> {
+        diesel::update(portfolios::table.find(id))
+            .set(portfolios::tax_jurisdiction.eq(jurisdiction.as_str()))
+            .execute(conn)
+    }
+
+    /// Portfolio `id` if it belongs to `owner_id`.
+    pub async fn get_owned(conn: &mut PgPooledConnection, id: Uuid, owner_id: Uuid) -> Result<Option<PortfolioDB>, DieselError> {
+        portfolios::table
+            .filter(portfolios::id.eq(id))
+            .filter(portfolios::owner_id.eq(owner_id))
+            .first::<PortfolioDB>(conn)
+            .optional()
+    }
+
+    pub async fn for_owner(conn: &mut PgPooledConnection, owner_id: Uuid) -> Result<Vec<PortfolioDB>, DieselError> {
+        portfolios::table
+            .filter(portfolios::owner_id.eq(owner_id))
+            .order(portfolios::created_at.asc())
+            .load::<PortfolioDB>(conn)
+    }
+
+    pub async fn set_owner(
+        conn: &mut PgPooledConnection,
+        id: Uuid,
+        owner_id: Option<Uuid>,
+    ) -> Result<usize, DieselError> {
+        diesel::update(portfolios::table.find(id))
+            .set(portfolios::owner_id.eq(owner_id))
+            .execute(conn)
+    }
+
+    pub async fn all(conn: &mut PgPooledConnection) -> Result<Vec<PortfolioDB>, DieselError> {
+        portfolios::table
+            .order(portfolios::created_at.asc())
+            .load::<PortfolioDB>(conn)
+    }
+}
@@ -0,0 +1,38 @@
+use diesel::prelude::*;
+use diesel::dsl::{delete, insert_into};
+use diesel::result::Error as DieselError;
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::portfolio_target_db::PortfolioTargetDB;
+use crate::schema::portfolio_targets;
+
+/// Portfolio target allocation repository
+pub struct PortfolioTargetRepo;
+
+impl PortfolioTargetRepo {
+    /// Replaces the whole target allocation of a portfolio in one transaction.
+    pub async fn replace(
+        conn: &mut PgPooledConnection,
+        portfolio_id: Uuid,
+        recs: &[PortfolioTargetDB],
+    ) -> Result<usize, DieselError> {
+        conn.transaction(|conn| {
+            delete(portfolio_targets::table.filter(portfolio_targets::portfolio_id.eq(portfolio_id)))
+                .execute(conn)?;
+
+            insert_into(portfolio_targets::table)
+                .values(recs)
+                .execute(conn)
+        })
+    }
+
+    pub async fn for_portfolio(
+        conn: &mut PgPooledConnection,
+        portfolio_id: Uuid,
+    ) -> Result<Vec<PortfolioTargetDB>, DieselError> {
+        portfolio_targets::table
+            .filter(portfolio_targets::portfolio_id.eq(portfolio_id))
+            .order(portfolio_targets::bucket.asc())
+            .load::<PortfolioTargetDB>(conn)
+    }
+}
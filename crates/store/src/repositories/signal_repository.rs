@@ -6,6 +6,7 @@ use crate::db::PgPooledConnection;
 use crate::models::signal_db::{NewStrategySignalDB, StrategySignalDB};
 use crate::schema::strategy_signals;
 use chrono::NaiveDateTime;
+use uuid::Uuid;
 
 /// Signals repository
 pub struct SignalsRepo;
@@ -20,10 +21,42 @@ impl SignalsRepo {
                 strategy_signals::value.eq(excluded(strategy_signals::value)),
                 strategy_signals::description.eq(excluded(strategy_signals::description)),
                 strategy_signals::source.eq(excluded(strategy_signals::source)),
+                strategy_signals::inputs.eq(excluded(strategy_signals::inputs)),
             ))
             .execute(conn)
     }
     
+    pub fn get_by_id(conn: &mut PgPooledConnection, id: Uuid) -> Result<Option<StrategySignalDB>, DieselError> {
+        strategy_signals::table
+            .find(id)
+            .first::<StrategySignalDB>(conn)
+            .optional()
+    }
+
+    /// Newest signals first, of every asset unless `asset` is given.
+    pub fn latest_n(
+        conn: &mut PgPooledConnection,
+        asset: Option<&str>,
+        n: i64,
+    ) -> Result<Vec<StrategySignalDB>, DieselError> {
+        let mut query = strategy_signals::table.into_boxed();
+        if let Some(asset) = asset {
+            query = query.filter(strategy_signals::asset_symbol.eq(asset));
+        }
+        query
+            .order(strategy_signals::timestamp.desc())
+            .limit(n)
+            .load::<StrategySignalDB>(conn)
+    }
+
+    /// Signals dated `from` or later, oldest first.
+    pub fn since(conn: &mut PgPooledConnection, from: NaiveDateTime) -> Result<Vec<StrategySignalDB>, DieselError> {
+        strategy_signals::table
+            .filter(strategy_signals::timestamp.ge(from))
+            .order(strategy_signals::timestamp.asc())
+            .load::<StrategySignalDB>(conn)
+    }
+
     pub fn latest_for_asset_and_type(
         conn: &mut PgPooledConnection,
         asset: &str,
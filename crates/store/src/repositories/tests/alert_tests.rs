@@ -0,0 +1,72 @@
+use chrono::{Duration, Utc};
+use domain::{AlertRule, DeliveryStatus};
+use uuid::Uuid;
+
+use crate::{models::alert_db::{AlertDeliveryDB, AlertRuleDB}, repositories::alert_repository::{AlertDeliveryRepo, AlertRuleRepo}};
+
+use super::establish_test_pool;
+
+fn create_rule(condition: &str) -> AlertRuleDB {
+    AlertRuleDB {
+        id: Uuid::new_v4(),
+        name: format!("rule-{}", Uuid::new_v4().simple()),
+        condition: condition.to_string(),
+        channel: "WEBHOOK".to_string(),
+        target: "http://localhost:9000/hook".to_string(),
+        cooldown_minutes: 60,
+        enabled: true,
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+fn create_delivery(rule_id: Uuid, dedup_key: &str, status: DeliveryStatus) -> AlertDeliveryDB {
+    AlertDeliveryDB {
+        id: Uuid::new_v4(),
+        rule_id,
+        dedup_key: dedup_key.to_string(),
+        status: status.as_str().to_string(),
+        subject: "test".to_string(),
+        error: None,
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+#[tokio::test]
+async fn test_enabled_rules_parse_back() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let rule = create_rule("FEAR_GREED_INDEX below 15");
+    AlertRuleRepo::insert(&mut conn, &rule).await.unwrap();
+
+    let stored = AlertRuleRepo::enabled(&mut conn).await.unwrap().into_iter().find(|r| r.id == rule.id).unwrap();
+    let parsed = AlertRule::try_from(stored).unwrap();
+    assert_eq!(parsed.condition.to_string(), "FEAR_GREED_INDEX below 15");
+
+    AlertRuleRepo::set_enabled(&mut conn, rule.id, false).await.unwrap();
+    assert!(AlertRuleRepo::enabled(&mut conn).await.unwrap().iter().all(|r| r.id != rule.id));
+    assert_eq!(AlertRuleRepo::delete(&mut conn, rule.id).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_are_not_settled() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let rule = create_rule("BTC_DOMINANCE crosses 0.6");
+    AlertRuleRepo::insert(&mut conn, &rule).await.unwrap();
+
+    AlertDeliveryRepo::insert(&mut conn, &create_delivery(rule.id, "2024-03-01", DeliveryStatus::Failed)).await.unwrap();
+    assert!(!AlertDeliveryRepo::is_settled(&mut conn, rule.id, "2024-03-01").await.unwrap());
+    assert_eq!(AlertDeliveryRepo::last_sent(&mut conn, rule.id).await.unwrap(), None);
+
+    let mut sent = create_delivery(rule.id, "2024-03-01", DeliveryStatus::Sent);
+    sent.created_at -= Duration::minutes(5);
+    AlertDeliveryRepo::insert(&mut conn, &sent).await.unwrap();
+    assert!(AlertDeliveryRepo::is_settled(&mut conn, rule.id, "2024-03-01").await.unwrap());
+    assert!(AlertDeliveryRepo::last_sent(&mut conn, rule.id).await.unwrap().is_some());
+
+    let log = AlertDeliveryRepo::latest_n(&mut conn, Some(rule.id), 10).await.unwrap();
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].status, "FAILED");
+}
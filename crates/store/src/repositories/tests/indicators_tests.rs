@@ -72,3 +72,20 @@ fn test_upsert_indicator() {
     assert_eq!(fetched.len(), 1);
     assert_eq!(fetched[0].value, Some(58.0));
 }
+
+#[test]
+fn test_insert_many_and_latest_dates() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    // A name of its own, so the latest RSI of the other tests is left alone.
+    let recs: Vec<IndicatorDB> = (1..=3)
+        .map(|d| IndicatorDB { name: "RSI_TEST_INSERT_MANY".to_string(), ..create_record((2024, 11, d), 50.0 + d as f64) })
+        .collect();
+    assert_eq!(IndicatorRepo::insert_many(&mut conn, &recs).unwrap(), 3);
+
+    let names = ["RSI_TEST_INSERT_MANY".to_string(), "UNKNOWN".to_string()];
+    let latest = IndicatorRepo::latest_dates(&mut conn, &names).unwrap();
+    assert_eq!(latest["RSI_TEST_INSERT_MANY"], NaiveDate::from_ymd_opt(2024, 11, 3).unwrap());
+    assert!(!latest.contains_key("UNKNOWN"));
+}
@@ -0,0 +1,119 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::{models::ledger_transaction_db::LedgerTransactionDB, repositories::{account_repository::AccountRepo, ledger_repository::LedgerRepo, portfolio_repository::PortfolioRepo}};
+
+use super::{establish_test_pool, portfolio_tests::{create_account, create_portfolio}};
+
+fn at(date: (i32, u32, u32)) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap().and_hms_opt(12, 0, 0).unwrap()
+}
+
+fn create_tx(account_id: Uuid, kind: &str, date: (i32, u32, u32), quantity: f64, price: Option<f64>) -> LedgerTransactionDB {
+    LedgerTransactionDB {
+        id: Uuid::new_v4(),
+        account_id,
+        timestamp: at(date),
+        kind: kind.to_string(),
+        asset_symbol: "BTC".to_string(),
+        quantity,
+        price_usd: price,
+        fee_usd: None,
+        counterparty_account_id: None,
+        note: None,
+        external_id: None,
+    }
+}
+
+#[tokio::test]
+async fn test_insert_and_load_portfolio_ledger() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("Ledger");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+    let account = create_account(portfolio.id, "Spot");
+    AccountRepo::insert(&mut conn, &account).await.unwrap();
+
+    let buy = create_tx(account.id, "BUY", (2024, 10, 1), 0.5, Some(60_000.0));
+    let sell = create_tx(account.id, "SELL", (2024, 10, 5), 0.2, Some(65_000.0));
+    LedgerRepo::insert(&mut conn, &sell).await.unwrap();
+    LedgerRepo::insert(&mut conn, &buy).await.unwrap();
+
+    let all = LedgerRepo::for_portfolio(&mut conn, portfolio.id).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].id, buy.id);
+    assert_eq!(all[1].id, sell.id);
+
+    let until = LedgerRepo::for_portfolio_until(&mut conn, portfolio.id, at((2024, 10, 2))).await.unwrap();
+    assert_eq!(until.len(), 1);
+    assert_eq!(until[0].kind, "BUY");
+}
+
+#[tokio::test]
+async fn test_upsert_ledger_transaction() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("Upsert");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+    let account = create_account(portfolio.id, "Spot");
+    AccountRepo::insert(&mut conn, &account).await.unwrap();
+
+    let buy = create_tx(account.id, "BUY", (2024, 10, 1), 0.5, Some(60_000.0));
+    LedgerRepo::insert(&mut conn, &buy).await.unwrap();
+
+    let corrected = LedgerTransactionDB { quantity: 0.75, ..buy.clone() };
+    LedgerRepo::insert(&mut conn, &corrected).await.unwrap();
+
+    let fetched = LedgerRepo::for_account(&mut conn, account.id).await.unwrap();
+    assert_eq!(fetched.len(), 1);
+    assert_eq!(fetched[0].quantity, 0.75);
+}
+
+#[tokio::test]
+async fn test_reimport_is_skipped() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("Import");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+    let account = create_account(portfolio.id, "Kraken");
+    AccountRepo::insert(&mut conn, &account).await.unwrap();
+
+    let imported = LedgerTransactionDB {
+        external_id: Some("kraken:TX-1".to_string()),
+        ..create_tx(account.id, "BUY", (2024, 10, 1), 0.5, Some(60_000.0))
+    };
+    let again = LedgerTransactionDB { id: Uuid::new_v4(), ..imported.clone() };
+
+    assert_eq!(LedgerRepo::insert_imported(&mut conn, std::slice::from_ref(&imported)).await.unwrap(), 1);
+    assert_eq!(LedgerRepo::insert_imported(&mut conn, &[again]).await.unwrap(), 0);
+
+    let fetched = LedgerRepo::for_account(&mut conn, account.id).await.unwrap();
+    assert_eq!(fetched.len(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_import_stores_nothing() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("Import");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+    let account = create_account(portfolio.id, "Binance");
+    AccountRepo::insert(&mut conn, &account).await.unwrap();
+
+    let valid = LedgerTransactionDB {
+        external_id: Some("binance:ok".to_string()),
+        ..create_tx(account.id, "BUY", (2024, 10, 1), 0.5, Some(60_000.0))
+    };
+    let too_long = LedgerTransactionDB {
+        asset_symbol: "X".repeat(40),
+        external_id: Some("binance:too-long".to_string()),
+        ..create_tx(account.id, "BUY", (2024, 10, 2), 0.5, Some(60_000.0))
+    };
+
+    assert!(LedgerRepo::insert_imported(&mut conn, &[valid, too_long]).await.is_err());
+    assert!(LedgerRepo::for_account(&mut conn, account.id).await.unwrap().is_empty());
+}
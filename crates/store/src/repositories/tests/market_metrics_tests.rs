@@ -1,6 +1,8 @@
 use chrono::NaiveDate;
 
-use crate::{models::market_metrics_db::MarketMetricDataDB, repositories::{market_metrics_repository::MarketMetricRepo, tests::establish_test_pool}};
+use domain::MarketSymbol;
+
+use crate::{models::{indicator_db::IndicatorDB, market_metrics_db::MarketMetricDataDB}, repositories::{indicator_repository::IndicatorRepo, market_metrics_repository::MarketMetricRepo, tests::establish_test_pool}};
 
 
 fn create_record(date: (i32, u32, u32), value: f64) -> MarketMetricDataDB {
@@ -71,3 +73,29 @@ async fn test_upsert_indicator() {
     assert_eq!(fetched.len(), 1);
     assert_eq!(fetched[0].value, Some(58.0));
 }
+
+#[tokio::test]
+async fn test_market_data_set_loads_indicators_of_price_inputs() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let day = |d: u32| NaiveDate::from_ymd_opt(2031, 3, d).unwrap();
+    let indicator = |name: &str, d: u32, value: f64| IndicatorDB {
+        name: name.to_string(),
+        category: "momentum".to_string(),
+        timestamp: day(d),
+        value: Some(value),
+        source: Some("computed".to_string()),
+    };
+    IndicatorRepo::insert_many(&mut conn, &[
+        indicator("BTC_USD_RSI_14", 1, 40.0),
+        indicator("BTC_USD_RSI_14", 2, 45.0),
+        indicator("ETH_USD_RSI_14", 2, 60.0),
+    ]).unwrap();
+
+    let data = MarketMetricRepo::market_data_set(&mut conn, &[MarketSymbol::BtcUsd], day(1), day(2)).await.unwrap();
+
+    assert_eq!(data.indicators["BTC_USD_RSI_14"], vec![(day(1), 40.0), (day(2), 45.0)]);
+    assert!(!data.indicators.contains_key("ETH_USD_RSI_14"));
+    assert_eq!(data.at(day(2)).indicator("BTC_USD_RSI_14"), Some(45.0));
+}
@@ -1,4 +1,6 @@
 #[cfg(test)]
+pub mod alert_tests;
+#[cfg(test)]
 pub mod indicators_tests;
 #[cfg(test)]
 pub mod market_data_tests;
@@ -6,6 +8,18 @@ pub mod market_data_tests;
 pub mod market_metrics_tests;
 #[cfg(test)]
 pub mod signal_tests;
+#[cfg(test)]
+pub mod portfolio_tests;
+#[cfg(test)]
+pub mod ledger_tests;
+#[cfg(test)]
+pub mod portfolio_nav_tests;
+#[cfg(test)]
+pub mod portfolio_target_tests;
+#[cfg(test)]
+pub mod user_tests;
+#[cfg(test)]
+pub mod yield_tests;
 
 use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
 use diesel::pg::PgConnection;
@@ -0,0 +1,82 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::{models::portfolio_nav_db::PortfolioNavDB, repositories::{portfolio_nav_repository::PortfolioNavRepo, portfolio_repository::PortfolioRepo}};
+
+use super::{establish_test_pool, portfolio_tests::create_portfolio};
+
+fn create_nav(portfolio_id: Uuid, date: (i32, u32, u32), nav: f64) -> PortfolioNavDB {
+    PortfolioNavDB {
+        portfolio_id,
+        timestamp: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+        nav_usd: nav,
+        net_flow_usd: 0.0,
+        forward_filled: false,
+    }
+}
+
+#[tokio::test]
+async fn test_range_portfolio_nav() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("NAV");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+
+    let rec1 = create_nav(portfolio.id, (2024, 10, 1), 10_000.0);
+    let rec2 = create_nav(portfolio.id, (2024, 10, 2), 10_500.0);
+    PortfolioNavRepo::insert(&mut conn, &rec1).await.unwrap();
+    PortfolioNavRepo::insert(&mut conn, &rec2).await.unwrap();
+
+    let range = PortfolioNavRepo::range(&mut conn, portfolio.id, rec1.timestamp, rec2.timestamp).await.unwrap();
+    assert_eq!(range.len(), 2);
+    assert_eq!(range[1].nav_usd, 10_500.0);
+
+    let latest = PortfolioNavRepo::latest_n(&mut conn, portfolio.id, 1).await.unwrap();
+    assert_eq!(latest[0].timestamp, rec2.timestamp);
+
+    let all = PortfolioNavRepo::for_portfolio(&mut conn, portfolio.id).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].timestamp, rec1.timestamp);
+}
+
+#[tokio::test]
+async fn test_upsert_portfolio_nav() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("NAV upsert");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+
+    let rec = create_nav(portfolio.id, (2024, 10, 1), 10_000.0);
+    PortfolioNavRepo::insert(&mut conn, &rec).await.unwrap();
+
+    let updated = PortfolioNavDB { nav_usd: 9_000.0, forward_filled: true, ..rec.clone() };
+    PortfolioNavRepo::insert(&mut conn, &updated).await.unwrap();
+
+    let fetched = PortfolioNavRepo::range(&mut conn, portfolio.id, rec.timestamp, rec.timestamp).await.unwrap();
+    assert_eq!(fetched.len(), 1);
+    assert_eq!(fetched[0].nav_usd, 9_000.0);
+    assert!(fetched[0].forward_filled);
+}
+
+#[tokio::test]
+async fn test_insert_many_resume_and_invalidate_portfolio_nav() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("NAV batch");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+    assert_eq!(PortfolioNavRepo::latest_date(&mut conn, portfolio.id).await.unwrap(), None);
+
+    let recs: Vec<PortfolioNavDB> = (1..=3).map(|d| create_nav(portfolio.id, (2024, 10, d), 10_000.0)).collect();
+    assert_eq!(PortfolioNavRepo::insert_many(&mut conn, &recs).await.unwrap(), 3);
+    let updated = PortfolioNavDB { nav_usd: 11_000.0, ..recs[2].clone() };
+    PortfolioNavRepo::insert_many(&mut conn, &[updated]).await.unwrap();
+
+    assert_eq!(PortfolioNavRepo::latest_date(&mut conn, portfolio.id).await.unwrap(), Some(recs[2].timestamp));
+    assert_eq!(PortfolioNavRepo::latest_n(&mut conn, portfolio.id, 1).await.unwrap()[0].nav_usd, 11_000.0);
+
+    assert_eq!(PortfolioNavRepo::delete_from(&mut conn, portfolio.id, recs[1].timestamp).await.unwrap(), 2);
+    assert_eq!(PortfolioNavRepo::latest_date(&mut conn, portfolio.id).await.unwrap(), Some(recs[0].timestamp));
+}
@@ -0,0 +1,34 @@
+use uuid::Uuid;
+
+use crate::{models::portfolio_target_db::PortfolioTargetDB, repositories::{portfolio_repository::PortfolioRepo, portfolio_target_repository::PortfolioTargetRepo}};
+
+use super::{establish_test_pool, portfolio_tests::create_portfolio};
+
+fn create_target(portfolio_id: Uuid, bucket: &str, weight: f64) -> PortfolioTargetDB {
+    PortfolioTargetDB {
+        portfolio_id,
+        bucket: bucket.to_string(),
+        weight,
+        band: 0.05,
+    }
+}
+
+#[tokio::test]
+async fn test_replace_portfolio_targets() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("Targets");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+
+    let first = vec![create_target(portfolio.id, "BTC", 0.6), create_target(portfolio.id, "USD", 0.4)];
+    PortfolioTargetRepo::replace(&mut conn, portfolio.id, &first).await.unwrap();
+
+    let second = vec![create_target(portfolio.id, "CLASS:CRYPTO", 0.7), create_target(portfolio.id, "CLASS:CASH", 0.3)];
+    PortfolioTargetRepo::replace(&mut conn, portfolio.id, &second).await.unwrap();
+
+    let fetched = PortfolioTargetRepo::for_portfolio(&mut conn, portfolio.id).await.unwrap();
+    assert_eq!(fetched.len(), 2);
+    assert_eq!(fetched[0].bucket, "CLASS:CASH");
+    assert_eq!(fetched[1].weight, 0.7);
+}
@@ -0,0 +1,85 @@
+use chrono::Utc;
+use domain::{CostBasisMethod, Jurisdiction};
+use uuid::Uuid;
+
+use crate::{models::{account_db::AccountDB, portfolio_db::PortfolioDB}, repositories::{account_repository::AccountRepo, portfolio_repository::PortfolioRepo}};
+
+use super::establish_test_pool;
+
+pub fn create_portfolio(name: &str) -> PortfolioDB {
+    PortfolioDB {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        created_at: Utc::now().naive_utc(),
+        cost_basis_method: "FIFO".to_string(),
+        tax_jurisdiction: "US".to_string(),
+        owner_id: None,
+    }
+}
+
+pub fn create_account(portfolio_id: Uuid, name: &str) -> AccountDB {
+    AccountDB {
+        id: Uuid::new_v4(),
+        portfolio_id,
+        name: name.to_string(),
+        venue: Some("binance".to_string()),
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+#[tokio::test]
+async fn test_insert_and_get_portfolio() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let rec = create_portfolio("Long term");
+    let inserted = PortfolioRepo::insert(&mut conn, &rec).await.expect("insert failed");
+    assert_eq!(inserted, 1);
+
+    let fetched = PortfolioRepo::get(&mut conn, rec.id).await.unwrap().unwrap();
+    assert_eq!(fetched.name, "Long term");
+}
+
+#[tokio::test]
+async fn test_set_cost_basis_method() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let rec = create_portfolio("Germany");
+    PortfolioRepo::insert(&mut conn, &rec).await.unwrap();
+    PortfolioRepo::set_cost_basis_method(&mut conn, rec.id, CostBasisMethod::Hifo).await.unwrap();
+
+    let fetched = PortfolioRepo::get(&mut conn, rec.id).await.unwrap().unwrap();
+    assert_eq!(fetched.cost_basis_method, "HIFO");
+}
+
+#[tokio::test]
+async fn test_set_tax_jurisdiction() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let rec = create_portfolio("Steuer");
+    PortfolioRepo::insert(&mut conn, &rec).await.unwrap();
+    PortfolioRepo::set_tax_jurisdiction(&mut conn, rec.id, Jurisdiction::De).await.unwrap();
+
+    let fetched = PortfolioRepo::get(&mut conn, rec.id).await.unwrap().unwrap();
+    assert_eq!(fetched.tax_jurisdiction, "DE");
+}
+
+#[tokio::test]
+async fn test_accounts_for_portfolio() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let portfolio = create_portfolio("Accounts");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+
+    let acc1 = create_account(portfolio.id, "Spot");
+    let acc2 = create_account(portfolio.id, "Cold wallet");
+    AccountRepo::insert(&mut conn, &acc1).await.unwrap();
+    AccountRepo::insert(&mut conn, &acc2).await.unwrap();
+
+    let accounts = AccountRepo::for_portfolio(&mut conn, portfolio.id).await.unwrap();
+    assert_eq!(accounts.len(), 2);
+    assert!(accounts.iter().all(|a| a.portfolio_id == portfolio.id));
+}
@@ -5,10 +5,12 @@ use super::establish_test_pool;
 fn create_signal(asset: &str, signal_type: &str, value: f64) -> NewStrategySignalDB {
     NewStrategySignalDB {
         asset_symbol: asset.to_string(),
+        timestamp: None,
         signal_type: signal_type.to_string(),
         value: Some(value),
         description: Some("Test signal".to_string()),
         source: Some("model_v1".to_string()),
+        inputs: None,
     }
 }
 
@@ -98,10 +100,12 @@ fn test_upsert_signal() {
 
     let upsert_insertable = NewStrategySignalDB {
         asset_symbol: upsert_rec.asset_symbol.clone(),
+        timestamp: Some(upsert_rec.timestamp),
         signal_type: upsert_rec.signal_type.clone(),
         value: Some(1.5),
         description: upsert_rec.description.clone(),
         source: upsert_rec.source.clone(),
+        inputs: Some(serde_json::json!({ "rule_version": "2", "inputs": { "close": 90.0 }, "thresholds": {} })),
     };
 
     SignalsRepo::insert(&mut conn, &upsert_insertable).unwrap();
@@ -112,4 +116,24 @@ fn test_upsert_signal() {
         .unwrap();
 
     assert_eq!(updated.value, Some(1.5));
+    assert_eq!(updated.inputs.unwrap()["rule_version"], "2");
+
+    let by_id = SignalsRepo::get_by_id(&mut conn, updated.id).unwrap().unwrap();
+    assert_eq!(by_id.value, Some(1.5));
+}
+
+#[test]
+fn test_latest_n_signals() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    SignalsRepo::insert(&mut conn, &create_signal("BTC", "BUY", 1.0)).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    SignalsRepo::insert(&mut conn, &create_signal("BTC", "SELL", 2.0)).unwrap();
+
+    let latest = SignalsRepo::latest_n(&mut conn, Some("BTC"), 2).unwrap();
+    assert_eq!(latest.len(), 2);
+    assert_eq!(latest[0].signal_type, "SELL");
+    assert!(latest.iter().all(|s| s.asset_symbol == "BTC"));
+    assert_eq!(SignalsRepo::latest_n(&mut conn, None, 1).unwrap().len(), 1);
 }
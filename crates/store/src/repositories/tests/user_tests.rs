@@ -0,0 +1,69 @@
+use chrono::Utc;
+use domain::GeneratedApiKey;
+use uuid::Uuid;
+
+use crate::{models::user_db::{ApiKeyDB, UserDB}, repositories::{portfolio_repository::PortfolioRepo, user_repository::{ApiKeyRepo, UserRepo}}};
+
+use super::{establish_test_pool, portfolio_tests::create_portfolio};
+
+pub fn create_user(name: &str) -> UserDB {
+    UserDB {
+        id: Uuid::new_v4(),
+        name: format!("{}-{}", name, Uuid::new_v4()),
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+pub fn create_api_key(user_id: Uuid, generated: &GeneratedApiKey) -> ApiKeyDB {
+    ApiKeyDB {
+        id: Uuid::new_v4(),
+        user_id,
+        key_hash: generated.hash.clone(),
+        prefix: generated.prefix.clone(),
+        label: Some("test".to_string()),
+        created_at: Utc::now().naive_utc(),
+        last_used_at: None,
+        revoked_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_and_revoke_api_key() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let user = create_user("alice");
+    UserRepo::insert(&mut conn, &user).await.unwrap();
+    let generated = GeneratedApiKey::generate();
+    let key = create_api_key(user.id, &generated);
+    ApiKeyRepo::insert(&mut conn, &key).await.unwrap();
+
+    let (_, owner) = ApiKeyRepo::user_for_hash(&mut conn, &generated.hash).await.unwrap().unwrap();
+    assert_eq!(owner.id, user.id);
+
+    let revoked = ApiKeyRepo::revoke(&mut conn, key.id, Uuid::new_v4(), Utc::now().naive_utc()).await.unwrap();
+    assert_eq!(revoked, 0);
+    let revoked = ApiKeyRepo::revoke(&mut conn, key.id, user.id, Utc::now().naive_utc()).await.unwrap();
+    assert_eq!(revoked, 1);
+    assert!(ApiKeyRepo::user_for_hash(&mut conn, &generated.hash).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_portfolios_are_scoped_to_owner() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let alice = create_user("alice");
+    let bob = create_user("bob");
+    UserRepo::insert(&mut conn, &alice).await.unwrap();
+    UserRepo::insert(&mut conn, &bob).await.unwrap();
+
+    let portfolio = create_portfolio("Alice's");
+    PortfolioRepo::insert(&mut conn, &portfolio).await.unwrap();
+    PortfolioRepo::set_owner(&mut conn, portfolio.id, Some(alice.id)).await.unwrap();
+
+    assert!(PortfolioRepo::get_owned(&mut conn, portfolio.id, alice.id).await.unwrap().is_some());
+    assert!(PortfolioRepo::get_owned(&mut conn, portfolio.id, bob.id).await.unwrap().is_none());
+    assert_eq!(PortfolioRepo::for_owner(&mut conn, alice.id).await.unwrap().len(), 1);
+    assert!(PortfolioRepo::for_owner(&mut conn, bob.id).await.unwrap().is_empty());
+}
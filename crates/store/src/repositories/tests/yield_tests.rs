@@ -0,0 +1,40 @@
+use chrono::NaiveDate;
+
+use crate::{models::yield_db::YieldDB, repositories::yield_repository::YieldRepo};
+
+use super::establish_test_pool;
+
+fn create_yield(protocol: &str, date: NaiveDate, apy: f64) -> YieldDB {
+    YieldDB {
+        source: "test".to_string(),
+        protocol: protocol.to_string(),
+        asset_symbol: "TESTUSD".to_string(),
+        kind: "LENDING".to_string(),
+        timestamp: date,
+        apy,
+        risk_adjusted_apy: apy * 0.9,
+        tvl_usd: Some(1_000_000.0),
+        spread_over_dff: Some(apy * 0.9 - 5.0),
+        spread_over_dgs2: None,
+    }
+}
+
+#[tokio::test]
+async fn test_insert_upserts_and_finds_previous() {
+    let pool = establish_test_pool();
+    let mut conn = pool.get().unwrap();
+
+    let monday = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+    let tuesday = NaiveDate::from_ymd_opt(2024, 6, 4).unwrap();
+    YieldRepo::insert(&mut conn, &create_yield("upsert", monday, 4.0)).await.unwrap();
+    YieldRepo::insert(&mut conn, &create_yield("upsert", monday, 6.0)).await.unwrap();
+    let today = create_yield("upsert", tuesday, 7.0);
+    YieldRepo::insert(&mut conn, &today).await.unwrap();
+
+    let previous = YieldRepo::previous(&mut conn, &today, tuesday).await.unwrap().unwrap();
+    assert_eq!(previous.timestamp, monday);
+    assert_eq!(previous.apy, 6.0);
+
+    let rows = YieldRepo::range_for_asset(&mut conn, "TESTUSD", monday, tuesday).await.unwrap();
+    assert!(rows.iter().filter(|r| r.protocol == "upsert").count() == 2);
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use uuid::Uuid;
+use crate::db::PgPooledConnection;
+use crate::models::user_db::{ApiKeyDB, UserDB};
+use crate::schema::{api_keys, users};
+
+/// User repository
+pub struct UserRepo;
+
+impl UserRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &UserDB) -> Result<usize, DieselError> {
+        insert_into(users::table)
+            .values(rec)
+            .execute(conn)
+    }
+
+    pub async fn get(conn: &mut PgPooledConnection, id: Uuid) -> Result<Option<UserDB>, DieselError> {
+        users::table
+            .find(id)
+            .first::<UserDB>(conn)
+            .optional()
+    }
+
+    pub async fn all(conn: &mut PgPooledConnection) -> Result<Vec<UserDB>, DieselError> {
+        users::table
+            .order(users::created_at.asc())
+            .load::<UserDB>(conn)
+    }
+}
+
+/// API key repository
+pub struct ApiKeyRepo;
+
+impl ApiKeyRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &ApiKeyDB) -> Result<usize, DieselError> {
+        insert_into(api_keys::table)
+            .values(rec)
+            .execute(conn)
+    }
+
+    /// The owner of a key that has not been revoked.
+    pub async fn user_for_hash(conn: &mut PgPooledConnection, key_hash: &str) -> Result<Option<(ApiKeyDB, UserDB)>, DieselError> {
+        api_keys::table
+            .inner_join(users::table)
+            .filter(api_keys::key_hash.eq(key_hash))
+            .filter(api_keys::revoked_at.is_null())
+            .first::<(ApiKeyDB, UserDB)>(conn)
+            .optional()
+    }
+
+    pub async fn touch(conn: &mut PgPooledConnection, id: Uuid, at: NaiveDateTime) -> Result<usize, DieselError> {
+        diesel::update(api_keys::table.find(id))
+            .set(api_keys::last_used_at.eq(at))
+            .execute(conn)
+    }
+
+    pub async fn for_user(conn: &mut PgPooledConnection, user_id: Uuid) -> Result<Vec<ApiKeyDB>, DieselError> {
+        api_keys::table
+            .filter(api_keys::user_id.eq(user_id))
+            .order(api_keys::created_at.asc())
+            .load::<ApiKeyDB>(conn)
+    }
+
+    /// Revokes a key of `user_id`; keys of other users are left alone.
+    pub async fn revoke(conn: &mut PgPooledConnection, id: Uuid, user_id: Uuid, at: NaiveDateTime) -> Result<usize, DieselError> {
+        diesel::update(
+            api_keys::table
+                .filter(api_keys::id.eq(id))
+                .filter(api_keys::user_id.eq(user_id))
+                .filter(api_keys::revoked_at.is_null()),
+        )
+            .set(api_keys::revoked_at.eq(at))
+            .execute(conn)
+    }
+}
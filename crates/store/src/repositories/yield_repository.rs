@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::dsl::insert_into;
+use diesel::result::Error as DieselError;
+use diesel::upsert::excluded;
+use crate::db::PgPooledConnection;
+use crate::models::yield_db::YieldDB;
+use crate::schema::yields;
+
+/// Yield repository
+pub struct YieldRepo;
+
+impl YieldRepo {
+    pub async fn insert(conn: &mut PgPooledConnection, rec: &YieldDB) -> Result<usize, DieselError> {
+        insert_into(yields::table)
+            .values(rec)
+            .on_conflict((yields::source, yields::protocol, yields::asset_symbol, yields::kind, yields::timestamp))
+            .do_update()
+            .set((
+                yields::apy.eq(excluded(yields::apy)),
+                yields::risk_adjusted_apy.eq(excluded(yields::risk_adjusted_apy)),
+                yields::tvl_usd.eq(excluded(yields::tvl_usd)),
+                yields::spread_over_dff.eq(excluded(yields::spread_over_dff)),
+                yields::spread_over_dgs2.eq(excluded(yields::spread_over_dgs2)),
+            ))
+            .execute(conn)
+    }
+
+    /// Latest observation of the same source, protocol, asset and kind dated before `before`.
+    pub async fn previous(
+        conn: &mut PgPooledConnection,
+        rec: &YieldDB,
+        before: NaiveDate,
+    ) -> Result<Option<YieldDB>, DieselError> {
+        yields::table
+            .filter(yields::source.eq(&rec.source))
+            .filter(yields::protocol.eq(&rec.protocol))
+            .filter(yields::asset_symbol.eq(&rec.asset_symbol))
+            .filter(yields::kind.eq(&rec.kind))
+            .filter(yields::timestamp.lt(before))
+            .order(yields::timestamp.desc())
+            .first::<YieldDB>(conn)
+            .optional()
+    }
+
+    pub async fn range_for_asset(
+        conn: &mut PgPooledConnection,
+        asset: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<YieldDB>, DieselError> {
+        yields::table
+            .filter(yields::asset_symbol.eq(asset))
+            .filter(yields::timestamp.ge(from))
+            .filter(yields::timestamp.le(to))
+            .order((yields::timestamp.asc(), yields::protocol.asc()))
+            .load::<YieldDB>(conn)
+    }
+}
@@ -1,5 +1,62 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    accounts (id) {
+        id -> Uuid,
+        portfolio_id -> Uuid,
+        #[max_length = 128]
+        name -> Varchar,
+        #[max_length = 64]
+        venue -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    alert_deliveries (id) {
+        id -> Uuid,
+        rule_id -> Uuid,
+        #[max_length = 128]
+        dedup_key -> Varchar,
+        #[max_length = 16]
+        status -> Varchar,
+        subject -> Text,
+        error -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    alert_rules (id) {
+        id -> Uuid,
+        #[max_length = 64]
+        name -> Varchar,
+        condition -> Text,
+        #[max_length = 16]
+        channel -> Varchar,
+        target -> Text,
+        cooldown_minutes -> Int8,
+        enabled -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    api_keys (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        key_hash -> Varchar,
+        #[max_length = 16]
+        prefix -> Varchar,
+        #[max_length = 128]
+        label -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        last_used_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     indicators (name, timestamp) {
         #[max_length = 128]
@@ -13,6 +70,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ledger_transactions (id) {
+        id -> Uuid,
+        account_id -> Uuid,
+        timestamp -> Timestamptz,
+        #[max_length = 32]
+        kind -> Varchar,
+        #[max_length = 16]
+        asset_symbol -> Varchar,
+        quantity -> Float8,
+        price_usd -> Nullable<Float8>,
+        fee_usd -> Nullable<Float8>,
+        counterparty_account_id -> Nullable<Uuid>,
+        note -> Nullable<Text>,
+        #[max_length = 128]
+        external_id -> Nullable<Varchar>,
+    }
+}
+
 diesel::table! {
     market_data (asset_symbol, timestamp) {
         #[max_length = 16]
@@ -34,6 +110,40 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    portfolio_nav (portfolio_id, timestamp) {
+        portfolio_id -> Uuid,
+        timestamp -> Date,
+        nav_usd -> Float8,
+        net_flow_usd -> Float8,
+        forward_filled -> Bool,
+    }
+}
+
+diesel::table! {
+    portfolio_targets (portfolio_id, bucket) {
+        portfolio_id -> Uuid,
+        #[max_length = 32]
+        bucket -> Varchar,
+        weight -> Float8,
+        band -> Float8,
+    }
+}
+
+diesel::table! {
+    portfolios (id) {
+        id -> Uuid,
+        #[max_length = 128]
+        name -> Varchar,
+        created_at -> Timestamptz,
+        #[max_length = 16]
+        cost_basis_method -> Varchar,
+        #[max_length = 8]
+        tax_jurisdiction -> Varchar,
+        owner_id -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     strategy_signals (id) {
         id -> Uuid,
@@ -46,12 +156,58 @@ diesel::table! {
         description -> Nullable<Text>,
         #[max_length = 64]
         source -> Nullable<Varchar>,
+        inputs -> Nullable<Jsonb>,
     }
 }
 
+diesel::table! {
+    users (id) {
+        id -> Uuid,
+        #[max_length = 128]
+        name -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    yields (source, protocol, asset_symbol, kind, timestamp) {
+        #[max_length = 64]
+        source -> Varchar,
+        #[max_length = 64]
+        protocol -> Varchar,
+        #[max_length = 16]
+        asset_symbol -> Varchar,
+        #[max_length = 16]
+        kind -> Varchar,
+        timestamp -> Date,
+        apy -> Float8,
+        risk_adjusted_apy -> Float8,
+        tvl_usd -> Nullable<Float8>,
+        spread_over_dff -> Nullable<Float8>,
+        spread_over_dgs2 -> Nullable<Float8>,
+    }
+}
+
+diesel::joinable!(accounts -> portfolios (portfolio_id));
+diesel::joinable!(alert_deliveries -> alert_rules (rule_id));
+diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(portfolio_nav -> portfolios (portfolio_id));
+diesel::joinable!(portfolio_targets -> portfolios (portfolio_id));
+diesel::joinable!(portfolios -> users (owner_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
+    accounts,
+    alert_deliveries,
+    alert_rules,
+    api_keys,
     indicators,
+    ledger_transactions,
     market_data,
     market_metrics,
+    portfolio_nav,
+    portfolio_targets,
+    portfolios,
     strategy_signals,
+    users,
+    yields,
 );
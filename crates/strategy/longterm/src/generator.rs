@@ -0,0 +1,74 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use domain::{MarketDataSet, MarketSymbol, Signal, StrategyRegistry, TwoHundredWeekMa};
+use store::db::PgPool;
+use store::models::signal_db::NewStrategySignalDB;
+use store::repositories::{
+    market_data_repository::MarketDataRepo, market_metrics_repository::MarketMetricRepo, signal_repository::SignalsRepo,
+};
+use tracing::info;
+
+/// `source` of every signal written by this service. The strategy is part of the `signal_type`.
+pub const SOURCE: &str = "longterm";
+
+/// Runs every registered strategy on the stored market data and persists the signals that fired.
+/// Signals are dated to the day they were evaluated on, so a second run on the same day updates
+/// them.
+pub struct SignalGenerator {
+    db_pool: PgPool,
+    registry: StrategyRegistry,
+    warm_up: Duration,
+}
+
+impl SignalGenerator {
+    pub fn new(db_pool: PgPool, registry: StrategyRegistry) -> Self {
+        // A little more than 200 weeks, so gaps in the series still leave a full window.
+        let warm_up = Duration::days(TwoHundredWeekMa::WINDOW_DAYS as i64 + 60);
+        Self { db_pool, registry, warm_up }
+    }
+
+    /// Loads at least `warm_up` of history, for strategies looking further back than the
+    /// built-in ones.
+    pub fn with_warm_up(mut self, warm_up: Duration) -> Self {
+        self.warm_up = self.warm_up.max(warm_up);
+        self
+    }
+
+    pub async fn run_once(&self) -> Result<Vec<Signal>> {
+        let today = Utc::now().date_naive();
+        let data = self.load(today).await?;
+        let signals = self.registry.evaluate_all(&data.at(today));
+
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+        for signal in &signals {
+            SignalsRepo::insert(&mut conn, &NewStrategySignalDB {
+                asset_symbol: signal.asset_symbol.clone(),
+                timestamp: signal.date.and_hms_opt(0, 0, 0),
+                signal_type: signal.signal_type(),
+                value: Some(signal.value()),
+                description: Some(signal.reason.clone()),
+                source: Some(SOURCE.to_string()),
+                inputs: Some(serde_json::to_value(&signal.explanation)?),
+            })?;
+            info!("{} {}: {}", signal.asset_symbol, signal.signal_type(), signal.reason);
+        }
+
+        Ok(signals)
+    }
+
+    /// Day of the newest stored `BTC_USD` close, written by daily ingestion.
+    pub async fn latest_close(&self) -> Result<Option<NaiveDate>> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+        let latest = MarketDataRepo::latest_n_for_asset(&mut conn, MarketSymbol::BtcUsd, 1).await?;
+        Ok(latest.first().map(|row| row.timestamp))
+    }
+
+    async fn load(&self, today: NaiveDate) -> Result<MarketDataSet> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+        let inputs = self.registry.inputs();
+        Ok(MarketMetricRepo::market_data_set(&mut conn, &inputs, today - self.warm_up, today).await?)
+    }
+}
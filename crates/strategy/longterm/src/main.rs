@@ -1,3 +1,93 @@
-fn main() {
-    println!("Hello, world!");
+mod generator;
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveTime, Utc};
+use domain::{RuleSet, StrategyRegistry};
+use dotenvy::dotenv;
+use store::db::establish_pool;
+use telemetry::setup_observability;
+
+use crate::generator::SignalGenerator;
+
+/// Earliest time of day (UTC) of the evaluation. The worker ingests every 24 hours counted from
+/// its own start, not at a fixed time, so the evaluation also waits for the previous day's close.
+const DEFAULT_RUN_AT: &str = "02:00";
+/// How often the stored closes are checked while waiting for daily ingestion.
+const INGESTION_POLL_MINUTES: i64 = 30;
+/// Evaluate on whatever is stored once daily ingestion is this late.
+const INGESTION_GRACE_HOURS: i64 = 12;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    setup_observability();
+
+    let run_at = std::env::var("LONGTERM_RUN_AT").unwrap_or_else(|_| DEFAULT_RUN_AT.to_string());
+    let run_at = NaiveTime::parse_from_str(&run_at, "%H:%M")
+        .with_context(|| format!("LONGTERM_RUN_AT must be HH:MM (UTC), got {run_at}"))?;
+
+    let mut registry = StrategyRegistry::builtin();
+    let mut warm_up = Duration::zero();
+    if let Ok(path) = std::env::var("LONGTERM_RULES") {
+        let rules = load_rules(&path)?;
+        warm_up = rules.warm_up();
+        tracing::info!("Loaded {} rules from {}", rules.rules().len(), path);
+        registry.register(Box::new(rules))?;
+    }
+
+    tracing::info!(
+        "Starting long-term signal generator, daily at {} UTC with strategies: {}",
+        run_at,
+        registry.names().join(", ")
+    );
+    let generator = SignalGenerator::new(establish_pool(), registry).with_warm_up(warm_up);
+
+    loop {
+        wait_for_ingestion(&generator).await;
+        match generator.run_once().await {
+            Ok(signals) => tracing::info!("Long-term evaluation stored {} signals", signals.len()),
+            Err(e) => tracing::error!("Long-term evaluation failed: {:#}", e),
+        }
+        sleep_until_next(run_at).await;
+    }
+}
+
+/// Reads and validates the TOML rules file named by `LONGTERM_RULES`, failing on any error.
+fn load_rules(path: &str) -> Result<RuleSet> {
+    let source = std::fs::read_to_string(path).with_context(|| format!("Cannot read rules file {path}"))?;
+    RuleSet::parse(&source).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(|e| format!("{path}: {e}")).collect();
+        anyhow::anyhow!("Invalid rules file\n{}", errors.join("\n"))
+    })
+}
+
+/// Waits until daily ingestion has stored yesterday's BTC close, at most `INGESTION_GRACE_HOURS`.
+async fn wait_for_ingestion(generator: &SignalGenerator) {
+    let deadline = Utc::now() + Duration::hours(INGESTION_GRACE_HOURS);
+    loop {
+        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        match generator.latest_close().await {
+            Ok(Some(latest)) if latest >= yesterday => return,
+            Ok(latest) => tracing::info!("Waiting for daily ingestion, newest BTC close: {:?}", latest),
+            Err(e) => tracing::warn!("Cannot check daily ingestion: {:#}", e),
+        }
+        if Utc::now() >= deadline {
+            tracing::warn!("Daily ingestion is late, evaluating on the stored data");
+            return;
+        }
+        tokio::time::sleep(Duration::minutes(INGESTION_POLL_MINUTES).to_std().unwrap_or_default()).await;
+    }
+}
+
+/// Sleeps until the next `at` (UTC), tomorrow if it already passed today.
+async fn sleep_until_next(at: NaiveTime) {
+    let now = Utc::now().naive_utc();
+    let mut next = now.date().and_time(at);
+    if next <= now {
+        next += Duration::days(1);
+    }
+
+    let wait = (next - now).to_std().unwrap_or_default();
+    tracing::info!("Next long-term evaluation at {} UTC", next);
+    tokio::time::sleep(wait).await;
 }
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use domain::{YieldBenchmark, YieldKind};
+use serde::Deserialize;
+
+/// Read from the JSON file in `YIELDS_CONFIG`; see `fixtures/yields.example.json`, which
+/// points at `fixtures/pools.json` served locally (`python3 -m http.server 8089 -d fixtures`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct YieldsConfig {
+    pub sources: Vec<HttpJsonSourceConfig>,
+    #[serde(default)]
+    pub thresholds: ThresholdConfig,
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+}
+
+impl YieldsConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("Invalid yields config {}", path.display()))
+    }
+}
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+/// Spread levels, in percentage points over `benchmark`, whose crossing emits a signal.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThresholdConfig {
+    #[serde(default)]
+    pub benchmark: YieldBenchmark,
+    pub levels: Vec<f64>,
+}
+
+impl Default for ThresholdConfig {
+    fn default() -> Self {
+        Self { benchmark: YieldBenchmark::Dff, levels: vec![0.0, 2.0, 5.0] }
+    }
+}
+
+/// An endpoint returning a JSON array of pools. Field names are JSON pointers relative to
+/// one pool (`/apy`, `/token/symbol`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpJsonSourceConfig {
+    pub name: String,
+    pub url: String,
+    /// Pointer to the array of pools; empty when the body is the array.
+    #[serde(default)]
+    pub items: String,
+    pub protocol_field: String,
+    pub asset_field: String,
+    pub apy_field: String,
+    #[serde(default)]
+    pub tvl_field: Option<String>,
+    /// Field holding `LENDING` or `STAKING`; when absent every pool is of `kind`.
+    #[serde(default)]
+    pub kind_field: Option<String>,
+    #[serde(default)]
+    pub kind: Option<YieldKind>,
+    /// Multiplies the reported APY into percent, e.g. 100 for sources quoting fractions.
+    #[serde(default = "default_apy_scale")]
+    pub apy_scale: f64,
+    /// Share of the APY given up for the risk of this source, between 0 and 1.
+    #[serde(default)]
+    pub risk_discount: f64,
+    /// Only keep these assets; every asset when empty.
+    #[serde(default)]
+    pub assets: Vec<String>,
+}
+
+fn default_apy_scale() -> f64 {
+    1.0
+}
@@ -1,3 +1,41 @@
-fn main() {
-    println!("Hello, world!");
+mod config;
+mod sources;
+mod tests;
+mod tracker;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use dotenvy::dotenv;
+use store::db::establish_pool;
+use telemetry::setup_observability;
+
+use crate::config::YieldsConfig;
+use crate::sources::{HttpJsonSource, YieldSource};
+use crate::tracker::YieldTracker;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+    setup_observability();
+
+    let path = std::env::var("YIELDS_CONFIG").map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("yields.json"));
+    let config = YieldsConfig::load(&path)?;
+
+    let sources: Vec<Box<dyn YieldSource>> = config
+        .sources
+        .into_iter()
+        .map(|source| Box::new(HttpJsonSource::new(source)) as Box<dyn YieldSource>)
+        .collect();
+    tracing::info!("Starting yield tracker with {} sources", sources.len());
+
+    let tracker = YieldTracker::new(establish_pool(), sources, config.thresholds);
+    let interval = std::time::Duration::from_secs(config.interval_hours * 60 * 60);
+
+    loop {
+        if let Err(e) = tracker.run_once().await {
+            tracing::error!("Yield tracking cycle failed: {:#}", e);
+        }
+        tokio::time::sleep(interval).await;
+    }
 }
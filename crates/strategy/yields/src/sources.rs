@@ -0,0 +1,118 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
+use domain::{YieldKind, YieldQuote, normalize_symbol};
+use reqwest::Client;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::config::HttpJsonSourceConfig;
+
+/// Width of `yields.protocol`.
+pub const MAX_PROTOCOL_LEN: usize = 64;
+/// Width of `yields.asset_symbol` and `strategy_signals.asset_symbol`.
+pub const MAX_ASSET_LEN: usize = 16;
+
+/// Somewhere APYs come from. Implementations only fetch and normalize quotes; spreads,
+/// storage and signals are the tracker's job.
+#[async_trait::async_trait]
+pub trait YieldSource: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Share of the APY given up for the risk of this source, between 0 and 1.
+    fn risk_discount(&self) -> f64 {
+        0.0
+    }
+
+    async fn fetch(&self, date: NaiveDate) -> Result<Vec<YieldQuote>>;
+}
+
+/// Any HTTP endpoint answering with a JSON array of pools, including a local fixture server.
+pub struct HttpJsonSource {
+    config: HttpJsonSourceConfig,
+    http: Client,
+}
+
+impl HttpJsonSource {
+    pub fn new(config: HttpJsonSourceConfig) -> Self {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { config, http }
+    }
+
+    /// Quotes in `body` dated `date`. Pools missing a field, with an unknown kind or with names
+    /// too long to store are skipped.
+    pub fn parse(&self, body: &Value, date: NaiveDate) -> Result<Vec<YieldQuote>> {
+        let config = &self.config;
+        let items = body
+            .pointer(&config.items)
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("{}: no array of pools at '{}'", config.name, config.items))?;
+
+        let quotes = items
+            .iter()
+            .filter_map(|item| {
+                let protocol = item.pointer(&config.protocol_field)?.as_str()?.trim().to_string();
+                let asset_symbol = normalize_symbol(item.pointer(&config.asset_field)?.as_str()?);
+                if protocol.chars().count() > MAX_PROTOCOL_LEN || asset_symbol.chars().count() > MAX_ASSET_LEN {
+                    warn!("{}: skipping pool {} / {}, name too long to store", config.name, protocol, asset_symbol);
+                    return None;
+                }
+                let apy = number(item.pointer(&config.apy_field)?)? * config.apy_scale;
+                let kind = match &config.kind_field {
+                    Some(field) => YieldKind::from_str(item.pointer(field)?.as_str()?).ok()?,
+                    None => config.kind?,
+                };
+                let tvl_usd = config.tvl_field.as_ref().and_then(|field| item.pointer(field)).and_then(number);
+
+                let wanted = config.assets.is_empty() || config.assets.iter().any(|a| a.eq_ignore_ascii_case(&asset_symbol));
+                (wanted && apy.is_finite()).then(|| YieldQuote {
+                    source: config.name.clone(),
+                    protocol,
+                    asset_symbol,
+                    kind,
+                    date,
+                    apy,
+                    tvl_usd,
+                })
+            })
+            .collect();
+
+        Ok(quotes)
+    }
+}
+
+#[async_trait::async_trait]
+impl YieldSource for HttpJsonSource {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn risk_discount(&self) -> f64 {
+        self.config.risk_discount
+    }
+
+    async fn fetch(&self, date: NaiveDate) -> Result<Vec<YieldQuote>> {
+        let body: Value = self.http
+            .get(&self.config.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("Cannot fetch {}", self.config.url))?
+            .json()
+            .await
+            .with_context(|| format!("{} did not return JSON", self.config.url))?;
+
+        self.parse(&body, date)
+    }
+}
+
+/// Numbers are sometimes sent as strings.
+fn number(value: &Value) -> Option<f64> {
+    value.as_f64().or_else(|| value.as_str()?.trim().parse().ok())
+}
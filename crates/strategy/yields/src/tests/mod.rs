@@ -0,0 +1,2 @@
+#[cfg(test)]
+pub mod source_tests;
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+use domain::YieldKind;
+use serde_json::Value;
+
+use crate::config::YieldsConfig;
+use crate::sources::HttpJsonSource;
+
+fn fixture_source() -> HttpJsonSource {
+    let config: YieldsConfig = serde_json::from_str(include_str!("../../fixtures/yields.example.json")).unwrap();
+    HttpJsonSource::new(config.sources.into_iter().next().unwrap())
+}
+
+#[test]
+fn test_parse_fixture_pools() {
+    let body: Value = serde_json::from_str(include_str!("../../fixtures/pools.json")).unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+
+    let quotes = fixture_source().parse(&body, date).unwrap();
+
+    // The farming pool has no known kind and the broken one no APY.
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(quotes[0].protocol, "aave-v3");
+    assert_eq!(quotes[0].tvl_usd, Some(412_000_000.0));
+    assert_eq!(quotes[1].asset_symbol, "USDT");
+    assert_eq!(quotes[1].apy, 5.4);
+    assert_eq!(quotes[2].kind, YieldKind::Staking);
+    assert!(quotes.iter().all(|q| q.source == "fixture" && q.date == date));
+}
+
+#[test]
+fn test_parse_rejects_body_without_pools() {
+    let body: Value = serde_json::from_str(r#"{ "error": "rate limited" }"#).unwrap();
+
+    assert!(fixture_source().parse(&body, NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()).is_err());
+}
+
+#[test]
+fn test_parse_normalizes_symbols_and_skips_names_too_long_to_store() {
+    let config: YieldsConfig = serde_json::from_str(include_str!("../../fixtures/yields.example.json")).unwrap();
+    let mut config = config.sources.into_iter().next().unwrap();
+    config.assets.clear();
+    let body: Value = serde_json::from_str(
+        r#"{ "data": [
+            { "project": " lido ", "symbol": " ethereum ", "apy": 3.0, "category": "staking" },
+            { "project": "curve", "symbol": "USDC-USDT-DAI-FRAX", "apy": 9.0, "category": "lending" },
+            { "project": "a-protocol-name-longer-than-the-sixty-four-characters-its-column-holds", "symbol": "USDC", "apy": 4.0, "category": "lending" }
+        ] }"#,
+    )
+    .unwrap();
+
+    let quotes = HttpJsonSource::new(config).parse(&body, NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()).unwrap();
+
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].protocol, "lido");
+    assert_eq!(quotes[0].asset_symbol, "ETH");
+}
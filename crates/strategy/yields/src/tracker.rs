@@ -0,0 +1,121 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use domain::{threshold_crossing, MarketSymbol, SignalExplanation, YieldObservation};
+use store::db::PgPool;
+use store::models::{signal_db::NewStrategySignalDB, yield_db::YieldDB};
+use store::repositories::{
+    market_metrics_repository::MarketMetricRepo, signal_repository::SignalsRepo, yield_repository::YieldRepo,
+};
+use tracing::{info, warn};
+
+use crate::config::ThresholdConfig;
+use crate::sources::YieldSource;
+
+/// `source` of every signal written by this service.
+pub const SOURCE: &str = "yields";
+/// `rule_version` recorded with every signal. Bump it when the crossing logic changes.
+pub const RULE_VERSION: &str = "spread-crossing-1";
+
+/// Collects quotes from every source, stores them with their spreads over DFF and DGS2 and
+/// signals when a risk-adjusted spread crosses one of the configured levels.
+pub struct YieldTracker {
+    db_pool: PgPool,
+    sources: Vec<Box<dyn YieldSource>>,
+    thresholds: ThresholdConfig,
+}
+
+impl YieldTracker {
+    pub fn new(db_pool: PgPool, sources: Vec<Box<dyn YieldSource>>, thresholds: ThresholdConfig) -> Self {
+        Self { db_pool, sources, thresholds }
+    }
+
+    pub async fn run_once(&self) -> Result<()> {
+        let today = Utc::now().date_naive();
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        // FRED publishes with a lag of a few days.
+        let rates = MarketMetricRepo::history(
+            &mut conn,
+            &[MarketSymbol::DFF, MarketSymbol::DGS2],
+            today - Duration::days(30),
+            today,
+        ).await?;
+        let rate = |symbol: MarketSymbol| rates.get(symbol.as_str()).cloned().unwrap_or_default();
+        let (dff, dgs2) = (rate(MarketSymbol::DFF), rate(MarketSymbol::DGS2));
+
+        for source in &self.sources {
+            let quotes = match source.fetch(today).await {
+                Ok(quotes) => quotes,
+                Err(e) => {
+                    warn!("Yield source {} failed: {:#}", source.name(), e);
+                    continue;
+                }
+            };
+            info!("{} returned {} yields", source.name(), quotes.len());
+
+            for quote in quotes {
+                let observation = YieldObservation::new(quote, source.risk_discount(), &dff, &dgs2);
+                let row = YieldDB::from(&observation);
+                let previous = YieldRepo::previous(&mut conn, &row, today).await?;
+                if let Err(e) = YieldRepo::insert(&mut conn, &row).await {
+                    warn!("Failed to persist {} {} on {}: {}", row.asset_symbol, row.kind, row.protocol, e);
+                    continue;
+                }
+
+                if let Some(signal) = self.crossing_signal(&observation, previous, today) {
+                    info!("{} {}: {}", signal.asset_symbol, signal.signal_type, signal.description.as_deref().unwrap_or_default());
+                    if let Err(e) = SignalsRepo::insert(&mut conn, &signal) {
+                        warn!("Failed to persist signal {} for {}: {}", signal.signal_type, signal.asset_symbol, e);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn crossing_signal(
+        &self,
+        observation: &YieldObservation,
+        previous: Option<YieldDB>,
+        today: NaiveDate,
+    ) -> Option<NewStrategySignalDB> {
+        let benchmark = self.thresholds.benchmark;
+        let current = observation.spread_over(benchmark)?;
+        let previous = YieldObservation::try_from(previous?).ok()?.spread_over(benchmark)?;
+        let (direction, level) = threshold_crossing(previous, current, &self.thresholds.levels)?;
+
+        let quote = &observation.quote;
+        let protocol: String = quote.protocol.to_uppercase().replace(['-', ' ', '.'], "_").chars().take(40).collect();
+        Some(NewStrategySignalDB {
+            asset_symbol: quote.asset_symbol.clone(),
+            timestamp: today.and_hms_opt(0, 0, 0),
+            signal_type: format!("YIELD_{}_{}_{}", protocol, quote.kind.as_str(), direction.as_str()),
+            value: Some(current),
+            description: Some(format!(
+                "{} {} on {} at {:.2}% ({:.2}% risk-adjusted) moved {} {:.2} points over {}: spread {:.2} -> {:.2}",
+                quote.asset_symbol,
+                quote.kind.as_str().to_lowercase(),
+                quote.protocol,
+                quote.apy,
+                observation.risk_adjusted_apy,
+                direction.as_str().to_lowercase(),
+                level,
+                benchmark.as_str(),
+                previous,
+                current,
+            )),
+            source: Some(SOURCE.to_string()),
+            inputs: serde_json::to_value(
+                SignalExplanation::new(RULE_VERSION)
+                    .input("apy", quote.apy)
+                    .input("risk_adjusted_apy", observation.risk_adjusted_apy)
+                    .input("previous_spread", previous)
+                    .input("spread", current)
+                    .threshold(format!("spread_over_{}", benchmark.as_str()), level),
+            )
+            .ok(),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Utc};
+use domain::{AlertMatch, AlertRule, DeliveryStatus, MarketSymbol, SignalEvent};
+use store::{
+    db::PgPool,
+    models::alert_db::AlertDeliveryDB,
+    repositories::{
+        alert_repository::{AlertDeliveryRepo, AlertRuleRepo},
+        market_metrics_repository::MarketMetricRepo,
+        signal_repository::SignalsRepo,
+    },
+};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::notifier::{Notifier, SmtpNotifier, WebhookNotifier};
+
+/// History loaded for series conditions; only the last two observations matter, the rest covers
+/// monthly releases.
+const SERIES_LOOKBACK_DAYS: i64 = 40;
+/// Signals older than this are not alerted on, even when the rule would match them.
+const SIGNAL_LOOKBACK_DAYS: i64 = 3;
+
+/// Evaluates the enabled alert rules against the stored series and signals and delivers new
+/// matches. Runs after every ingestion cycle.
+pub struct AlertEngine {
+    db_pool: PgPool,
+    notifiers: Vec<Box<dyn Notifier>>,
+    /// Workers finishing at the same time would otherwise both deliver the same match.
+    running: Mutex<()>,
+}
+
+impl AlertEngine {
+    pub fn new(db_pool: PgPool, notifiers: Vec<Box<dyn Notifier>>) -> Self {
+        Self { db_pool, notifiers, running: Mutex::new(()) }
+    }
+
+    /// Webhooks always, mail when SMTP is configured (see [`SmtpNotifier::from_env`]).
+    pub fn from_env(db_pool: PgPool) -> Result<Self> {
+        let mut notifiers: Vec<Box<dyn Notifier>> = vec![Box::new(WebhookNotifier::new())];
+        if let Some(smtp) = SmtpNotifier::from_env()? {
+            notifiers.push(Box::new(smtp));
+        }
+        Ok(Self::new(db_pool, notifiers))
+    }
+
+    /// Returns the number of alerts sent.
+    pub async fn run(&self) -> Result<usize> {
+        let _running = self.running.lock().await;
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow!("Failed to get DB connection: {e}"))?;
+
+        let rules: Vec<AlertRule> = AlertRuleRepo::enabled(&mut conn).await?
+            .into_iter()
+            .filter_map(|row| AlertRule::try_from(row).map_err(|e| warn!("Skipping {e}")).ok())
+            .collect();
+        if rules.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now().naive_utc();
+        let today = now.date();
+        let symbols: Vec<MarketSymbol> = rules.iter().filter_map(|r| r.condition.symbol().cloned()).collect();
+        let data = MarketMetricRepo::market_data_set(&mut conn, &symbols, today - Duration::days(SERIES_LOOKBACK_DAYS), today).await?;
+        let signals: Vec<SignalEvent> = SignalsRepo::since(&mut conn, now - Duration::days(SIGNAL_LOOKBACK_DAYS))?
+            .into_iter()
+            .map(SignalEvent::from)
+            .collect();
+        let ctx = data.at(today);
+
+        let mut sent = 0;
+        for rule in &rules {
+            let series = rule.condition.symbol().map(|s| ctx.series(s)).unwrap_or_default();
+            for alert in rule.evaluate(series, &signals) {
+                if AlertDeliveryRepo::is_settled(&mut conn, rule.id, &alert.dedup_key).await? {
+                    continue;
+                }
+
+                let last_sent = AlertDeliveryRepo::last_sent(&mut conn, rule.id).await?;
+                let (status, error) = if rule.cooling_down(last_sent, Utc::now().naive_utc()) {
+                    (DeliveryStatus::Suppressed, None)
+                } else {
+                    match self.deliver(rule, &alert).await {
+                        Ok(()) => (DeliveryStatus::Sent, None),
+                        Err(e) => (DeliveryStatus::Failed, Some(format!("{e:#}"))),
+                    }
+                };
+
+                match &error {
+                    Some(e) => warn!("Alert {} ({}) failed: {}", rule.name, alert.subject, e),
+                    None => info!("Alert {} ({}) {}", rule.name, alert.subject, status.as_str()),
+                }
+                if status == DeliveryStatus::Sent {
+                    sent += 1;
+                }
+
+                AlertDeliveryRepo::insert(
+                    &mut conn,
+                    &AlertDeliveryDB {
+                        id: Uuid::new_v4(),
+                        rule_id: rule.id,
+                        dedup_key: alert.dedup_key,
+                        status: status.as_str().to_string(),
+                        subject: alert.subject,
+                        error,
+                        created_at: Utc::now().naive_utc(),
+                    },
+                ).await?;
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn deliver(&self, rule: &AlertRule, alert: &AlertMatch) -> Result<()> {
+        let notifier = self.notifiers
+            .iter()
+            .find(|n| n.channel() == rule.channel)
+            .ok_or_else(|| anyhow!("No {} notifier configured", rule.channel.as_str()))?;
+        notifier.send(&rule.target, alert).await
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use domain::{BTC_GENESIS, BtcCycleReading, MarketSymbol, btc_cycle_history};
+use store::{
+    db::PgPool,
+    models::market_metrics_db::MarketMetricDataDB,
+    repositories::{market_data_repository::MarketDataRepo, market_metrics_repository::MarketMetricRepo},
+};
+use tracing::{info, warn};
+use crate::framework::IngestionJob;
+
+/// Days before the newest stored reading that are computed again, so late or revised closes are
+/// picked up.
+const RECOMPUTE_DAYS: i64 = 7;
+
+/// Computes the BTC cycle indicators (Mayer multiple, 200-week MA, Pi Cycle, power law) from the
+/// stored `BTC_USD` closes and stores them in `market_metrics`.
+pub struct BtcCycleJob {
+    db_pool: PgPool,
+}
+
+#[derive(Debug)]
+pub struct BtcCycleResult {
+    timestamp: chrono::DateTime<Utc>,
+    readings: Vec<BtcCycleReading>,
+}
+
+impl BtcCycleJob {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionJob for BtcCycleJob {
+    type Output = BtcCycleResult;
+
+    fn name(&self) -> &'static str { "btc_cycle" }
+
+    async fn fetch_all(&self) -> Result<Self::Output> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        let today = Utc::now().date_naive();
+        // The 200-week MA and the power law need the whole history, however few days are computed.
+        let btc = MarketSymbol::BtcUsd;
+        let closes: Vec<(NaiveDate, f64)> = MarketDataRepo::range_for_asset(&mut conn, btc.as_str(), BTC_GENESIS, today).await?
+            .into_iter()
+            .map(|r| (r.timestamp, r.price_usd))
+            .collect();
+
+        // Only days from shortly before the newest stored reading on, the first run fills the history.
+        let from = match MarketMetricRepo::latest_n(&mut conn, MarketSymbol::BtcMayerMultiple, 1).await?.first() {
+            Some(latest) => latest.timestamp - Duration::days(RECOMPUTE_DAYS),
+            None => BTC_GENESIS,
+        };
+
+        Ok(BtcCycleResult { timestamp: Utc::now(), readings: btc_cycle_history(&closes, from, today) })
+    }
+
+    async fn store(&self, result: Self::Output) -> Result<()> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        for reading in &result.readings {
+            for (symbol, value) in reading.metrics() {
+                if let Err(e) = MarketMetricRepo::insert(
+                    &mut conn,
+                    &MarketMetricDataDB {
+                        name: symbol.as_str().into(),
+                        timestamp: reading.date,
+                        value: Some(value),
+                        source: Some("computed".into()),
+                    },
+                ).await {
+                    warn!("Failed to persist {} on {}: {}", symbol.as_str(), reading.date, e);
+                }
+            }
+        }
+
+        match result.readings.last() {
+            Some(latest) if latest.pi_cycle_top => warn!("Pi Cycle top crossover on {}", latest.date),
+            Some(latest) => info!("BTC cycle indicators computed up to {}", latest.date),
+            None => warn!("No BTC closes to compute cycle indicators from"),
+        }
+        info!("BTC cycle indicators persisted successfully at {}", result.timestamp);
+        Ok(())
+    }
+}
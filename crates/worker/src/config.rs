@@ -1,4 +1,4 @@
-use domain::MarketSymbol;
+use domain::{DEFAULT_INDICATORS, TechnicalIndicator, MarketSymbol};
 use web2::clients::M2Country;
 
 #[derive(Debug, Clone)]
@@ -57,3 +57,27 @@ impl Default for MontlyWorkerConfig {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct IndicatorWorkerConfig {
+    /// Price series of `market_data` the indicators are computed over.
+    pub series: Vec<MarketSymbol>,
+    pub indicators: Vec<TechnicalIndicator>,
+}
+
+impl Default for IndicatorWorkerConfig {
+    fn default() -> Self {
+        Self {
+            series: vec![
+                MarketSymbol::BtcUsd,
+                MarketSymbol::EthUsd,
+                MarketSymbol::Gold,
+                MarketSymbol::Oil,
+                MarketSymbol::Sp500,
+                MarketSymbol::Nasdaq,
+                MarketSymbol::UsdIndex,
+            ],
+            indicators: DEFAULT_INDICATORS.to_vec(),
+        }
+    }
+}
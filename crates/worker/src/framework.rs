@@ -1,8 +1,10 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
 use chrono::{Utc};
 
+use crate::alerts::AlertEngine;
 use crate::util::sleep_until_next_month;
 
 #[async_trait::async_trait]
@@ -19,11 +21,18 @@ pub struct IngestionWorker<J: IngestionJob, S: Scheduler> {
     scheduler: S,
     max_retries: u32,
     retry_delay: Duration,
+    alerts: Option<Arc<AlertEngine>>,
 }
 
 impl<J: IngestionJob, S: Scheduler> IngestionWorker<J, S> {
     pub fn new(job: J, scheduler: S, max_retries: u32, retry_delay: Duration) -> Self {
-        Self { job, scheduler, max_retries, retry_delay }
+        Self { job, scheduler, max_retries, retry_delay, alerts: None }
+    }
+
+    /// Evaluates the alert rules after every stored cycle, so alerts go out as soon as new data is in.
+    pub fn with_alerts(mut self, alerts: Arc<AlertEngine>) -> Self {
+        self.alerts = Some(alerts);
+        self
     }
 
     pub async fn run(&self) -> Result<()> {
@@ -46,6 +55,13 @@ impl<J: IngestionJob, S: Scheduler> IngestionWorker<J, S> {
         let result = self.fetch_with_retry().await?;
         self.job.store(result).await?;
         tracing::info!("{} cycle completed successfully", self.job.name());
+
+        if let Some(alerts) = &self.alerts {
+            match alerts.run().await {
+                Ok(sent) => tracing::info!("{} alerts sent after {}", sent, self.job.name()),
+                Err(e) => tracing::warn!("Alert evaluation after {} failed: {:#}", self.job.name(), e),
+            }
+        }
         Ok(())
     }
 
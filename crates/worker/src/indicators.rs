@@ -0,0 +1,106 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use store::{
+    db::PgPool,
+    models::indicator_db::IndicatorDB,
+    repositories::{indicator_repository::IndicatorRepo, market_data_repository::MarketDataRepo},
+};
+use tracing::{info, warn};
+use crate::{config::IndicatorWorkerConfig, framework::IngestionJob};
+
+/// Start of the history loaded for series without any stored indicator values yet.
+const FULL_HISTORY_FROM: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+
+/// Computes the technical indicators of every configured price series and stores them in
+/// `indicators`. Only the days from the newest stored value on are recomputed, from just enough
+/// older closes for the values to come out as if computed over the whole history.
+pub struct IndicatorJob {
+    config: IndicatorWorkerConfig,
+    db_pool: PgPool,
+}
+
+#[derive(Debug)]
+pub struct IndicatorResult {
+    timestamp: chrono::DateTime<Utc>,
+    rows: Vec<IndicatorDB>,
+}
+
+impl IndicatorJob {
+    pub fn new(config: IndicatorWorkerConfig, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionJob for IndicatorJob {
+    type Output = IndicatorResult;
+
+    fn name(&self) -> &'static str { "indicators" }
+
+    async fn fetch_all(&self) -> Result<Self::Output> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        let today = Utc::now().date_naive();
+        let mut rows = Vec::new();
+
+        for symbol in &self.config.series {
+            let prefix = symbol.as_str();
+            let names: Vec<String> = self.config.indicators.iter().flat_map(|i| i.series_names(prefix)).collect();
+            let latest = IndicatorRepo::latest_dates(&mut conn, &names)?;
+
+            // The newest stored day is recomputed too, its close may have been updated since.
+            let resume = match names.iter().map(|name| latest.get(name).copied()).collect::<Option<Vec<_>>>() {
+                Some(dates) => dates.into_iter().min(),
+                None => None,
+            };
+            let from = match resume {
+                Some(resume) => {
+                    let warmup = self.config.indicators.iter().map(|i| i.warmup()).max().unwrap_or(0);
+                    // Warmups count observations; markets closed on weekends and holidays have
+                    // about five in seven days.
+                    resume - Duration::days((warmup * 7 / 5 + 14) as i64)
+                }
+                None => FULL_HISTORY_FROM,
+            };
+
+            let closes: Vec<(NaiveDate, f64)> = MarketDataRepo::range_for_asset(&mut conn, prefix, from, today).await?
+                .into_iter()
+                .map(|r| (r.timestamp, r.price_usd))
+                .collect();
+            if closes.is_empty() {
+                warn!("No closes of {} to compute indicators over", prefix);
+                continue;
+            }
+
+            for indicator in &self.config.indicators {
+                for output in indicator.compute(prefix, &closes) {
+                    rows.extend(
+                        output.values
+                            .into_iter()
+                            .filter(|(date, _)| resume.is_none_or(|resume| *date >= resume))
+                            .map(|(date, value)| IndicatorDB {
+                                name: output.name.clone(),
+                                category: output.category.as_str().into(),
+                                timestamp: date,
+                                value: Some(value),
+                                source: Some("computed".into()),
+                            }),
+                    );
+                }
+            }
+            info!("Computed indicators of {} from {}", prefix, resume.unwrap_or(closes[0].0));
+        }
+
+        Ok(IndicatorResult { timestamp: Utc::now(), rows })
+    }
+
+    async fn store(&self, result: Self::Output) -> Result<()> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        let stored = IndicatorRepo::insert_many(&mut conn, &result.rows)?;
+        info!("{} indicator values persisted successfully at {}", stored, result.timestamp);
+        Ok(())
+    }
+}
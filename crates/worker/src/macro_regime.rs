@@ -0,0 +1,76 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use domain::{MACRO_REGIME_INPUTS, MacroRegimeReading, regime_history};
+use store::{db::PgPool, models::market_metrics_db::MarketMetricDataDB, repositories::market_metrics_repository::MarketMetricRepo};
+use tracing::{info, warn};
+use crate::framework::IngestionJob;
+
+/// Days reclassified on every run, so revised or late FRED releases are picked up.
+const RECLASSIFY_DAYS: i64 = 365;
+/// History needed before the first reclassified day: a year for the year-over-year inputs plus
+/// room for a monthly release being late.
+const LOOKBACK_DAYS: i64 = 365 + 120;
+
+/// Labels each day with a macro regime from the stored rates, dollar, CPI and M2, and stores the
+/// regime with its sub-scores in `market_metrics`.
+pub struct MacroRegimeJob {
+    db_pool: PgPool,
+}
+
+#[derive(Debug)]
+pub struct MacroRegimeResult {
+    timestamp: chrono::DateTime<Utc>,
+    readings: Vec<MacroRegimeReading>,
+}
+
+impl MacroRegimeJob {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionJob for MacroRegimeJob {
+    type Output = MacroRegimeResult;
+
+    fn name(&self) -> &'static str { "macro_regime" }
+
+    async fn fetch_all(&self) -> Result<Self::Output> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        let today = Utc::now().date_naive();
+        let from = today - Duration::days(RECLASSIFY_DAYS);
+        let data = MarketMetricRepo::market_data_set(&mut conn, &MACRO_REGIME_INPUTS, from - Duration::days(LOOKBACK_DAYS), today).await?;
+
+        Ok(MacroRegimeResult { timestamp: Utc::now(), readings: regime_history(&data, from, today) })
+    }
+
+    async fn store(&self, result: Self::Output) -> Result<()> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        for reading in &result.readings {
+            for (symbol, value) in reading.metrics() {
+                if let Err(e) = MarketMetricRepo::insert(
+                    &mut conn,
+                    &MarketMetricDataDB {
+                        name: symbol.as_str().into(),
+                        timestamp: reading.date,
+                        value: Some(value),
+                        source: Some("computed".into()),
+                    },
+                ).await {
+                    warn!("Failed to persist {} on {}: {}", symbol.as_str(), reading.date, e);
+                }
+            }
+        }
+
+        match result.readings.last() {
+            Some(latest) => info!("Macro regime on {} is {} ({:.2})", latest.date, latest.regime.as_str(), latest.score),
+            None => warn!("Not enough macro data to classify a regime"),
+        }
+        info!("Macro regime persisted successfully at {}", result.timestamp);
+        Ok(())
+    }
+}
@@ -1,15 +1,26 @@
+mod alerts;
+mod btc_cycle;
 mod daily_ingestion;
 mod montly_ingestion;
 mod config;
 mod framework;
+mod indicators;
+mod macro_regime;
+mod notifier;
+mod portfolio_nav;
+mod risk;
 mod util;
+mod tests;
+
+use std::sync::Arc;
 
 use anyhow::Result;
 use dotenvy::dotenv;
 use store::db::establish_pool;
 use telemetry::setup_observability;
 use crate::{
-    config::{DailyWorkerConfig, MontlyWorkerConfig}, daily_ingestion::DailyIngestionJob, framework::{FixedIntervalScheduler, IngestionWorker, MonthlyScheduler}, montly_ingestion::MonthlyIngestionJob
+    alerts::AlertEngine, btc_cycle::BtcCycleJob, config::{DailyWorkerConfig, IndicatorWorkerConfig, MontlyWorkerConfig}, daily_ingestion::DailyIngestionJob, framework::{FixedIntervalScheduler, IngestionWorker, MonthlyScheduler}, indicators::IndicatorJob, macro_regime::MacroRegimeJob, montly_ingestion::MonthlyIngestionJob,
+    portfolio_nav::PortfolioNavJob, risk::RiskMetricsJob,
 };
 
 
@@ -21,21 +32,60 @@ async fn main() -> Result<()> {
 
     let fred_api_key = std::env::var("FRED_API_KEY")?;
     let db_pool = establish_pool();
+    let alerts = Arc::new(AlertEngine::from_env(db_pool.clone())?);
 
     // --- Daily Job ---
     let daily_job = DailyIngestionJob::new(fred_api_key.clone(), DailyWorkerConfig::default(), db_pool.clone());
     let daily_scheduler = FixedIntervalScheduler::new(std::time::Duration::from_secs(24 * 60 * 60));
-    let daily_worker = IngestionWorker::new(daily_job, daily_scheduler, 3, std::time::Duration::from_secs(60));
+    let daily_worker = IngestionWorker::new(daily_job, daily_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
 
     // --- Monthly Job ---
     let monthly_job = MonthlyIngestionJob::new(fred_api_key.clone(), MontlyWorkerConfig::default());
     let monthly_scheduler = MonthlyScheduler::new();
-    let monthly_worker = IngestionWorker::new(monthly_job, monthly_scheduler, 3, std::time::Duration::from_secs(60));
+    let monthly_worker = IngestionWorker::new(monthly_job, monthly_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
+
+    // --- Portfolio NAV Job ---
+    let nav_job = PortfolioNavJob::new(db_pool.clone());
+    let nav_scheduler = FixedIntervalScheduler::new(std::time::Duration::from_secs(24 * 60 * 60));
+    let nav_worker = IngestionWorker::new(nav_job, nav_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
+
+    // --- Macro Regime Job ---
+    let regime_job = MacroRegimeJob::new(db_pool.clone());
+    let regime_scheduler = FixedIntervalScheduler::new(std::time::Duration::from_secs(24 * 60 * 60));
+    let regime_worker = IngestionWorker::new(regime_job, regime_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
+
+    // --- BTC Cycle Job ---
+    let cycle_job = BtcCycleJob::new(db_pool.clone());
+    let cycle_scheduler = FixedIntervalScheduler::new(std::time::Duration::from_secs(24 * 60 * 60));
+    let cycle_worker = IngestionWorker::new(cycle_job, cycle_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
+
+    // --- Risk Metrics Job ---
+    let risk_job = RiskMetricsJob::new(db_pool.clone());
+    let risk_scheduler = FixedIntervalScheduler::new(std::time::Duration::from_secs(24 * 60 * 60));
+    let risk_worker = IngestionWorker::new(risk_job, risk_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
+
+    // --- Indicators Job ---
+    let indicator_job = IndicatorJob::new(IndicatorWorkerConfig::default(), db_pool.clone());
+    let indicator_scheduler = FixedIntervalScheduler::new(std::time::Duration::from_secs(24 * 60 * 60));
+    let indicator_worker =
+        IngestionWorker::new(indicator_job, indicator_scheduler, 3, std::time::Duration::from_secs(60))
+        .with_alerts(alerts.clone());
 
-    // Run both concurrently
+    // Run all concurrently
     tokio::try_join!(
         daily_worker.run(),
         monthly_worker.run(),
+        nav_worker.run(),
+        regime_worker.run(),
+        indicator_worker.run(),
+        cycle_worker.run(),
+        risk_worker.run(),
     )?;
 
     Ok(())
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use domain::{AlertChannel, AlertMatch};
+use lettre::message::{Mailbox, header::ContentType};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+/// Delivers matched alerts over one channel.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    fn channel(&self) -> AlertChannel;
+    /// `target` is the rule's webhook URL or mail address.
+    async fn send(&self, target: &str, alert: &AlertMatch) -> Result<()>;
+}
+
+/// POSTs the match as JSON. Any status outside 2xx is a failed delivery.
+pub struct WebhookNotifier {
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new() -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent("crypto-portfolio-alerts")
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .expect("Failed to build HTTP client");
+        Self { http }
+    }
+}
+
+impl Default for WebhookNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for WebhookNotifier {
+    fn channel(&self) -> AlertChannel {
+        AlertChannel::Webhook
+    }
+
+    async fn send(&self, target: &str, alert: &AlertMatch) -> Result<()> {
+        self.http
+            .post(target)
+            .json(alert)
+            .send()
+            .await
+            .with_context(|| format!("POST {target}"))?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Mails the match as plain text.
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpNotifier {
+    pub fn new(transport: AsyncSmtpTransport<Tokio1Executor>, from: Mailbox) -> Self {
+        Self { transport, from }
+    }
+
+    /// Configured from `SMTP_HOST`, `SMTP_PORT` (587), `SMTP_USERNAME`, `SMTP_PASSWORD` and
+    /// `ALERT_EMAIL_FROM`. `None` when `SMTP_HOST` is not set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(host) = std::env::var("SMTP_HOST") else {
+            return Ok(None);
+        };
+        let port = match std::env::var("SMTP_PORT") {
+            Ok(port) => port.parse().with_context(|| format!("Invalid SMTP_PORT: {port}"))?,
+            Err(_) => 587,
+        };
+        let from = std::env::var("ALERT_EMAIL_FROM").context("ALERT_EMAIL_FROM must be set when SMTP_HOST is")?;
+
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)?.port(port);
+        if let (Ok(username), Ok(password)) = (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+            transport = transport.credentials(Credentials::new(username, password));
+        }
+        let from = from.parse().with_context(|| format!("Invalid ALERT_EMAIL_FROM: {from}"))?;
+        Ok(Some(Self::new(transport.build(), from)))
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for SmtpNotifier {
+    fn channel(&self) -> AlertChannel {
+        AlertChannel::Email
+    }
+
+    async fn send(&self, target: &str, alert: &AlertMatch) -> Result<()> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(target.parse().with_context(|| format!("Invalid mail address: {target}"))?)
+            .subject(&alert.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(alert.message.clone())?;
+        self.transport.send(email).await?;
+        Ok(())
+    }
+}
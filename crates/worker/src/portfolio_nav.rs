@@ -0,0 +1,85 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use domain::{LedgerTransaction, NavPoint, daily_nav};
+use store::{db::PgPool, models::portfolio_nav_db::PortfolioNavDB, repositories::{ledger_repository::LedgerRepo, market_data_repository::MarketDataRepo, portfolio_nav_repository::PortfolioNavRepo, portfolio_repository::PortfolioRepo}};
+use tracing::{info, warn};
+use uuid::Uuid;
+use crate::framework::IngestionJob;
+
+/// Extends the daily NAV series of every portfolio from its newest stored day, which is valued
+/// again in case its closes arrived late. Ledger writes drop the NAV from the day they touch,
+/// so back-dated or imported transactions are picked up from there on the next run.
+pub struct PortfolioNavJob {
+    db_pool: PgPool,
+}
+
+#[derive(Debug)]
+pub struct PortfolioNavResult {
+    timestamp: chrono::DateTime<Utc>,
+    series: Vec<(Uuid, Vec<NavPoint>)>,
+}
+
+impl PortfolioNavJob {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionJob for PortfolioNavJob {
+    type Output = PortfolioNavResult;
+
+    fn name(&self) -> &'static str { "portfolio_nav" }
+
+    async fn fetch_all(&self) -> Result<Self::Output> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        let today = Utc::now().date_naive();
+        let mut series = Vec::new();
+
+        for portfolio in PortfolioRepo::all(&mut conn).await? {
+            let transactions = match LedgerRepo::for_portfolio(&mut conn, portfolio.id).await?
+                .into_iter()
+                .map(LedgerTransaction::try_from)
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(transactions) => transactions,
+                Err(e) => {
+                    warn!("Skipping NAV of portfolio {}: {}", portfolio.id, e);
+                    continue;
+                }
+            };
+
+            let Some(first) = transactions.first() else { continue };
+            let latest = PortfolioNavRepo::latest_date(&mut conn, portfolio.id).await?;
+            let from = latest.map_or(first.timestamp.date(), |date| date.max(first.timestamp.date()));
+
+            let assets: Vec<String> = transactions.iter().map(|tx| tx.asset_symbol.clone()).collect();
+            // Look back a little so the first days can be forward-filled from an older close.
+            let prices = MarketDataRepo::price_history(&mut conn, &assets, from - Duration::days(7), today).await?;
+
+            match daily_nav(&transactions, &prices, from, today) {
+                Ok(points) => series.push((portfolio.id, points)),
+                Err(e) => warn!("Skipping NAV of portfolio {}: {}", portfolio.id, e),
+            }
+        }
+
+        Ok(PortfolioNavResult { timestamp: Utc::now(), series })
+    }
+
+    async fn store(&self, result: Self::Output) -> Result<()> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        for (portfolio_id, points) in result.series {
+            let rows: Vec<PortfolioNavDB> = points.iter().map(|point| PortfolioNavDB::from_point(portfolio_id, point)).collect();
+            if let Err(e) = PortfolioNavRepo::insert_many(&mut conn, &rows).await {
+                warn!("Failed to persist NAV of {}: {}", portfolio_id, e);
+            }
+        }
+
+        info!("Portfolio NAV persisted successfully at {}", result.timestamp);
+        Ok(())
+    }
+}
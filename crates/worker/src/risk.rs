@@ -0,0 +1,86 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use domain::{BTC_GENESIS, DerivedSeries, MarketSymbol, RISK_INPUTS, VOLATILITY_WINDOWS, risk_series};
+use store::{
+    db::PgPool,
+    models::market_metrics_db::MarketMetricDataDB,
+    repositories::market_metrics_repository::MarketMetricRepo,
+};
+use tracing::{info, warn};
+use crate::framework::IngestionJob;
+
+/// Days before the newest stored value that are computed again, so late or revised closes are
+/// picked up.
+const RECOMPUTE_DAYS: i64 = 7;
+
+/// Computes realized volatility, beta against the S&P 500 and NASDAQ and the ETH/BTC relative
+/// strength from the stored closes and stores them in `market_metrics`.
+pub struct RiskMetricsJob {
+    db_pool: PgPool,
+}
+
+#[derive(Debug)]
+pub struct RiskMetricsResult {
+    timestamp: chrono::DateTime<Utc>,
+    series: Vec<DerivedSeries>,
+}
+
+impl RiskMetricsJob {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl IngestionJob for RiskMetricsJob {
+    type Output = RiskMetricsResult;
+
+    fn name(&self) -> &'static str { "risk_metrics" }
+
+    async fn fetch_all(&self) -> Result<Self::Output> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        let today = Utc::now().date_naive();
+        // Only days from shortly before the newest stored value on, the first run fills the history.
+        let from = match MarketMetricRepo::latest_n(&mut conn, MarketSymbol::BtcVolatility30d, 1).await?.first() {
+            Some(latest) => latest.timestamp - Duration::days(RECOMPUTE_DAYS),
+            None => BTC_GENESIS,
+        };
+
+        // The longest volatility window of daily closes, and a margin for missing days.
+        let longest = VOLATILITY_WINDOWS.into_iter().max().unwrap_or_default() as i64;
+        let history_from = from - Duration::days(longest + 30);
+        let data = MarketMetricRepo::market_data_set(&mut conn, &RISK_INPUTS, history_from, today).await?;
+
+        Ok(RiskMetricsResult { timestamp: Utc::now(), series: risk_series(&data.at(today), from) })
+    }
+
+    async fn store(&self, result: Self::Output) -> Result<()> {
+        let mut conn = self.db_pool.get()
+            .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {e}"))?;
+
+        for series in &result.series {
+            for (date, value) in &series.values {
+                if let Err(e) = MarketMetricRepo::insert(
+                    &mut conn,
+                    &MarketMetricDataDB {
+                        name: series.symbol.as_str().into(),
+                        timestamp: *date,
+                        value: Some(*value),
+                        source: Some("computed".into()),
+                    },
+                ).await {
+                    warn!("Failed to persist {} on {}: {}", series.symbol.as_str(), date, e);
+                }
+            }
+            match series.values.last() {
+                Some((date, value)) => info!("{} computed up to {}: {:.4}", series.symbol.as_str(), date, value),
+                None => warn!("Not enough closes to compute {}", series.symbol.as_str()),
+            }
+        }
+
+        info!("Risk metrics persisted successfully at {}", result.timestamp);
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+#[cfg(test)]
+pub mod notifier_tests;
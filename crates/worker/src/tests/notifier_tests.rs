@@ -0,0 +1,125 @@
+use domain::AlertMatch;
+use lettre::{AsyncSmtpTransport, Tokio1Executor};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::notifier::{Notifier, SmtpNotifier, WebhookNotifier};
+
+fn alert() -> AlertMatch {
+    AlertMatch {
+        rule_id: Uuid::new_v4(),
+        rule_name: "fear".to_string(),
+        dedup_key: "2024-03-02".to_string(),
+        subject: "FEAR_GREED_INDEX below 15".to_string(),
+        message: "FEAR_GREED_INDEX was 12 on 2024-03-02".to_string(),
+    }
+}
+
+/// Accepts one HTTP request, answers with `status` and returns the request as received.
+async fn stand_in_http(status: &'static str) -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text[..end]
+                    .lines()
+                    .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    break;
+                }
+            }
+            if n == 0 {
+                break;
+            }
+        }
+        let response = format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+        socket.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    });
+    (url, server)
+}
+
+/// Accepts one SMTP session without TLS or auth and returns the message data.
+async fn stand_in_smtp() -> (u16, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = socket.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut data = String::new();
+
+        writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let command = line.to_uppercase();
+            if command.starts_with("EHLO") {
+                writer.write_all(b"250-localhost\r\n250 8BITMIME\r\n").await.unwrap();
+            } else if command.starts_with("DATA") {
+                writer.write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n").await.unwrap();
+                while let Some(line) = lines.next_line().await.unwrap() {
+                    if line == "." {
+                        break;
+                    }
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                writer.write_all(b"250 OK queued\r\n").await.unwrap();
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 Bye\r\n").await.unwrap();
+                break;
+            } else {
+                writer.write_all(b"250 OK\r\n").await.unwrap();
+            }
+        }
+        data
+    });
+    (port, server)
+}
+
+#[tokio::test]
+async fn test_webhook_posts_the_match_as_json() {
+    let (url, server) = stand_in_http("200 OK").await;
+    let alert = alert();
+
+    WebhookNotifier::new().send(&url, &alert).await.unwrap();
+
+    let request = server.await.unwrap();
+    assert!(request.starts_with("POST /hook HTTP/1.1"));
+    let body = &request[request.find("\r\n\r\n").unwrap() + 4..];
+    assert_eq!(serde_json::from_str::<AlertMatch>(body).unwrap(), alert);
+}
+
+#[tokio::test]
+async fn test_webhook_fails_on_error_status() {
+    let (url, server) = stand_in_http("500 Internal Server Error").await;
+
+    assert!(WebhookNotifier::new().send(&url, &alert()).await.is_err());
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_smtp_mails_subject_and_message() {
+    let (port, server) = stand_in_smtp().await;
+    let transport = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous("127.0.0.1").port(port).build();
+    let notifier = SmtpNotifier::new(transport, "alerts@example.com".parse().unwrap());
+
+    notifier.send("me@example.com", &alert()).await.unwrap();
+
+    let data = server.await.unwrap();
+    assert!(data.contains("To: me@example.com"));
+    assert!(data.contains("Subject: FEAR_GREED_INDEX below 15"));
+    assert!(data.contains("FEAR_GREED_INDEX was 12 on 2024-03-02"));
+    assert!(notifier.send("not an address", &alert()).await.is_err());
+}
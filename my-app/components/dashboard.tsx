@@ -31,6 +31,18 @@ interface DashboardData {
       values: Array<[string, number, string]>
     }>
   }
+  macro_regime: {
+    date: string
+    regime: string
+    formattedName: string
+    since: string
+    score: number | null
+    sub_scores: Array<{
+      name: string
+      formattedName: string
+      value: number
+    }>
+  } | null
 }
 
 export function Dashboard() {
@@ -155,6 +167,7 @@ export function Dashboard() {
             <div className="lg:col-span-2">
               <MacroMetricsGrid
                 data={data.macro_metrics.data}
+                regime={data.macro_regime}
                 onMetricClick={(name, formattedName) => setSelectedMetric({ name, formattedName })}
               />
             </div>
@@ -9,6 +9,13 @@ interface MacroMetricsGridProps {
     formattedName: string
     values: Array<[string, number, string]>
   }>
+  regime?: {
+    regime: string
+    formattedName: string
+    since: string
+    score: number | null
+    sub_scores: Array<{ name: string; formattedName: string; value: number }>
+  } | null
   onMetricClick: (name: string, formattedName: string) => void
 }
 
@@ -21,11 +28,36 @@ const iconMap: Record<string, any> = {
   default: BarChart3,
 }
 
-export function MacroMetricsGrid({ data, onMetricClick }: MacroMetricsGridProps) {
+const regimeColor: Record<string, string> = {
+  RISK_ON: "text-success",
+  EASING: "text-primary",
+  TIGHTENING: "text-warning",
+  RISK_OFF: "text-danger",
+}
+
+export function MacroMetricsGrid({ data, regime, onMetricClick }: MacroMetricsGridProps) {
   return (
     <div className="glass rounded-xl p-6 space-y-4 animate-in fade-in slide-in-from-bottom duration-700 delay-100">
       <h3 className="text-sm text-muted-foreground">Economic Indicators</h3>
 
+      {regime && (
+        <div className="rounded-lg p-4 space-y-2">
+          <div className="flex items-baseline justify-between">
+            <p className={`text-lg font-bold ${regimeColor[regime.regime] || "text-foreground"}`}>
+              {regime.formattedName}
+            </p>
+            <p className="text-xs text-muted-foreground/70">since {regime.since}</p>
+          </div>
+          <div className="flex flex-wrap gap-x-4 gap-y-1">
+            {regime.sub_scores.map((score) => (
+              <p key={score.name} className="text-xs text-muted-foreground">
+                {score.formattedName}: <span className="text-foreground">{score.value.toFixed(2)}</span>
+              </p>
+            ))}
+          </div>
+        </div>
+      )}
+
       <div className="grid grid-cols-2 md:grid-cols-3 gap-4">
         {data.slice(0, 6).map((metric) => {
           const latest = metric.values[metric.values.length - 1]